@@ -0,0 +1,64 @@
+use pelican::graphics::{Rectangle, Transform};
+use pelican::ui::{View, Window, Color, Button, AnimationCurve};
+use pelican::ui::{ApplicationMain, ApplicationDelegate};
+use pelican::ui::{ViewController, ViewControllerBehavior};
+use pelican::ui::animation::AnimationRepeat;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// A box that slides across and changes colour when the button is pressed,
+/// and a dot that pulses forever.
+struct ExampleViewController {}
+impl ViewControllerBehavior for ExampleViewController {
+    fn view_did_load(&self, view: View) {
+        let shape = View::new(Rectangle::new(40, 100, 120, 120));
+        shape.set_background_color(Color::new(2, 117, 227, 255));
+        view.add_subview(shape.clone());
+
+        let dot = View::new(Rectangle::new(520, 30, 40, 40));
+        dot.set_background_color(Color::red());
+        view.add_subview(dot.clone());
+
+        View::animate(Duration::from_millis(600), AnimationCurve::EaseInOut, || {
+            dot.set_alpha(0.2);
+            dot.set_transform(Transform::scale(0.5, 0.5));
+        })
+        .with_repeat(AnimationRepeat::Forever)
+        .with_autoreverse(true);
+
+        let moved = Rc::new(Cell::new(false));
+        let button = Button::new(Rectangle::new(40, 30, 120, 40), "Move", move || {
+            moved.set(!moved.get());
+
+            let (frame, color) = if moved.get() {
+                (Rectangle::new(380, 220, 180, 140), Color::new(227, 117, 2, 255))
+            } else {
+                (Rectangle::new(40, 100, 120, 120), Color::new(2, 117, 227, 255))
+            };
+
+            View::animate(Duration::from_millis(400), AnimationCurve::EaseOut, || {
+                shape.set_frame(frame);
+                shape.set_background_color(color);
+            })
+            .with_completion(|finished| println!("Finished: {}", finished));
+        });
+        view.add_subview(button);
+    }
+}
+
+struct AppDelegate {}
+impl ApplicationDelegate for AppDelegate {
+    fn application_did_finish_launching(&self) {
+        let frame = Rectangle::new(200, 200, 600, 400);
+        let view_controller = ViewController::new(ExampleViewController {});
+        let window = Window::new("Animation", frame, view_controller);
+        window.make_key_and_visible();
+    }
+}
+
+pub fn main() -> Result<(), String> {
+    let application_main = ApplicationMain::new(AppDelegate {});
+    application_main.launch();
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use pelican::graphics::Rectangle;
+use pelican::ui::{View, Window, Color, Label, Button, AutoresizingMask};
+use pelican::ui::{ApplicationMain, ApplicationDelegate};
+use pelican::ui::{ViewController, ViewControllerBehavior};
+
+/// A frame-based screen that follows the window as it's resized: a header
+/// across the top, a content area filling the rest and a button kept in the
+/// bottom right corner.
+struct ExampleViewController {}
+impl ViewControllerBehavior for ExampleViewController {
+    fn view_did_load(&self, view: View) {
+        let header = View::new(Rectangle::new(0, 0, 600, 48));
+        header.set_background_color(Color::new(2, 117, 227, 255));
+        header.set_autoresizing_mask(AutoresizingMask::FLEXIBLE_WIDTH);
+        view.add_subview(header.clone());
+
+        let title = Label::new(Rectangle::new(16, 14, 200, 20), String::from("Autoresizing"));
+        title.set_text_color(Color::white());
+        header.add_subview(title);
+
+        let content = View::new(Rectangle::new(20, 68, 560, 312));
+        content.set_background_color(Color::gray());
+        content.set_autoresizing_mask(AutoresizingMask::FLEXIBLE_SIZE);
+        view.add_subview(content.clone());
+
+        let button = Button::new(Rectangle::new(420, 262, 120, 30), "Corner", || println!("Pressed"));
+        button.set_autoresizing_mask(AutoresizingMask::FLEXIBLE_LEFT_MARGIN | AutoresizingMask::FLEXIBLE_TOP_MARGIN);
+        content.add_subview(button);
+    }
+}
+
+struct AppDelegate {}
+impl ApplicationDelegate for AppDelegate {
+    fn application_did_finish_launching(&self) {
+        let frame = Rectangle::new(200, 200, 600, 400);
+        let view_controller = ViewController::new(ExampleViewController {});
+        let window = Window::new("Autoresizing", frame, view_controller);
+        window.set_resizable(true);
+        window.make_key_and_visible();
+    }
+}
+
+pub fn main() -> Result<(), String> {
+    let application_main = ApplicationMain::new(AppDelegate {});
+    application_main.launch();
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use pelican::graphics::Rectangle;
+use pelican::ui::{View, Window, Color, Label, Button, Constraint};
+use pelican::ui::{ApplicationMain, ApplicationDelegate};
+use pelican::ui::{ViewController, ViewControllerBehavior};
+use pelican::ui::layout::Priority;
+
+/// A header, a sidebar and a content area that follow the window as it's
+/// resized, with a button sized by its text.
+struct ExampleViewController {}
+impl ViewControllerBehavior for ExampleViewController {
+    fn view_did_load(&self, view: View) {
+        let header = View::new(Rectangle::new(0, 0, 0, 0));
+        header.set_background_color(Color::new(2, 117, 227, 255));
+        view.add_subview(header.clone());
+
+        let title = Label::new(Rectangle::new(0, 0, 0, 0), String::from("Constraints"));
+        title.set_text_color(Color::white());
+        header.add_subview(title.clone());
+
+        let sidebar = View::new(Rectangle::new(0, 0, 0, 0));
+        sidebar.set_background_color(Color::gray());
+        view.add_subview(sidebar.clone());
+
+        let content = View::new(Rectangle::new(0, 0, 0, 0));
+        view.add_subview(content.clone());
+
+        let button = Button::new(Rectangle::new(0, 0, 0, 0), "Centered", || println!("Pressed"));
+        content.add_subview(button.clone());
+
+        Constraint::activate_all(&[
+            header.leading_anchor().constraint_equal_to(&view.leading_anchor()),
+            header.trailing_anchor().constraint_equal_to(&view.trailing_anchor()),
+            header.top_anchor().constraint_equal_to(&view.top_anchor()),
+            header.height_anchor().constraint_equal_to_constant(48.0),
+
+            title.leading_anchor().constraint_equal_to(&header.leading_anchor()).with_constant(16.0),
+            title.center_y_anchor().constraint_equal_to(&header.center_y_anchor()),
+
+            sidebar.leading_anchor().constraint_equal_to(&view.leading_anchor()),
+            sidebar.top_anchor().constraint_equal_to(&header.bottom_anchor()),
+            sidebar.bottom_anchor().constraint_equal_to(&view.bottom_anchor()),
+            sidebar.width_anchor().constraint_equal_to(&view.width_anchor()).with_multiplier(0.25),
+            sidebar.width_anchor().constraint_greater_than_or_equal_to_constant(120.0),
+            sidebar.width_anchor().constraint_less_than_or_equal_to_constant(200.0).with_priority(Priority::HIGH),
+
+            content.leading_anchor().constraint_equal_to(&sidebar.trailing_anchor()),
+            content.trailing_anchor().constraint_equal_to(&view.trailing_anchor()),
+            content.top_anchor().constraint_equal_to(&header.bottom_anchor()),
+            content.bottom_anchor().constraint_equal_to(&view.bottom_anchor()),
+
+            button.center_x_anchor().constraint_equal_to(&content.center_x_anchor()),
+            button.center_y_anchor().constraint_equal_to(&content.center_y_anchor())
+        ]);
+    }
+}
+
+struct AppDelegate {}
+impl ApplicationDelegate for AppDelegate {
+    fn application_did_finish_launching(&self) {
+        let frame = Rectangle::new(200, 200, 600, 400);
+        let view_controller = ViewController::new(ExampleViewController {});
+        let window = Window::new("Constraints", frame, view_controller);
+        window.set_resizable(true);
+        window.make_key_and_visible();
+    }
+}
+
+pub fn main() -> Result<(), String> {
+    let application_main = ApplicationMain::new(AppDelegate {});
+    application_main.launch();
+    Ok(())
+}
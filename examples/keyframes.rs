@@ -0,0 +1,59 @@
+use pelican::graphics::Rectangle;
+use pelican::ui::{View, Window, Color, Label, Animation, AnimationCurve};
+use pelican::ui::{ApplicationMain, ApplicationDelegate};
+use pelican::ui::{ViewController, ViewControllerBehavior};
+use pelican::ui::animation::{AnimationRepeat, Keyframe};
+use std::cell::RefCell;
+use std::time::Duration;
+
+/// A progress bar filling up in fits and starts, changing colour as it goes,
+/// driven by a value animation rather than animating the views directly.
+struct ExampleViewController {
+    // Kept so the animation carries on playing.
+    progress: RefCell<Option<Animation<f32>>>
+}
+impl ViewControllerBehavior for ExampleViewController {
+    fn view_did_load(&self, view: View) {
+        let track = View::new(Rectangle::new(50, 180, 500, 20));
+        track.set_background_color(Color::gray());
+        view.add_subview(track.clone());
+
+        let fill = View::new(Rectangle::new(0, 0, 0, 20));
+        track.add_subview(fill.clone());
+
+        let label = Label::new(Rectangle::new(50, 150, 200, 20), String::from("0%"));
+        view.add_subview(label.clone());
+
+        let progress = Animation::new_keyframes(Duration::from_secs(4), vec![
+            Keyframe::new(0.0, 0.0),
+            Keyframe::new(0.3, 0.4).with_curve(AnimationCurve::EaseOut),
+            Keyframe::new(0.5, 0.45),
+            Keyframe::new(1.0, 1.0).with_curve(AnimationCurve::EaseInOut)
+        ])
+        .with_repeat(AnimationRepeat::Forever)
+        .with_tick(move |progress: &f32| {
+            fill.set_frame(Rectangle::new(0, 0, (500.0 * progress).round() as u32, 20));
+            fill.set_background_color(Color::new((227.0 * (1.0 - progress)) as u8, (200.0 * progress) as u8, 80, 255));
+            label.set_text(format!("{}%", (progress * 100.0).round()));
+        });
+
+        progress.start();
+        self.progress.replace(Some(progress));
+    }
+}
+
+struct AppDelegate {}
+impl ApplicationDelegate for AppDelegate {
+    fn application_did_finish_launching(&self) {
+        let frame = Rectangle::new(200, 200, 600, 400);
+        let view_controller = ViewController::new(ExampleViewController { progress: RefCell::new(None) });
+        let window = Window::new("Keyframes", frame, view_controller);
+        window.make_key_and_visible();
+    }
+}
+
+pub fn main() -> Result<(), String> {
+    let application_main = ApplicationMain::new(AppDelegate {});
+    application_main.launch();
+    Ok(())
+}
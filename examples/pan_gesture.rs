@@ -3,7 +3,7 @@ use pelican::ui::{View, Window, Color};
 use pelican::ui::{ApplicationMain, ApplicationDelegate};
 use pelican::ui::{ViewController, ViewControllerBehavior};
 use pelican::ui::gesture::pan_recognizer::PanRecognizer;
-use pelican::ui::gesture::pan_recognizer::PanState;
+use pelican::ui::gesture::recognizer::{Recognizer, RecognizerState};
 use std::cell::RefCell;
 use pelican::graphics::Point;
 use std::rc::Rc;
@@ -47,7 +47,7 @@ impl ViewControllerBehavior for ExampleViewController {
             // Get the changes in the X and Y directions relative to
             // the superview's coordinate space.
             let translation = gesture_recognizer.translation_in(&superview);
-            if gesture_recognizer.state() == PanState::Began {
+            if gesture_recognizer.state() == RecognizerState::Began {
                 // Save the view's original position.
                 state.initial_center = piece.frame().center();
             }
@@ -55,7 +55,7 @@ impl ViewControllerBehavior for ExampleViewController {
             let initial_center = state.initial_center.clone();
 
             // Update the position for the .began, .changed, and .ended states
-            if gesture_recognizer.state() != PanState::Cancelled {
+            if gesture_recognizer.state() != RecognizerState::Cancelled {
                 // println!("updating position, translation: {:?}", translation);
                 // Add the X and Y translation to the view's original position.
                 let new_center = Point::new(initial_center.x + translation.x, initial_center.y + translation.y);
@@ -0,0 +1,56 @@
+use pelican::graphics::{EdgeInsets, Rectangle};
+use pelican::ui::{View, Window, Color, Label, Button, StackView};
+use pelican::ui::{ApplicationMain, ApplicationDelegate};
+use pelican::ui::{ViewController, ViewControllerBehavior};
+use pelican::ui::layout::Axis;
+use pelican::ui::view::stack_view::{StackAlignment, StackDistribution};
+
+/// A column of rows, each a label, a spacer and a button. Pressing a button
+/// hides its label, and the row closes up around it.
+struct ExampleViewController {}
+impl ViewControllerBehavior for ExampleViewController {
+    fn view_did_load(&self, view: View) {
+        let column = StackView::new(Rectangle::new(0, 0, 600, 400), Axis::Vertical);
+        column.set_padding(EdgeInsets::uniform(20));
+        column.set_spacing(10);
+        column.set_distribution(StackDistribution::FillEqually);
+        view.add_subview(column.clone());
+
+        for index in 0..5 {
+            let row = StackView::new(Rectangle::new(0, 0, 0, 0), Axis::Horizontal);
+            row.set_alignment(StackAlignment::Center);
+            row.set_spacing(10);
+            row.set_background_color(Color::gray());
+
+            let label = Label::new(Rectangle::new(0, 0, 0, 0), format!("Row {}", index + 1));
+            let spacer = View::new(Rectangle::new(0, 0, 0, 0));
+            spacer.set_background_color(Color::clear());
+
+            let hidden_label = label.clone();
+            let button = Button::new(Rectangle::new(0, 0, 0, 0), "Toggle label", move || {
+                hidden_label.set_hidden(!hidden_label.is_hidden());
+            });
+
+            row.add_arranged_subview(label);
+            row.add_arranged_subview(spacer);
+            row.add_arranged_subview(button);
+            column.add_arranged_subview(row);
+        }
+    }
+}
+
+struct AppDelegate {}
+impl ApplicationDelegate for AppDelegate {
+    fn application_did_finish_launching(&self) {
+        let frame = Rectangle::new(200, 200, 600, 400);
+        let view_controller = ViewController::new(ExampleViewController {});
+        let window = Window::new("Stack views", frame, view_controller);
+        window.make_key_and_visible();
+    }
+}
+
+pub fn main() -> Result<(), String> {
+    let application_main = ApplicationMain::new(AppDelegate {});
+    application_main.launch();
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use pelican::graphics::Rectangle;
+use pelican::ui::{View, Window, Color, Label};
+use pelican::ui::{ApplicationMain, ApplicationDelegate};
+use pelican::ui::{ViewController, ViewControllerBehavior};
+use pelican::ui::{TableView, TableViewCell};
+use pelican::ui::view::table_view::{IndexPath, TableViewDataSource, TableViewDelegate};
+use pelican::text::VerticalAlignment;
+
+static LABEL_TAG: u32 = 1;
+
+/// 100,000 rows, of which only the dozen or so on screen have cells.
+struct Rows {}
+impl TableViewDataSource for Rows {
+    fn number_of_sections(&self, _table_view: &TableView) -> usize {
+        100
+    }
+
+    fn number_of_rows(&self, _table_view: &TableView, _section: usize) -> usize {
+        1000
+    }
+
+    fn cell_for_row(&self, table_view: &TableView, index_path: IndexPath) -> TableViewCell {
+        let cell = table_view.dequeue_reusable_cell("row").unwrap_or_else(|| {
+            let cell = TableViewCell::new("row");
+            let label = Label::new(Rectangle::new(10, 0, 300, 44), String::new());
+            label.set_vertical_alignment(VerticalAlignment::Middle);
+            label.set_tag(LABEL_TAG);
+            cell.content_view().add_subview(label);
+            cell
+        });
+
+        let label = Label::from_view(cell.view_with_tag(LABEL_TAG).expect("cell missing label"));
+        label.set_text(format!("Row {} of section {}", index_path.row + 1, index_path.section + 1));
+
+        cell
+    }
+
+    fn view_for_header(&self, _table_view: &TableView, section: usize) -> Option<View> {
+        let header = Label::new(Rectangle::new(0, 0, 0, 0), format!("  Section {}", section + 1));
+        header.set_vertical_alignment(VerticalAlignment::Middle);
+        header.set_background_color(Color::gray());
+        Some(header.into())
+    }
+}
+
+struct Selection {}
+impl TableViewDelegate for Selection {
+    fn did_select_row(&self, _table_view: &TableView, index_path: IndexPath) {
+        println!("Selected row {} of section {}", index_path.row + 1, index_path.section + 1);
+    }
+}
+
+struct ExampleViewController {}
+impl ViewControllerBehavior for ExampleViewController {
+    fn view_did_load(&self, view: View) {
+        let table_view = TableView::new(Rectangle::new(0, 0, 600, 400));
+        table_view.set_section_header_height(28);
+        table_view.set_data_source(Rows {});
+        table_view.set_delegate(Selection {});
+        view.add_subview(table_view);
+    }
+}
+
+struct AppDelegate {}
+impl ApplicationDelegate for AppDelegate {
+    fn application_did_finish_launching(&self) {
+        let frame = Rectangle::new(200, 200, 600, 400);
+        let view_controller = ViewController::new(ExampleViewController {});
+        let window = Window::new("Table (100,000 rows)", frame, view_controller);
+        window.make_key_and_visible();
+    }
+}
+
+pub fn main() -> Result<(), String> {
+    let application_main = ApplicationMain::new(AppDelegate {});
+    application_main.launch();
+    Ok(())
+}
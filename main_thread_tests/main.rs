@@ -6,6 +6,9 @@ use pelican::graphics::Size;
 use pelican::graphics::Image;
 use pelican::ui::ImageView;
 use pelican::ui::{ViewController, ViewControllerBehavior};
+use pelican::ui::WindowDelegate;
+use std::cell::Cell;
+use std::rc::Rc;
 use pelican::ui::run_loop::RunLoop;
 use pelican::ui::timer::Timer;
 use pelican::ui::application::Application;
@@ -14,6 +17,21 @@ use pelican::platform::thread;
 struct ExampleViewController {}
 impl ViewControllerBehavior for ExampleViewController {}
 
+struct ExampleWindowDelegate {
+    should_close: bool,
+    will_close_called: Rc<Cell<bool>>
+}
+
+impl WindowDelegate for ExampleWindowDelegate {
+    fn window_should_close(&self, _window: &Window) -> bool {
+        self.should_close
+    }
+
+    fn window_will_close(&self, _window: &Window) {
+        self.will_close_called.set(true);
+    }
+}
+
 pub fn main() -> Result<(), String> {
     println!("custom test: main thread");
     main_thread();
@@ -33,6 +51,15 @@ pub fn main() -> Result<(), String> {
     println!("custom test: application");
     application();
 
+    println!("custom test: window_management");
+    window_management();
+
+    println!("custom test: key_window");
+    key_window();
+
+    println!("custom test: window_close");
+    window_close();
+
     Ok(())
 }
 
@@ -187,6 +214,129 @@ fn application() {
     assert_eq!(application.windows().len(), start_window_count + 1);
 }
 
+fn window_management() {
+    let frame = Rectangle {
+        origin: Point { x: 10, y: 10 },
+        size: Size { width: 50, height: 50 }
+    };
+
+    let view_controller = ViewController::new(ExampleViewController {});
+    let window = Window::new("test", frame, view_controller);
+
+    assert_eq!(window.title(), "test");
+    window.set_title("renamed");
+    assert_eq!(window.title(), "renamed");
+
+    window.set_size(Size::new(80, 60));
+    assert_eq!(window.size(), Size::new(80, 60));
+    assert_eq!(window.frame().size(), &Size::new(80, 60));
+    assert_eq!(window.bounds().size(), &Size::new(80, 60));
+    assert_eq!(window.context().size(), Size::new(80, 60));
+
+    window.set_minimum_size(Size::new(100, 100));
+    assert_eq!(window.minimum_size(), Size::new(100, 100));
+    assert_eq!(window.frame().size(), &window.context().size());
+
+    window.set_resizable(true);
+    assert!(window.is_resizable());
+    window.set_resizable(false);
+    assert!(!window.is_resizable());
+
+    window.set_borderless(true);
+    assert!(window.is_borderless());
+    window.set_borderless(false);
+    assert!(!window.is_borderless());
+}
+
+struct KeyWindowDelegate {
+    became_key: Rc<Cell<usize>>,
+    resigned_key: Rc<Cell<usize>>
+}
+
+impl WindowDelegate for KeyWindowDelegate {
+    fn window_did_become_key(&self, _window: &Window) {
+        self.became_key.set(self.became_key.get() + 1);
+    }
+
+    fn window_did_resign_key(&self, _window: &Window) {
+        self.resigned_key.set(self.resigned_key.get() + 1);
+    }
+}
+
+fn key_window() {
+    let frame = Rectangle {
+        origin: Point { x: 10, y: 10 },
+        size: Size { width: 50, height: 50 }
+    };
+
+    let first = Window::new("first", frame.clone(), ViewController::new(ExampleViewController {}));
+    let second = Window::new("second", frame, ViewController::new(ExampleViewController {}));
+
+    let became_key = Rc::new(Cell::new(0));
+    let resigned_key = Rc::new(Cell::new(0));
+    first.set_delegate(KeyWindowDelegate {
+        became_key: became_key.clone(),
+        resigned_key: resigned_key.clone()
+    });
+
+    first.make_key_window();
+    assert!(first.is_key_window());
+    assert!(!second.is_key_window());
+    assert_eq!(became_key.get(), 1);
+
+    // Making it key again doesn't notify a second time.
+    first.make_key_window();
+    assert_eq!(became_key.get(), 1);
+
+    second.make_key_window();
+    assert!(!first.is_key_window());
+    assert!(second.is_key_window());
+    assert_eq!(resigned_key.get(), 1);
+
+    let child = View::new(Rectangle::new(0, 0, 10, 10));
+    second.add_subview(child.clone());
+    assert_eq!(child.window(), Some(second.clone()));
+}
+
+fn window_close() {
+    let frame = Rectangle {
+        origin: Point { x: 10, y: 10 },
+        size: Size { width: 50, height: 50 }
+    };
+
+    {
+        let mut application = Application::borrow_mut();
+        application.set_terminates_after_last_window_closed(false);
+    }
+
+    let start_window_count = Application::borrow().windows().len();
+
+    let view_controller = ViewController::new(ExampleViewController {});
+    let window = Window::new("test", frame, view_controller);
+    let will_close_called = Rc::new(Cell::new(false));
+
+    // The delegate can refuse to close the window.
+    window.set_delegate(ExampleWindowDelegate {
+        should_close: false,
+        will_close_called: will_close_called.clone()
+    });
+
+    assert!(!window.perform_close());
+    assert!(!will_close_called.get());
+    assert_eq!(Application::borrow().windows().len(), start_window_count + 1);
+
+    window.set_delegate(ExampleWindowDelegate {
+        should_close: true,
+        will_close_called: will_close_called.clone()
+    });
+
+    assert!(window.perform_close());
+    assert!(will_close_called.get());
+    assert!(window.is_hidden());
+    assert_eq!(Application::borrow().windows().len(), start_window_count);
+    assert!(!Application::borrow().windows().contains(&window));
+}
+
 fn example_resources_directory() -> std::path::PathBuf {
     let mut path = std::env::current_exe().unwrap();
     path.pop();
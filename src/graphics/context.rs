@@ -10,9 +10,12 @@ use sdl2::video::WindowContext;
 use sdl2::render::TextureCreator;
 use sdl2::render::Canvas;
 use sdl2::pixels::Color;
+use sdl2::video::FullscreenType;
+use sdl2::video::WindowPos;
+use sdl2::sys::SDL_WindowFlags;
 
 use std::rc::Rc;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::convert::TryInto;
 
 /// `Context` for a graphics render target. E.g. a window.
@@ -30,16 +33,18 @@ struct ContextInner {
     ///
     /// As opposed to the actual pixel size: `pixel_size`. This would be
     /// different to `pixel_size` if the display has has higher DPI.
-    size: Size<u32>,
+    ///
+    /// Refreshed by `update_size` whenever the window is resized.
+    size: RefCell<Size<u32>>,
 
     /// The actual pixel size of the drawable canvas.
     ///
     /// As opposed to the point size: `size`.
-    pixel_size: Size<u32>,
+    pixel_size: RefCell<Size<u32>>,
 
     /// The render scale. This would be different if using a higher density
     /// display.
-    render_scale: f32,
+    render_scale: Cell<f32>,
 
     /// Internal SDL canvas
     canvas: Rc<RefCell<Canvas<Window>>>,
@@ -84,10 +89,10 @@ impl Context {
         Context {
             inner: Rc::new(ContextInner {
                 id: id,
-                size: size,
-                render_scale: render_scale,
+                size: RefCell::new(size),
+                render_scale: Cell::new(render_scale),
                 canvas: Rc::new(RefCell::new(canvas)),
-                pixel_size: pixel_size,
+                pixel_size: RefCell::new(pixel_size),
                 texture_creator: texture_creator
             })
         }
@@ -102,15 +107,211 @@ impl Context {
     }
 
     pub fn render_scale(&self) -> f32 {
-        self.inner.render_scale
+        self.inner.render_scale.get()
     }
 
     pub fn size(&self) -> Size<u32> {
-        self.inner.size.clone()
+        self.inner.size.borrow().clone()
     }
 
     pub fn pixel_size(&self) -> Size<u32> {
-        self.inner.pixel_size.clone()
+        self.inner.pixel_size.borrow().clone()
+    }
+
+    /// Re-reads the point size, pixel size and render scale from the
+    /// underlying window.
+    ///
+    /// Called after the size is changed, either through `set_size` or by the
+    /// OS (e.g. the user dragging the window edge).
+    pub(crate) fn update_size(&self) {
+        let canvas = self.inner.canvas.borrow();
+        let (width, height) = canvas.window().size();
+        let (pixel_width, pixel_height) = canvas.output_size().expect("failed to get canvas output size");
+
+        if width > 0 {
+            self.inner.render_scale.set(pixel_width as f32 / width as f32);
+        }
+
+        self.inner.size.replace(Size { width, height });
+        self.inner.pixel_size.replace(Size { width: pixel_width, height: pixel_height });
+    }
+
+    pub fn title(&self) -> String {
+        let canvas = self.inner.canvas.borrow();
+        String::from(canvas.window().title())
+    }
+
+    pub fn set_title(&self, title: &str) {
+        let mut canvas = self.inner.canvas.borrow_mut();
+        canvas.window_mut().set_title(title).expect("window title must not contain a nul byte");
+    }
+
+    /// The position of the window on screen.
+    pub fn position(&self) -> Point<i32> {
+        let canvas = self.inner.canvas.borrow();
+        let (x, y) = canvas.window().position();
+        Point { x, y }
+    }
+
+    pub fn set_position(&self, position: Point<i32>) {
+        let mut canvas = self.inner.canvas.borrow_mut();
+        canvas.window_mut().set_position(
+            WindowPos::Positioned(position.x),
+            WindowPos::Positioned(position.y)
+        );
+    }
+
+    /// Resizes the window. The size is clamped by the OS to the
+    /// `minimum_size` and `maximum_size`, so `size()` may not match the given
+    /// size afterwards.
+    pub fn set_size(&self, size: Size<u32>) {
+        {
+            let mut canvas = self.inner.canvas.borrow_mut();
+            canvas.window_mut().set_size(size.width, size.height).expect("failed to set window size");
+        }
+
+        self.update_size();
+    }
+
+    pub fn minimum_size(&self) -> Size<u32> {
+        let canvas = self.inner.canvas.borrow();
+        let (width, height) = canvas.window().minimum_size();
+        Size { width, height }
+    }
+
+    pub fn set_minimum_size(&self, size: Size<u32>) {
+        let mut canvas = self.inner.canvas.borrow_mut();
+        canvas.window_mut().set_minimum_size(size.width, size.height).expect("failed to set window minimum size");
+    }
+
+    /// The maximum size of the window. A dimension of `0` means there is no
+    /// maximum for that dimension.
+    pub fn maximum_size(&self) -> Size<u32> {
+        let canvas = self.inner.canvas.borrow();
+        let (width, height) = canvas.window().maximum_size();
+        Size { width, height }
+    }
+
+    pub fn set_maximum_size(&self, size: Size<u32>) {
+        let mut canvas = self.inner.canvas.borrow_mut();
+        canvas.window_mut().set_maximum_size(size.width, size.height).expect("failed to set window maximum size");
+    }
+
+    pub fn is_resizable(&self) -> bool {
+        self.has_window_flag(SDL_WindowFlags::SDL_WINDOW_RESIZABLE)
+    }
+
+    pub fn set_resizable(&self, resizable: bool) {
+        let mut canvas = self.inner.canvas.borrow_mut();
+        canvas.window_mut().set_resizable(resizable);
+    }
+
+    pub fn fullscreen_mode(&self) -> FullscreenMode {
+        let canvas = self.inner.canvas.borrow();
+        match canvas.window().fullscreen_state() {
+            FullscreenType::Off => FullscreenMode::Off,
+            FullscreenType::Desktop => FullscreenMode::Desktop,
+            FullscreenType::True => FullscreenMode::Exclusive
+        }
+    }
+
+    /// Change the fullscreen mode of the window.
+    ///
+    /// Not every platform supports every mode; if the change is refused, a
+    /// warning is printed and the window stays in its current mode.
+    pub fn set_fullscreen_mode(&self, mode: FullscreenMode) {
+        let fullscreen_type = match mode {
+            FullscreenMode::Off => FullscreenType::Off,
+            FullscreenMode::Desktop => FullscreenType::Desktop,
+            FullscreenMode::Exclusive => FullscreenType::True
+        };
+
+        {
+            let mut canvas = self.inner.canvas.borrow_mut();
+            if let Err(error) = canvas.window_mut().set_fullscreen(fullscreen_type) {
+                println!("Warning: failed to change fullscreen mode: {}", error);
+            }
+        }
+
+        self.update_size();
+    }
+
+    /// Hides the OS window. Used when the window is closed, as the
+    /// underlying window lives for as long as the `Context` does.
+    pub(crate) fn hide(&self) {
+        let mut canvas = self.inner.canvas.borrow_mut();
+        canvas.window_mut().hide();
+    }
+
+    pub fn minimize(&self) {
+        let mut canvas = self.inner.canvas.borrow_mut();
+        canvas.window_mut().minimize();
+    }
+
+    pub fn maximize(&self) {
+        let mut canvas = self.inner.canvas.borrow_mut();
+        canvas.window_mut().maximize();
+    }
+
+    /// Restore the size and position of a minimized or maximized window.
+    pub fn restore(&self) {
+        let mut canvas = self.inner.canvas.borrow_mut();
+        canvas.window_mut().restore();
+    }
+
+    pub fn is_minimized(&self) -> bool {
+        let canvas = self.inner.canvas.borrow();
+        canvas.window().is_minimized()
+    }
+
+    pub fn is_maximized(&self) -> bool {
+        let canvas = self.inner.canvas.borrow();
+        canvas.window().is_maximized()
+    }
+
+    pub fn is_borderless(&self) -> bool {
+        self.has_window_flag(SDL_WindowFlags::SDL_WINDOW_BORDERLESS)
+    }
+
+    pub fn set_borderless(&self, borderless: bool) {
+        let mut canvas = self.inner.canvas.borrow_mut();
+        canvas.window_mut().set_bordered(!borderless);
+    }
+
+    pub fn is_always_on_top(&self) -> bool {
+        let canvas = self.inner.canvas.borrow();
+        canvas.window().is_always_on_top()
+    }
+
+    pub fn set_always_on_top(&self, always_on_top: bool) {
+        let mut canvas = self.inner.canvas.borrow_mut();
+        canvas.window_mut().set_always_on_top(always_on_top);
+    }
+
+    /// The opacity of the whole window, from `0.0` (fully transparent) to
+    /// `1.0` (fully opaque).
+    ///
+    /// Platforms without support for window opacity always return `1.0`.
+    pub fn opacity(&self) -> f32 {
+        let canvas = self.inner.canvas.borrow();
+        canvas.window().opacity().unwrap_or(1.0)
+    }
+
+    /// Set the opacity of the whole window. The value is clamped between
+    /// `0.0` and `1.0`.
+    ///
+    /// If the platform doesn't support window opacity, a warning is printed
+    /// and nothing changes.
+    pub fn set_opacity(&self, opacity: f32) {
+        let mut canvas = self.inner.canvas.borrow_mut();
+        if let Err(error) = canvas.window_mut().set_opacity(opacity) {
+            println!("Warning: failed to set window opacity: {}", error);
+        }
+    }
+
+    fn has_window_flag(&self, flag: SDL_WindowFlags) -> bool {
+        let canvas = self.inner.canvas.borrow();
+        canvas.window().window_flags() & flag as u32 != 0
     }
 
     pub fn draw(&self) {
@@ -200,6 +401,20 @@ impl Context {
     }
 }
 
+/// How a window occupies the screen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FullscreenMode {
+    /// A regular window.
+    Off,
+
+    /// Borderless, covering the whole display at the desktop resolution.
+    Desktop,
+
+    /// Takes exclusive control of the display, changing the display mode to
+    /// match the window size.
+    Exclusive
+}
+
 impl Clone for Context {
     fn clone(&self) -> Self {
         Context {
@@ -0,0 +1,61 @@
+/// Distances inset from each edge of a rectangle. E.g. the space around the
+/// content of a `ScrollView`.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct EdgeInsets {
+    pub top: i32,
+    pub left: i32,
+    pub bottom: i32,
+    pub right: i32
+}
+
+impl EdgeInsets {
+    pub fn new(top: i32, left: i32, bottom: i32, right: i32) -> EdgeInsets {
+        EdgeInsets {
+            top,
+            left,
+            bottom,
+            right
+        }
+    }
+
+    /// The same inset from every edge.
+    pub fn uniform(inset: i32) -> EdgeInsets {
+        EdgeInsets::new(inset, inset, inset, inset)
+    }
+
+    pub fn zero() -> EdgeInsets {
+        EdgeInsets::uniform(0)
+    }
+
+    /// The left and right insets together.
+    pub fn horizontal(&self) -> i32 {
+        self.left + self.right
+    }
+
+    /// The top and bottom insets together.
+    pub fn vertical(&self) -> i32 {
+        self.top + self.bottom
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let insets = EdgeInsets::new(1, 2, 3, 4);
+        assert_eq!(insets.top, 1);
+        assert_eq!(insets.left, 2);
+        assert_eq!(insets.bottom, 3);
+        assert_eq!(insets.right, 4);
+        assert_eq!(insets.horizontal(), 6);
+        assert_eq!(insets.vertical(), 4);
+    }
+
+    #[test]
+    fn test_zero() {
+        assert_eq!(EdgeInsets::zero(), EdgeInsets::default());
+        assert_eq!(EdgeInsets::uniform(2), EdgeInsets::new(2, 2, 2, 2));
+    }
+}
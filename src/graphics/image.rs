@@ -88,6 +88,16 @@ impl<'a> Image<'a> {
         &self.size
     }
 
+    /// The scale of the currently loaded image file. E.g. `2` if the @2x
+    /// version was loaded.
+    pub(crate) fn scale(&self) -> u8 {
+        self.scale_loaded
+    }
+
+    pub(crate) fn surface(&self) -> &Surface<'a> {
+        &self.surface
+    }
+
     /// Returns the layer to be drawn for the given context.
     ///
     /// Note: Because the scale of the context may change, in the case the
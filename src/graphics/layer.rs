@@ -173,6 +173,13 @@ impl Layer {
         texture.set_color_mod(red, green, blue);
     }
 
+    /// Set how opaque the texture is drawn, from `0.0` to `1.0`, for the
+    /// next render.
+    pub fn set_alpha_factor(&self, alpha: f32) {
+        let mut texture = self.texture.borrow_mut();
+        texture.set_alpha_mod((alpha.clamp(0.0, 1.0) * 255.0).round() as u8);
+    }
+
     /// Note: The destination at this point is using the (unscaled) point
     /// system, not the real pixel size. The size of the real texture itself is
     /// determined by this method; the difference in quality being the source
@@ -7,6 +7,7 @@ pub use number::Number;
 
 mod context;
 pub use context::Context;
+pub use context::FullscreenMode;
 
 mod point;
 pub use point::Point;
@@ -17,6 +18,12 @@ pub use size::Size;
 mod rectangle;
 pub use rectangle::Rectangle;
 
+mod edge_insets;
+pub use edge_insets::EdgeInsets;
+
+mod transform;
+pub use transform::Transform;
+
 mod layer;
 pub use layer::Layer;
 
@@ -0,0 +1,118 @@
+use crate::graphics::Rectangle;
+
+/// A scale and translation applied to a view when it's drawn, without
+/// changing its frame. See `View::set_transform`.
+///
+/// The scale is around the centre of the view's frame. Rotation isn't
+/// supported.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub translate_x: f32,
+    pub translate_y: f32
+}
+
+impl Transform {
+    /// Leaves the view as it is.
+    pub fn identity() -> Transform {
+        Transform::scale(1.0, 1.0)
+    }
+
+    pub fn scale(x: f32, y: f32) -> Transform {
+        Transform {
+            scale_x: x,
+            scale_y: y,
+            translate_x: 0.0,
+            translate_y: 0.0
+        }
+    }
+
+    pub fn translation(x: f32, y: f32) -> Transform {
+        Transform {
+            scale_x: 1.0,
+            scale_y: 1.0,
+            translate_x: x,
+            translate_y: y
+        }
+    }
+
+    /// This transform, then moved by the given amount.
+    pub fn translated(&self, x: f32, y: f32) -> Transform {
+        Transform {
+            translate_x: self.translate_x + x,
+            translate_y: self.translate_y + y,
+            ..*self
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Transform::identity()
+    }
+
+    /// Transforms a point, given relative to the centre the transform is
+    /// around.
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (x * self.scale_x + self.translate_x, y * self.scale_y + self.translate_y)
+    }
+
+    /// Undoes `apply`.
+    pub fn unapply(&self, x: f32, y: f32) -> (f32, f32) {
+        ((x - self.translate_x) / self.scale_x, (y - self.translate_y) / self.scale_y)
+    }
+
+    /// The area the rectangle covers once transformed around its centre.
+    pub fn apply_to_rect(&self, rect: &Rectangle<i32, u32>) -> Rectangle<i32, u32> {
+        if self.is_identity() {
+            return rect.clone();
+        }
+
+        let width = rect.size.width as f32 * self.scale_x;
+        let height = rect.size.height as f32 * self.scale_y;
+        let x = rect.origin.x as f32 + (rect.size.width as f32 - width) / 2.0 + self.translate_x;
+        let y = rect.origin.y as f32 + (rect.size.height as f32 - height) / 2.0 + self.translate_y;
+
+        Rectangle::new(
+            x.round() as i32,
+            y.round() as i32,
+            width.round().max(0.0) as u32,
+            height.round().max(0.0) as u32
+        )
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform::identity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity() {
+        let rect = Rectangle::new(10, 20, 30, 40);
+        assert!(Transform::identity().is_identity());
+        assert_eq!(Transform::identity().apply_to_rect(&rect), rect);
+    }
+
+    #[test]
+    fn test_scale_around_centre() {
+        let rect = Rectangle::new(10, 10, 20, 20);
+        assert_eq!(Transform::scale(2.0, 2.0).apply_to_rect(&rect), Rectangle::new(0, 0, 40, 40));
+
+        // Scaled, keeping the top left where it was.
+        let transform = Transform::scale(2.0, 2.0).translated(10.0, 10.0);
+        assert_eq!(transform.apply_to_rect(&rect), Rectangle::new(10, 10, 40, 40));
+    }
+
+    #[test]
+    fn test_apply_and_unapply() {
+        let transform = Transform::scale(2.0, 3.0).translated(1.0, -1.0);
+        assert_eq!(transform.apply(5.0, 5.0), (11.0, 14.0));
+        assert_eq!(transform.unapply(11.0, 14.0), (5.0, 5.0));
+        assert_eq!(Transform::translation(4.0, 2.0).apply(0.0, 0.0), (4.0, 2.0));
+    }
+}
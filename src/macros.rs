@@ -158,6 +158,8 @@ macro_rules! custom_view {
         impl $view {
             #![allow(dead_code)]
 
+            // One argument per behavior field.
+            #[allow(clippy::too_many_arguments)]
             pub(crate) fn new_all(frame: $crate::graphics::Rectangle<i32, u32>, $($key: $value),*) -> Self {
                 let super_behavior = $super {
                     view: $crate::ui::WeakView::none()
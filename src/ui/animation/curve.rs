@@ -0,0 +1,252 @@
+use crate::ui::animation::Spring;
+
+/// How an animation's progress changes over its duration, e.g. starting
+/// slowly and speeding up.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AnimationCurve {
+    /// A constant speed.
+    Linear,
+
+    /// Starts slowly and speeds up.
+    EaseIn,
+
+    /// Starts quickly and slows down. Usually the best fit for views
+    /// appearing or moving in response to the user.
+    EaseOut,
+
+    /// Speeds up, then slows down.
+    EaseInOut,
+
+    /// A custom curve; see `CubicBezier`.
+    CubicBezier(CubicBezier),
+
+    /// The motion of a spring, from the start to the end, sped up or slowed
+    /// down to fit the animation's duration. Usually made with
+    /// `AnimationCurve::spring`. Use `settling_duration` as the duration for
+    /// the spring to move at its natural speed.
+    ///
+    /// Springs that aren't damped enough overshoot the end and bounce back.
+    Spring {
+        spring: Spring,
+
+        /// How much of the spring's motion, in seconds, is fitted to the
+        /// animation's duration.
+        settling_duration: f32
+    }
+}
+
+impl AnimationCurve {
+    /// The whole motion of the spring until it settles. Working out how long
+    /// that takes means simulating the spring, so it's done once here rather
+    /// than on every frame.
+    pub fn spring(spring: Spring) -> AnimationCurve {
+        AnimationCurve::Spring { spring, settling_duration: spring.settling_duration() }
+    }
+
+    /// How far the animated values are from their start to their end, for
+    /// the given fraction of the duration. Both start at `0.0` and end at
+    /// `1.0`, but springs can go past the end in between.
+    pub fn value(&self, time: f32) -> f32 {
+        let time = time.clamp(0.0, 1.0);
+
+        match self {
+            AnimationCurve::Linear => time,
+            AnimationCurve::EaseIn => CubicBezier::EASE_IN.value(time),
+            AnimationCurve::EaseOut => CubicBezier::EASE_OUT.value(time),
+            AnimationCurve::EaseInOut => CubicBezier::EASE_IN_OUT.value(time),
+            AnimationCurve::CubicBezier(curve) => curve.value(time),
+            AnimationCurve::Spring { spring, settling_duration } => {
+                if time >= 1.0 {
+                    1.0
+                } else {
+                    spring.value(time * settling_duration)
+                }
+            }
+        }
+    }
+}
+
+/// An easing curve from `(0, 0)` to `(1, 1)`, shaped by two control points,
+/// as in CSS's `cubic-bezier()`. The x of each control point is time, and
+/// is kept between `0.0` and `1.0`; the y is progress, and can go beyond
+/// them to overshoot.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CubicBezier {
+    pub x1: f32,
+    pub y1: f32,
+    pub x2: f32,
+    pub y2: f32
+}
+
+/// How close a time found for an x on the curve must be.
+const BEZIER_EPSILON: f32 = 1e-6;
+
+impl CubicBezier {
+    pub const EASE_IN: CubicBezier = CubicBezier::new(0.42, 0.0, 1.0, 1.0);
+    pub const EASE_OUT: CubicBezier = CubicBezier::new(0.0, 0.0, 0.58, 1.0);
+    pub const EASE_IN_OUT: CubicBezier = CubicBezier::new(0.42, 0.0, 0.58, 1.0);
+
+    pub const fn new(x1: f32, y1: f32, x2: f32, y2: f32) -> CubicBezier {
+        CubicBezier { x1, y1, x2, y2 }
+    }
+
+    /// The progress at the given time, both from `0.0` to `1.0`.
+    pub fn value(&self, time: f32) -> f32 {
+        let time = time.clamp(0.0, 1.0);
+
+        if time == 0.0 || time == 1.0 {
+            return time;
+        }
+
+        let x1 = self.x1.clamp(0.0, 1.0);
+        let x2 = self.x2.clamp(0.0, 1.0);
+        let t = solve_for(time, x1, x2);
+
+        bezier(t, self.y1, self.y2)
+    }
+}
+
+/// One coordinate of the curve at parameter `t`, for control point
+/// coordinates `a` and `b` (the ends being `0` and `1`).
+fn bezier(t: f32, a: f32, b: f32) -> f32 {
+    ((((1.0 - 3.0 * b + 3.0 * a) * t) + (3.0 * b - 6.0 * a)) * t + 3.0 * a) * t
+}
+
+fn bezier_slope(t: f32, a: f32, b: f32) -> f32 {
+    3.0 * (1.0 - 3.0 * b + 3.0 * a) * t * t + 2.0 * (3.0 * b - 6.0 * a) * t + 3.0 * a
+}
+
+/// The parameter `t` at which the curve's x is `x`. Newton's method is
+/// usually quickest, with bisection for flat parts of the curve.
+fn solve_for(x: f32, x1: f32, x2: f32) -> f32 {
+    let mut t = x;
+
+    for _ in 0..8 {
+        let error = bezier(t, x1, x2) - x;
+
+        if error.abs() < BEZIER_EPSILON {
+            return t;
+        }
+
+        let slope = bezier_slope(t, x1, x2);
+
+        if slope.abs() < BEZIER_EPSILON {
+            break;
+        }
+
+        t -= error / slope;
+    }
+
+    let mut low = 0.0;
+    let mut high = 1.0;
+    t = x;
+
+    for _ in 0..32 {
+        let value = bezier(t, x1, x2);
+
+        if (value - x).abs() < BEZIER_EPSILON {
+            break;
+        }
+
+        if value < x {
+            low = t;
+        } else {
+            high = t;
+        }
+
+        t = (low + high) / 2.0;
+    }
+
+    t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(value: f32, expected: f32) {
+        assert!((value - expected).abs() < 0.001, "{} is not close to {}", value, expected);
+    }
+
+    #[test]
+    fn test_end_points() {
+        let curves = [
+            AnimationCurve::Linear,
+            AnimationCurve::EaseIn,
+            AnimationCurve::EaseOut,
+            AnimationCurve::EaseInOut,
+            AnimationCurve::CubicBezier(CubicBezier::new(0.3, -0.5, 0.7, 1.5)),
+            AnimationCurve::spring(Spring::default())
+        ];
+
+        for curve in curves.iter() {
+            assert_eq!(curve.value(0.0), 0.0, "{:?}", curve);
+            assert_eq!(curve.value(1.0), 1.0, "{:?}", curve);
+            assert_eq!(curve.value(2.0), 1.0, "{:?}", curve);
+        }
+    }
+
+    #[test]
+    fn test_shape() {
+        assert_eq!(AnimationCurve::Linear.value(0.25), 0.25);
+        assert!(AnimationCurve::EaseIn.value(0.25) < 0.25);
+        assert!(AnimationCurve::EaseOut.value(0.25) > 0.25);
+        assert!(AnimationCurve::EaseInOut.value(0.25) < 0.25);
+        assert!(AnimationCurve::EaseInOut.value(0.75) > 0.75);
+        assert_close(AnimationCurve::EaseInOut.value(0.5), 0.5);
+    }
+
+    #[test]
+    fn test_cubic_bezier() {
+        // Control points on the diagonal make a straight line.
+        let linear = CubicBezier::new(1.0 / 3.0, 1.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0);
+        for time in [0.1, 0.3, 0.5, 0.9].iter() {
+            assert_close(linear.value(*time), *time);
+        }
+
+        // CSS's `ease`.
+        let ease = CubicBezier::new(0.25, 0.1, 0.25, 1.0);
+        assert_close(ease.value(0.5), 0.8024);
+
+        assert_close(CubicBezier::EASE_IN.value(0.5), 0.3153);
+        assert_close(CubicBezier::EASE_OUT.value(0.5), 0.6847);
+
+        // Overshoots, then comes back.
+        let back = CubicBezier::new(0.3, 0.0, 0.6, 1.8);
+        assert!(back.value(0.8) > 1.0);
+    }
+
+    #[test]
+    fn test_monotonic() {
+        let curves = [CubicBezier::EASE_IN, CubicBezier::EASE_OUT, CubicBezier::EASE_IN_OUT, CubicBezier::new(0.0, 1.0, 1.0, 0.0)];
+
+        for curve in curves.iter() {
+            let mut previous = 0.0;
+
+            for step in 1..=100 {
+                let value = curve.value(step as f32 / 100.0);
+                assert!(value >= previous, "{:?} at {}", curve, step);
+                previous = value;
+            }
+        }
+    }
+
+    #[test]
+    fn test_spring_curve() {
+        let bouncy = AnimationCurve::spring(Spring::new(1.0, 100.0, 5.0));
+        let overshoots = (1..100).any(|step| bouncy.value(step as f32 / 100.0) > 1.0);
+        assert!(overshoots);
+
+        let critical = AnimationCurve::spring(Spring::new(1.0, 100.0, 20.0));
+        for step in 1..100 {
+            let value = critical.value(step as f32 / 100.0);
+            assert!(value > 0.0 && value <= 1.0);
+        }
+
+        // Fitted to the spring's settling duration, worked out up front.
+        let spring = Spring::default();
+        let curve = AnimationCurve::spring(spring);
+        assert_eq!(curve, AnimationCurve::Spring { spring, settling_duration: spring.settling_duration() });
+        assert_eq!(curve.value(0.5), spring.value(spring.settling_duration() / 2.0));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::graphics::{Number, Point, Rectangle, Size, Transform};
+use crate::ui::Color;
+
+/// A value that can be animated, by working out the values between a start
+/// and an end.
+pub trait Interpolate: Clone {
+    /// The value `progress` of the way from `self` to `to`, where `0.0` is
+    /// `self` and `1.0` is `to`. Progress can go beyond either end, e.g. for
+    /// a curve that overshoots.
+    fn interpolate(&self, to: &Self, progress: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, to: &f32, progress: f32) -> f32 {
+        self + (to - self) * progress
+    }
+}
+
+impl Interpolate for i32 {
+    fn interpolate(&self, to: &i32, progress: f32) -> i32 {
+        (*self as f32).interpolate(&(*to as f32), progress).round() as i32
+    }
+}
+
+impl Interpolate for u32 {
+    fn interpolate(&self, to: &u32, progress: f32) -> u32 {
+        (*self as f32).interpolate(&(*to as f32), progress).round().max(0.0) as u32
+    }
+}
+
+impl Interpolate for u8 {
+    fn interpolate(&self, to: &u8, progress: f32) -> u8 {
+        (*self as f32).interpolate(&(*to as f32), progress).round().clamp(0.0, 255.0) as u8
+    }
+}
+
+impl<T> Interpolate for Point<T> where T: Number + Interpolate {
+    fn interpolate(&self, to: &Point<T>, progress: f32) -> Point<T> {
+        Point::new(self.x.interpolate(&to.x, progress), self.y.interpolate(&to.y, progress))
+    }
+}
+
+impl<T> Interpolate for Size<T> where T: Number + Interpolate {
+    fn interpolate(&self, to: &Size<T>, progress: f32) -> Size<T> {
+        Size::new(self.width.interpolate(&to.width, progress), self.height.interpolate(&to.height, progress))
+    }
+}
+
+impl<T, U> Interpolate for Rectangle<T, U> where T: Number + Interpolate, U: Number + Interpolate {
+    fn interpolate(&self, to: &Rectangle<T, U>, progress: f32) -> Rectangle<T, U> {
+        Rectangle {
+            origin: self.origin.interpolate(&to.origin, progress),
+            size: self.size.interpolate(&to.size, progress)
+        }
+    }
+}
+
+impl Interpolate for Color {
+    fn interpolate(&self, to: &Color, progress: f32) -> Color {
+        Color::new(
+            self.red.interpolate(&to.red, progress),
+            self.green.interpolate(&to.green, progress),
+            self.blue.interpolate(&to.blue, progress),
+            self.alpha.interpolate(&to.alpha, progress)
+        )
+    }
+}
+
+impl Interpolate for Transform {
+    fn interpolate(&self, to: &Transform, progress: f32) -> Transform {
+        Transform {
+            scale_x: self.scale_x.interpolate(&to.scale_x, progress),
+            scale_y: self.scale_y.interpolate(&to.scale_y, progress),
+            translate_x: self.translate_x.interpolate(&to.translate_x, progress),
+            translate_y: self.translate_y.interpolate(&to.translate_y, progress)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(10.0.interpolate(&20.0, 0.25), 12.5);
+        assert_eq!((-10).interpolate(&10, 0.5), 0);
+        assert_eq!(10u32.interpolate(&0, 2.0), 0);
+        assert_eq!(200u8.interpolate(&255, 2.0), 255);
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let from = Rectangle::new(0, 0, 100, 100);
+        let to = Rectangle::new(100, -50, 200, 0);
+
+        assert_eq!(from.interpolate(&to, 0.0), from);
+        assert_eq!(from.interpolate(&to, 0.5), Rectangle::new(50, -25, 150, 50));
+        assert_eq!(from.interpolate(&to, 1.0), to);
+    }
+
+    #[test]
+    fn test_color_and_transform() {
+        let color = Color::black().interpolate(&Color::new(255, 255, 255, 0), 0.5);
+        assert_eq!(color, Color::new(128, 128, 128, 128));
+
+        let transform = Transform::identity().interpolate(&Transform::scale(2.0, 3.0).translated(10.0, 0.0), 0.5);
+        assert_eq!(transform, Transform::scale(1.5, 2.0).translated(5.0, 0.0));
+    }
+}
@@ -0,0 +1,576 @@
+//! Animating views. See `View::animate`.
+//!
+//! Changes made to a view's animatable properties inside the closure given
+//! to `View::animate` are recorded rather than shown straight away. Each
+//! property is then moved from its old value to its new one, a little every
+//! frame, by a `Timer` on the `RunLoop`.
+//!
+//! Other values, e.g. ones used by custom drawing, are animated with an
+//! `Animation` instead.
+
+pub mod curve;
+pub mod interpolate;
+pub mod spring;
+pub mod value_animation;
+
+pub use curve::{AnimationCurve, CubicBezier};
+pub use interpolate::Interpolate;
+pub use spring::Spring;
+pub use value_animation::{Animation, Keyframe};
+
+use crate::graphics::{Point, Rectangle, Transform};
+use crate::ui::run_loop::RunLoop;
+use crate::ui::timer::Timer;
+use crate::ui::{Color, View, WeakView};
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+use std::time::{Duration, Instant};
+
+/// How often running animations update their views.
+const FRAME_INTERVAL_MS: u64 = 16;
+
+/// A timer calling `frame` once every frame, for motion that doesn't have a
+/// set duration, e.g. scrolling content slowing down after a flick. It's
+/// already added to the `RunLoop`; invalidate it to stop.
+pub(crate) fn frame_timer(frame: impl Fn() + 'static) -> Timer {
+    let timer = Timer::new_repeating(Duration::from_millis(FRAME_INTERVAL_MS), frame);
+
+    let run_loop = RunLoop::borrow();
+    run_loop.add_timer(timer.clone());
+
+    timer
+}
+
+/// The views and old values of the properties changed inside a
+/// `View::animate` closure.
+type Changes = Vec<(WeakView, AnimatedValue)>;
+
+thread_local! {
+    /// The changes for each `View::animate` closure being run; the innermost
+    /// is last.
+    static TRANSACTIONS: RefCell<Vec<Changes>> = const { RefCell::new(Vec::new()) };
+
+    /// Animations that haven't finished or been cancelled.
+    static RUNNING: RefCell<Vec<ViewAnimation>> = const { RefCell::new(Vec::new()) };
+}
+
+/// An animatable property of a view, with a value for it.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum AnimatedValue {
+    Frame(Rectangle<i32, u32>),
+    BoundsOrigin(Point<i32>),
+    BackgroundColor(Color),
+    Alpha(f32),
+    Transform(Transform)
+}
+
+impl AnimatedValue {
+    /// The view's current value for the same property.
+    fn current(&self, view: &View) -> AnimatedValue {
+        match self {
+            AnimatedValue::Frame(_) => AnimatedValue::Frame(view.frame()),
+            AnimatedValue::BoundsOrigin(_) => AnimatedValue::BoundsOrigin(view.bounds().origin),
+            AnimatedValue::BackgroundColor(_) => AnimatedValue::BackgroundColor(view.background_color()),
+            AnimatedValue::Alpha(_) => AnimatedValue::Alpha(view.alpha()),
+            AnimatedValue::Transform(_) => AnimatedValue::Transform(view.transform())
+        }
+    }
+
+    fn apply(&self, view: &View) {
+        match self {
+            AnimatedValue::Frame(frame) => {
+                view.set_frame(frame.clone());
+            },
+            AnimatedValue::BoundsOrigin(origin) => {
+                let bounds = view.bounds();
+                view.set_bounds(Rectangle { origin: origin.clone(), size: bounds.size });
+            },
+            AnimatedValue::BackgroundColor(color) => view.set_background_color(color.clone()),
+            AnimatedValue::Alpha(alpha) => view.set_alpha(*alpha),
+            AnimatedValue::Transform(transform) => view.set_transform(*transform)
+        }
+    }
+
+    fn interpolate(&self, to: &AnimatedValue, progress: f32) -> AnimatedValue {
+        match (self, to) {
+            (AnimatedValue::Frame(from), AnimatedValue::Frame(to)) => AnimatedValue::Frame(from.interpolate(to, progress)),
+            (AnimatedValue::BoundsOrigin(from), AnimatedValue::BoundsOrigin(to)) => AnimatedValue::BoundsOrigin(from.interpolate(to, progress)),
+            (AnimatedValue::BackgroundColor(from), AnimatedValue::BackgroundColor(to)) => AnimatedValue::BackgroundColor(from.interpolate(to, progress)),
+            (AnimatedValue::Alpha(from), AnimatedValue::Alpha(to)) => AnimatedValue::Alpha(from.interpolate(to, progress).clamp(0.0, 1.0)),
+            (AnimatedValue::Transform(from), AnimatedValue::Transform(to)) => AnimatedValue::Transform(from.interpolate(to, progress)),
+            _ => panic!("cannot interpolate between different properties")
+        }
+    }
+
+    fn is_same_property(&self, other: &AnimatedValue) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+/// Called by a view's setters before an animatable property changes. Inside
+/// a `View::animate` closure, the old value is kept so the change can be
+/// animated.
+pub(crate) fn record(view: &View, old_value: impl FnOnce() -> AnimatedValue) {
+    TRANSACTIONS.with(|transactions| {
+        let mut transactions = transactions.borrow_mut();
+
+        let changes = match transactions.last_mut() {
+            Some(changes) => changes,
+            None => return
+        };
+
+        let old_value = old_value();
+        let id = Some(view.id());
+
+        // Only the value from before the first change is animated from.
+        let recorded = changes.iter().any(|(recorded_view, value)| {
+            recorded_view.id() == id && value.is_same_property(&old_value)
+        });
+
+        if !recorded {
+            changes.push((view.downgrade(), old_value));
+        }
+    });
+}
+
+/// Stop animating the given view's properties, leaving them where they are.
+pub(crate) fn cancel_view_animations(view: &View) {
+    let id = Some(view.id());
+
+    for animation in running() {
+        animation.remove_tracks(|track| track.view.id() == id);
+    }
+}
+
+/// How many times an animation plays.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimationRepeat {
+    Count(u32),
+    Forever
+}
+
+/// Called when an animation ends, with whether it finished rather than
+/// being cancelled.
+type Completion = Box<dyn FnOnce(bool)>;
+
+/// Where an animation is along its timeline. See `Timing::phase`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Phase {
+    /// Waiting for its delay to pass.
+    Waiting,
+
+    /// Playing, at this fraction of its duration. Runs backwards on the way
+    /// back when autoreversing.
+    Playing(f32),
+
+    /// Done, ending at this fraction of its duration.
+    Finished(f32)
+}
+
+/// The duration, delay and repetition of an animation.
+struct Timing {
+    duration: Duration,
+    delay: Cell<Duration>,
+    repeat: Cell<AnimationRepeat>,
+    autoreverses: Cell<bool>
+}
+
+impl Timing {
+    fn new(duration: Duration) -> Timing {
+        Timing {
+            duration,
+            delay: Cell::new(Duration::from_secs(0)),
+            repeat: Cell::new(AnimationRepeat::Count(1)),
+            autoreverses: Cell::new(false)
+        }
+    }
+
+    fn phase(&self, started_at: Instant, now: Instant) -> Phase {
+        let start = started_at + self.delay.get();
+
+        if now < start {
+            return Phase::Waiting;
+        }
+
+        let autoreverses = self.autoreverses.get();
+        let legs_per_play = if autoreverses { 2 } else { 1 };
+        let legs = match self.repeat.get() {
+            AnimationRepeat::Count(count) => Some(count.max(1) * legs_per_play),
+            AnimationRepeat::Forever => None
+        };
+
+        let elapsed = now.duration_since(start).as_secs_f64();
+        let duration = self.duration.as_secs_f64();
+        let elapsed_legs = if duration > 0.0 { elapsed / duration } else { f64::INFINITY };
+
+        if let Some(legs) = legs {
+            if elapsed_legs >= legs as f64 {
+                return Phase::Finished(if autoreverses { 0.0 } else { 1.0 });
+            }
+        }
+
+        let leg = elapsed_legs.floor();
+        let time = (elapsed_legs - leg) as f32;
+
+        Phase::Playing(if autoreverses && leg as u32 % 2 == 1 { 1.0 - time } else { time })
+    }
+}
+
+/// A property of one view being animated.
+struct Track {
+    view: WeakView,
+    from: AnimatedValue,
+    to: AnimatedValue
+}
+
+struct ViewAnimationInner {
+    timing: Timing,
+    curve: AnimationCurve,
+    started_at: Instant,
+    tracks: RefCell<Vec<Track>>,
+    completion: RefCell<Option<Completion>>,
+    timer: RefCell<Option<Timer>>,
+    is_running: Cell<bool>
+}
+
+/// A running animation started with `View::animate`. Options can be
+/// changed straight after starting it, before its first frame:
+///
+/// ```ignore
+/// View::animate(Duration::from_millis(300), AnimationCurve::EaseOut, || {
+///     view.set_alpha(0.0);
+/// })
+/// .with_delay(Duration::from_secs(1))
+/// .with_completion(move |_| view.remove_from_superview());
+/// ```
+#[derive(Clone)]
+pub struct ViewAnimation {
+    inner: Rc<ViewAnimationInner>
+}
+
+impl ViewAnimation {
+    pub(crate) fn animate(duration: Duration, curve: AnimationCurve, changes: impl FnOnce()) -> ViewAnimation {
+        let animation = ViewAnimation::record(duration, curve, changes);
+
+        let weak_animation = Rc::downgrade(&animation.inner);
+        let timer = frame_timer(move || {
+            if let Some(inner) = Weak::upgrade(&weak_animation) {
+                ViewAnimation { inner }.update(Instant::now());
+            }
+        });
+
+        animation.inner.timer.replace(Some(timer));
+        animation
+    }
+
+    /// Runs `changes`, moving the views back to where they were, and adds
+    /// the animation to the running ones. Nothing moves them on until it's
+    /// updated, which `animate` does every frame.
+    fn record(duration: Duration, curve: AnimationCurve, changes: impl FnOnce()) -> ViewAnimation {
+        TRANSACTIONS.with(|transactions| transactions.borrow_mut().push(Vec::new()));
+        changes();
+        let changes = TRANSACTIONS.with(|transactions| transactions.borrow_mut().pop())
+            .expect("animation transaction was missing");
+
+        let tracks: Vec<Track> = changes.into_iter()
+            .filter_map(|(weak_view, from)| {
+                let view = weak_view.upgrade()?;
+                let to = from.current(&view);
+
+                if to == from {
+                    None
+                } else {
+                    Some(Track { view: weak_view, from, to })
+                }
+            })
+            .collect();
+
+        // Start from where the views were before the changes, in the order
+        // they changed so subviews autoresized with their superview end up
+        // back where they were.
+        for track in tracks.iter() {
+            if let Some(view) = track.view.upgrade() {
+                track.from.apply(&view);
+            }
+        }
+
+        // The new animation takes over properties already being animated.
+        for animation in running() {
+            animation.remove_tracks(|running_track| {
+                tracks.iter().any(|track| {
+                    track.view.id() == running_track.view.id() && track.from.is_same_property(&running_track.from)
+                })
+            });
+        }
+
+        let animation = ViewAnimation {
+            inner: Rc::new(ViewAnimationInner {
+                timing: Timing::new(duration),
+                curve,
+                started_at: Instant::now(),
+                tracks: RefCell::new(tracks),
+                completion: RefCell::new(None),
+                timer: RefCell::new(None),
+                is_running: Cell::new(true)
+            })
+        };
+
+        RUNNING.with(|running| running.borrow_mut().push(animation.clone()));
+
+        animation
+    }
+
+    /// Wait before starting. The views stay at their old values until then.
+    pub fn with_delay(self, delay: Duration) -> ViewAnimation {
+        self.inner.timing.delay.set(delay);
+        self
+    }
+
+    pub fn with_repeat(self, repeat: AnimationRepeat) -> ViewAnimation {
+        self.inner.timing.repeat.set(repeat);
+        self
+    }
+
+    /// Play each time forwards and then backwards, ending back at the old
+    /// values.
+    pub fn with_autoreverse(self, autoreverses: bool) -> ViewAnimation {
+        self.inner.timing.autoreverses.set(autoreverses);
+        self
+    }
+
+    /// Called once the animation ends, with `true` if it finished or `false`
+    /// if it was cancelled or another animation took over all of its
+    /// properties.
+    pub fn with_completion(self, completion: impl FnOnce(bool) + 'static) -> ViewAnimation {
+        self.inner.completion.replace(Some(Box::new(completion)));
+        self
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.inner.is_running.get()
+    }
+
+    /// Stop the animation, leaving the views where they are.
+    pub fn cancel(&self) {
+        self.finish(false);
+    }
+
+    /// Move the views to where they should be at the given time.
+    pub(crate) fn update(&self, now: Instant) {
+        if !self.is_running() {
+            return;
+        }
+
+        let inner = &self.inner;
+
+        match inner.timing.phase(inner.started_at, now) {
+            Phase::Waiting => {},
+            Phase::Playing(time) => self.set_progress(inner.curve.value(time)),
+            Phase::Finished(time) => {
+                self.set_progress(inner.curve.value(time));
+                self.finish(true);
+            }
+        }
+    }
+
+    fn set_progress(&self, progress: f32) {
+        let values: Vec<(View, AnimatedValue)> = self.inner.tracks.borrow().iter()
+            .filter_map(|track| {
+                let view = track.view.upgrade()?;
+                Some((view, track.from.interpolate(&track.to, progress)))
+            })
+            .collect();
+
+        for (view, value) in values.iter() {
+            value.apply(view);
+        }
+    }
+
+    /// Stop animating the tracks matching `predicate`. If that leaves
+    /// nothing to animate, the animation is cancelled.
+    fn remove_tracks(&self, predicate: impl Fn(&Track) -> bool) {
+        let is_empty = {
+            let mut tracks = self.inner.tracks.borrow_mut();
+
+            let count = tracks.len();
+            tracks.retain(|track| !predicate(track));
+
+            if tracks.len() == count {
+                return;
+            }
+
+            tracks.is_empty()
+        };
+
+        if is_empty {
+            self.finish(false);
+        }
+    }
+
+    fn finish(&self, finished: bool) {
+        if !self.is_running() {
+            return;
+        }
+
+        self.inner.is_running.set(false);
+
+        if let Some(timer) = self.inner.timer.borrow_mut().take() {
+            timer.invalidate();
+        }
+
+        RUNNING.with(|running| running.borrow_mut().retain(|animation| animation != self));
+
+        let completion = self.inner.completion.borrow_mut().take();
+
+        if let Some(completion) = completion {
+            completion(finished);
+        }
+    }
+}
+
+impl PartialEq for ViewAnimation {
+    fn eq(&self, rhs: &ViewAnimation) -> bool {
+        Rc::ptr_eq(&self.inner, &rhs.inner)
+    }
+}
+
+fn running() -> Vec<ViewAnimation> {
+    RUNNING.with(|running| running.borrow().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Animations are recorded without their frame timer, which can only run
+    // on the main thread, and updated by hand instead.
+
+    fn after(animation: &ViewAnimation, milliseconds: u64) -> Instant {
+        animation.inner.started_at + Duration::from_millis(milliseconds)
+    }
+
+    #[test]
+    fn test_animate_frame_and_alpha() {
+        let view = View::new(Rectangle::new(0, 0, 100, 100));
+
+        let animation = ViewAnimation::record(Duration::from_millis(100), AnimationCurve::Linear, || {
+            view.set_frame(Rectangle::new(100, 0, 200, 100));
+            view.set_alpha(0.0);
+        });
+
+        // Starts from the old values.
+        assert_eq!(view.frame(), Rectangle::new(0, 0, 100, 100));
+        assert_eq!(view.alpha(), 1.0);
+
+        animation.update(after(&animation, 50));
+        assert_eq!(view.frame(), Rectangle::new(50, 0, 150, 100));
+        assert_eq!(view.bounds().size, view.frame().size);
+        assert_eq!(view.alpha(), 0.5);
+
+        animation.update(after(&animation, 150));
+        assert_eq!(view.frame(), Rectangle::new(100, 0, 200, 100));
+        assert_eq!(view.alpha(), 0.0);
+        assert!(!animation.is_running());
+    }
+
+    #[test]
+    fn test_delay_and_completion() {
+        let view = View::new(Rectangle::new(0, 0, 100, 100));
+        let finished = Rc::new(Cell::new(None));
+
+        let completion_finished = finished.clone();
+        let animation = ViewAnimation::record(Duration::from_millis(100), AnimationCurve::Linear, || {
+            view.set_background_color(Color::black());
+        })
+        .with_delay(Duration::from_millis(100))
+        .with_completion(move |finished| completion_finished.set(Some(finished)));
+
+        animation.update(after(&animation, 50));
+        assert_eq!(view.background_color(), Color::white());
+
+        animation.update(after(&animation, 150));
+        assert_eq!(view.background_color(), Color::gray());
+        assert_eq!(finished.get(), None);
+
+        animation.update(after(&animation, 200));
+        assert_eq!(view.background_color(), Color::black());
+        assert_eq!(finished.get(), Some(true));
+    }
+
+    #[test]
+    fn test_repeat_and_autoreverse() {
+        let view = View::new(Rectangle::new(0, 0, 100, 100));
+
+        let animation = ViewAnimation::record(Duration::from_millis(100), AnimationCurve::Linear, || {
+            view.set_transform(Transform::scale(2.0, 2.0));
+        })
+        .with_repeat(AnimationRepeat::Count(2))
+        .with_autoreverse(true);
+
+        animation.update(after(&animation, 50));
+        assert_eq!(view.transform(), Transform::scale(1.5, 1.5));
+
+        animation.update(after(&animation, 175));
+        assert_eq!(view.transform(), Transform::scale(1.25, 1.25));
+
+        animation.update(after(&animation, 350));
+        assert_eq!(view.transform(), Transform::scale(1.5, 1.5));
+        assert!(animation.is_running());
+
+        // Ends back where it started.
+        animation.update(after(&animation, 400));
+        assert_eq!(view.transform(), Transform::identity());
+        assert!(!animation.is_running());
+    }
+
+    #[test]
+    fn test_cancel() {
+        let view = View::new(Rectangle::new(0, 0, 100, 100));
+        let finished = Rc::new(Cell::new(None));
+
+        let completion_finished = finished.clone();
+        let animation = ViewAnimation::record(Duration::from_millis(100), AnimationCurve::Linear, || {
+            view.set_bounds(Rectangle::new(0, 100, 100, 100));
+        })
+        .with_completion(move |finished| completion_finished.set(Some(finished)));
+
+        animation.update(after(&animation, 50));
+        animation.cancel();
+        animation.update(after(&animation, 100));
+
+        assert_eq!(view.bounds(), Rectangle::new(0, 50, 100, 100));
+        assert_eq!(finished.get(), Some(false));
+    }
+
+    #[test]
+    fn test_taking_over_a_property() {
+        let view = View::new(Rectangle::new(0, 0, 100, 100));
+
+        let first = ViewAnimation::record(Duration::from_millis(100), AnimationCurve::Linear, || {
+            view.set_alpha(0.0);
+            view.set_transform(Transform::scale(2.0, 2.0));
+        });
+
+        first.update(after(&first, 50));
+
+        // Fades back in from half way, while the first keeps scaling.
+        let second = ViewAnimation::record(Duration::from_millis(100), AnimationCurve::Linear, || {
+            view.set_alpha(1.0);
+        });
+
+        first.update(after(&first, 100));
+        assert_eq!(view.alpha(), 0.5);
+        assert_eq!(view.transform(), Transform::scale(2.0, 2.0));
+
+        second.update(after(&second, 100));
+        assert_eq!(view.alpha(), 1.0);
+
+        // Cancelled once it has nothing left to animate.
+        let third = ViewAnimation::record(Duration::from_millis(100), AnimationCurve::Linear, || {
+            view.set_alpha(0.0);
+        });
+
+        view.cancel_animations();
+        assert!(!third.is_running());
+    }
+}
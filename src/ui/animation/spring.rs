@@ -0,0 +1,272 @@
+/// A damped spring, pulling something back to where it rests. Used for
+/// spring animations (see `AnimationCurve::Spring`) and for content
+/// bouncing back from past the edge of a `ScrollView`.
+///
+/// The lower the damping compared to the stiffness, the more the spring
+/// overshoots and wobbles before settling; see `damping_ratio`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spring {
+    pub mass: f32,
+
+    /// How strongly the spring pulls back, for each point it's stretched.
+    pub stiffness: f32,
+
+    /// How strongly the spring's motion is slowed, for each point per
+    /// second it moves.
+    pub damping: f32,
+
+    /// How fast the spring is already moving towards the end when a spring
+    /// animation starts, in the distance from the start to the end per
+    /// second.
+    pub initial_velocity: f32
+}
+
+/// How close to the end (as a fraction of the distance from the start) a
+/// spring must stay to have settled.
+const SETTLED_DISTANCE: f32 = 0.001;
+
+/// How slowly (in the distance from the start per second) a spring must
+/// move to have settled.
+const SETTLED_SPEED: f32 = 0.01;
+
+/// How finely `Spring::settling_duration` looks for the spring settling,
+/// in seconds.
+const SETTLING_STEP: f32 = 1.0 / 120.0;
+
+/// The longest `Spring::settling_duration` looks for, in seconds.
+const MAXIMUM_SETTLING_DURATION: f32 = 60.0;
+
+impl Spring {
+    pub const fn new(mass: f32, stiffness: f32, damping: f32) -> Spring {
+        Spring { mass, stiffness, damping, initial_velocity: 0.0 }
+    }
+
+    /// A spring damped just enough to return as quickly as possible without
+    /// overshooting; see `damping_ratio`.
+    pub fn critically_damped(mass: f32, stiffness: f32) -> Spring {
+        Spring::new(mass, stiffness, 2.0 * (stiffness * mass).sqrt())
+    }
+
+    pub const fn with_initial_velocity(self, initial_velocity: f32) -> Spring {
+        Spring { initial_velocity, ..self }
+    }
+
+    /// How damped the spring is: below `1.0` it overshoots and wobbles,
+    /// `1.0` (critically damped) returns as quickly as possible without
+    /// overshooting, and above `1.0` it returns more slowly.
+    pub fn damping_ratio(&self) -> f32 {
+        self.damping / (2.0 * (self.stiffness * self.mass).sqrt())
+    }
+
+    /// Where the spring is (from `0.0` at the start to `1.0` at the end)
+    /// after `time` seconds, starting at `initial_velocity`.
+    pub fn value(&self, time: f32) -> f32 {
+        let (displacement, _) = self.step(-1.0, self.initial_velocity, time);
+        1.0 + displacement
+    }
+
+    /// How fast the spring is moving after `time` seconds, in the distance
+    /// from the start to the end per second.
+    pub fn velocity(&self, time: f32) -> f32 {
+        let (_, velocity) = self.step(-1.0, self.initial_velocity, time);
+        velocity
+    }
+
+    /// The displacement from rest and velocity of something on the spring,
+    /// `elapsed` seconds after it was at `displacement` moving at `velocity`
+    /// (in the same units per second). E.g. scroll view content pulled past
+    /// its edge.
+    ///
+    /// Solved exactly, so taking one large step is the same as many small
+    /// ones.
+    pub fn step(&self, displacement: f32, velocity: f32, elapsed: f32) -> (f32, f32) {
+        let mass = self.mass.max(f32::EPSILON) as f64;
+        let stiffness = self.stiffness.max(0.0) as f64;
+        let damping = self.damping.max(0.0) as f64;
+        let x0 = displacement as f64;
+        let v0 = velocity as f64;
+        let t = elapsed as f64;
+
+        if stiffness == 0.0 {
+            // Nothing pulls it back; only damping slows it down.
+            if damping == 0.0 {
+                return ((x0 + v0 * t) as f32, velocity);
+            }
+
+            let decay = (-damping / mass * t).exp();
+            let x = x0 + v0 * mass / damping * (1.0 - decay);
+            return (x as f32, (v0 * decay) as f32);
+        }
+
+        let natural_frequency = (stiffness / mass).sqrt();
+        let ratio = damping / (2.0 * (stiffness * mass).sqrt());
+
+        let (x, v) = if (ratio - 1.0).abs() < 1e-6 {
+            // Critically damped.
+            let b = v0 + natural_frequency * x0;
+            let decay = (-natural_frequency * t).exp();
+            let x = decay * (x0 + b * t);
+            let v = decay * (b - natural_frequency * (x0 + b * t));
+            (x, v)
+        } else if ratio < 1.0 {
+            // Under damped; oscillates.
+            let frequency = natural_frequency * (1.0 - ratio * ratio).sqrt();
+            let decay_rate = ratio * natural_frequency;
+            let b = (v0 + decay_rate * x0) / frequency;
+            let decay = (-decay_rate * t).exp();
+            let (sin, cos) = (frequency * t).sin_cos();
+            let position = x0 * cos + b * sin;
+            let x = decay * position;
+            let v = decay * (-decay_rate * position + frequency * (b * cos - x0 * sin));
+            (x, v)
+        } else {
+            // Over damped.
+            let root = natural_frequency * (ratio * ratio - 1.0).sqrt();
+            let r1 = -ratio * natural_frequency + root;
+            let r2 = -ratio * natural_frequency - root;
+            let c2 = (v0 - r1 * x0) / (r2 - r1);
+            let c1 = x0 - c2;
+            let (e1, e2) = ((r1 * t).exp(), (r2 * t).exp());
+            (c1 * e1 + c2 * e2, c1 * r1 * e1 + c2 * r2 * e2)
+        };
+
+        (x as f32, v as f32)
+    }
+
+    /// About how long, in seconds, the spring takes to come to rest at the
+    /// end, starting at `initial_velocity`.
+    pub fn settling_duration(&self) -> f32 {
+        let mut settled_at = 0.0;
+        let mut time = 0.0;
+
+        while time < MAXIMUM_SETTLING_DURATION {
+            time += SETTLING_STEP;
+
+            let (displacement, velocity) = self.step(-1.0, self.initial_velocity, time);
+
+            if displacement.abs() >= SETTLED_DISTANCE || velocity.abs() >= SETTLED_SPEED {
+                settled_at = time + SETTLING_STEP;
+            } else if time - settled_at > 1.0 {
+                // Damped springs don't start moving again.
+                break;
+            }
+        }
+
+        settled_at.min(MAXIMUM_SETTLING_DURATION)
+    }
+}
+
+impl Default for Spring {
+    /// A lively spring that overshoots a little, settling in about a second.
+    fn default() -> Spring {
+        Spring::new(1.0, 100.0, 10.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(value: f32, expected: f32) {
+        assert!((value - expected).abs() < 0.001, "{} is not close to {}", value, expected);
+    }
+
+    #[test]
+    fn test_damping_ratio() {
+        assert_close(Spring::new(1.0, 100.0, 20.0).damping_ratio(), 1.0);
+        assert_close(Spring::new(1.0, 100.0, 10.0).damping_ratio(), 0.5);
+        assert_close(Spring::new(4.0, 100.0, 10.0).damping_ratio(), 0.25);
+        assert_close(Spring::critically_damped(1.0, 150.0).damping_ratio(), 1.0);
+        assert_close(Spring::critically_damped(2.0, 50.0).damping, 20.0);
+    }
+
+    #[test]
+    fn test_value() {
+        let springs = [Spring::new(1.0, 100.0, 5.0), Spring::new(1.0, 100.0, 20.0), Spring::new(1.0, 100.0, 50.0)];
+
+        for spring in springs.iter() {
+            assert_close(spring.value(0.0), 0.0);
+            assert_close(spring.velocity(0.0), 0.0);
+            assert_close(spring.value(10.0), 1.0);
+            assert_close(spring.velocity(10.0), 0.0);
+        }
+
+        // An undamped spring oscillates around the end forever, with a
+        // period of 2π / √(stiffness / mass).
+        let undamped = Spring::new(1.0, 100.0, 0.0);
+        let period = 2.0 * std::f32::consts::PI / 10.0;
+        assert_close(undamped.value(period / 2.0), 2.0);
+        assert_close(undamped.value(period), 0.0);
+    }
+
+    #[test]
+    fn test_overshoot() {
+        let under = Spring::new(1.0, 100.0, 5.0);
+        let critical = Spring::new(1.0, 100.0, 20.0);
+        let over = Spring::new(1.0, 100.0, 50.0);
+
+        let maximum = |spring: &Spring| {
+            (1..200).map(|step| spring.value(step as f32 / 100.0)).fold(0.0, f32::max)
+        };
+
+        assert!(maximum(&under) > 1.0);
+        assert!(maximum(&critical) <= 1.0);
+        assert!(maximum(&over) <= 1.0);
+
+        // The critically damped spring gets there first.
+        assert!(critical.value(0.3) > over.value(0.3));
+    }
+
+    #[test]
+    fn test_initial_velocity() {
+        let spring = Spring::new(1.0, 100.0, 20.0);
+        let moving = spring.with_initial_velocity(10.0);
+
+        assert_eq!(moving.velocity(0.0), 10.0);
+        assert!(moving.value(0.1) > spring.value(0.1));
+
+        // Moving away from the end, it comes back.
+        let backwards = spring.with_initial_velocity(-10.0);
+        assert!(backwards.value(0.05) < 0.0);
+        assert_close(backwards.value(10.0), 1.0);
+    }
+
+    #[test]
+    fn test_step() {
+        let spring = Spring::new(1.0, 150.0, 15.0);
+
+        let (displacement, velocity) = spring.step(-50.0, 0.0, 0.5);
+
+        let mut state = (-50.0, 0.0);
+        for _ in 0..50 {
+            state = spring.step(state.0, state.1, 0.01);
+        }
+
+        assert!((state.0 - displacement).abs() < 0.01);
+        assert!((state.1 - velocity).abs() < 0.01);
+
+        // Without stiffness, it just slows down.
+        let (displacement, velocity) = Spring::new(1.0, 0.0, 2.0).step(0.0, 100.0, 100.0);
+        assert_close(displacement, 50.0);
+        assert_close(velocity, 0.0);
+    }
+
+    #[test]
+    fn test_settling_duration() {
+        let stiff = Spring::new(1.0, 400.0, 40.0);
+        let soft = Spring::new(1.0, 50.0, 14.1);
+        let bouncy = Spring::new(1.0, 400.0, 4.0);
+
+        assert!(stiff.settling_duration() < soft.settling_duration());
+        assert!(stiff.settling_duration() < bouncy.settling_duration());
+
+        for spring in [stiff, soft, bouncy].iter() {
+            let duration = spring.settling_duration();
+            assert!(duration > 0.0);
+            assert!((spring.value(duration) - 1.0).abs() < SETTLED_DISTANCE);
+            assert!((spring.value(duration + 1.0) - 1.0).abs() < SETTLED_DISTANCE);
+        }
+
+        assert_eq!(Spring::new(1.0, 100.0, 0.0).settling_duration(), MAXIMUM_SETTLING_DURATION);
+    }
+}
@@ -0,0 +1,382 @@
+use crate::ui::animation::{frame_timer, AnimationCurve, AnimationRepeat, Completion, Interpolate, Phase, Timing};
+use crate::ui::timer::Timer;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// A value at a point in an `Animation`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keyframe<T> {
+    /// When the value is reached, as a fraction of the animation's duration
+    /// from `0.0` to `1.0`.
+    pub time: f32,
+
+    pub value: T,
+
+    /// The easing from the previous keyframe to this one.
+    pub curve: AnimationCurve
+}
+
+impl<T> Keyframe<T> {
+    pub fn new(time: f32, value: T) -> Keyframe<T> {
+        Keyframe { time: time.clamp(0.0, 1.0), value, curve: AnimationCurve::Linear }
+    }
+
+    pub fn with_curve(self, curve: AnimationCurve) -> Keyframe<T> {
+        Keyframe { curve, ..self }
+    }
+}
+
+/// Called with the new value on every frame of an `Animation`.
+type Tick<T> = Rc<dyn Fn(&T)>;
+
+struct AnimationInner<T> {
+    timing: Timing,
+    keyframes: Vec<Keyframe<T>>,
+    value: RefCell<T>,
+    started_at: Cell<Option<Instant>>,
+    tick: RefCell<Option<Tick<T>>>,
+    completion: RefCell<Option<Completion>>,
+    timer: RefCell<Option<Timer>>
+}
+
+impl<T> Drop for AnimationInner<T> {
+    fn drop(&mut self) {
+        if let Some(timer) = self.timer.get_mut().take() {
+            timer.invalidate();
+        }
+    }
+}
+
+/// Animates any value that can be interpolated, rather than a view's
+/// properties, e.g. for drawing a progress ring or a chart moving between
+/// data sets.
+///
+/// The animation moves through its keyframes on the `RunLoop`, calling the
+/// tick callback with each new value. The callback usually stores the
+/// value and calls `set_needs_display`, for `Behavior::draw` to draw with.
+///
+/// ```ignore
+/// let weak_view = view.downgrade();
+/// let animation = Animation::new(Duration::from_secs(1), 0.0, 0.75, AnimationCurve::EaseInOut)
+///     .with_tick(move |_| {
+///         if let Some(view) = weak_view.upgrade() {
+///             view.set_needs_display();
+///         }
+///     });
+///
+/// animation.start();
+///
+/// // Then when drawing:
+/// let progress = animation.value();
+/// ```
+///
+/// Keep the animation while it plays, e.g. in the view's behavior; it stops
+/// once it's dropped.
+#[derive(Clone)]
+pub struct Animation<T: Interpolate + 'static> {
+    inner: Rc<AnimationInner<T>>
+}
+
+impl<T: Interpolate + 'static> Animation<T> {
+    /// Animate from one value to another.
+    pub fn new(duration: Duration, from: T, to: T, curve: AnimationCurve) -> Animation<T> {
+        Animation::new_keyframes(duration, vec![
+            Keyframe::new(0.0, from),
+            Keyframe::new(1.0, to).with_curve(curve)
+        ])
+    }
+
+    /// Animate through each keyframe in turn. Before the first keyframe the
+    /// value is the first keyframe's, and after the last it's the last's.
+    pub fn new_keyframes(duration: Duration, keyframes: Vec<Keyframe<T>>) -> Animation<T> {
+        let mut keyframes = keyframes;
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        let first = keyframes.first().expect("an animation needs at least one keyframe");
+        let value = first.value.clone();
+
+        Animation {
+            inner: Rc::new(AnimationInner {
+                timing: Timing::new(duration),
+                keyframes,
+                value: RefCell::new(value),
+                started_at: Cell::new(None),
+                tick: RefCell::new(None),
+                completion: RefCell::new(None),
+                timer: RefCell::new(None)
+            })
+        }
+    }
+
+    /// Wait before starting, once started.
+    pub fn with_delay(self, delay: Duration) -> Animation<T> {
+        self.inner.timing.delay.set(delay);
+        self
+    }
+
+    pub fn with_repeat(self, repeat: AnimationRepeat) -> Animation<T> {
+        self.inner.timing.repeat.set(repeat);
+        self
+    }
+
+    /// Play each time forwards and then backwards, ending back at the first
+    /// keyframe.
+    pub fn with_autoreverse(self, autoreverses: bool) -> Animation<T> {
+        self.inner.timing.autoreverses.set(autoreverses);
+        self
+    }
+
+    /// Called with the value on every frame, including the last.
+    pub fn with_tick(self, tick: impl Fn(&T) + 'static) -> Animation<T> {
+        self.inner.tick.replace(Some(Rc::new(tick)));
+        self
+    }
+
+    /// Called once the animation ends, with `true` if it finished or `false`
+    /// if it was cancelled.
+    pub fn with_completion(self, completion: impl FnOnce(bool) + 'static) -> Animation<T> {
+        self.inner.completion.replace(Some(Box::new(completion)));
+        self
+    }
+
+    /// Start playing from the beginning. Does nothing if it's already
+    /// playing.
+    pub fn start(&self) {
+        if self.is_running() {
+            return;
+        }
+
+        self.inner.started_at.set(Some(Instant::now()));
+
+        // Dropping the animation invalidates the timer, so it doesn't keep
+        // the animation alive.
+        let weak_inner = Rc::downgrade(&self.inner);
+        let timer = frame_timer(move || {
+            if let Some(inner) = weak_inner.upgrade() {
+                Animation { inner }.update(Instant::now());
+            }
+        });
+
+        self.inner.timer.replace(Some(timer));
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.inner.timer.borrow().is_some()
+    }
+
+    /// Stop the animation, keeping its current value.
+    pub fn cancel(&self) {
+        self.finish(false);
+    }
+
+    /// The value as of the last frame.
+    pub fn value(&self) -> T {
+        self.inner.value.borrow().clone()
+    }
+
+    /// The value at the given fraction of the duration, from `0.0` to `1.0`.
+    pub fn value_at(&self, time: f32) -> T {
+        let keyframes = &self.inner.keyframes;
+        let first = &keyframes[0];
+        let last = &keyframes[keyframes.len() - 1];
+
+        if time <= first.time {
+            return first.value.clone();
+        }
+
+        if time >= last.time {
+            return last.value.clone();
+        }
+
+        let index = keyframes.iter().rposition(|keyframe| keyframe.time <= time).unwrap_or(0);
+        let (from, to) = (&keyframes[index], &keyframes[index + 1]);
+        let progress = to.curve.value((time - from.time) / (to.time - from.time));
+
+        from.value.interpolate(&to.value, progress)
+    }
+
+    /// Move the value to where it should be at the given time.
+    pub(crate) fn update(&self, now: Instant) {
+        let started_at = match self.inner.started_at.get() {
+            Some(started_at) if self.is_running() => started_at,
+            _ => return
+        };
+
+        match self.inner.timing.phase(started_at, now) {
+            Phase::Waiting => {},
+            Phase::Playing(time) => self.set_value(self.value_at(time)),
+            Phase::Finished(time) => {
+                self.set_value(self.value_at(time));
+                self.finish(true);
+            }
+        }
+    }
+
+    fn set_value(&self, value: T) {
+        self.inner.value.replace(value.clone());
+
+        // Not borrowed while it's called, in case it changes the animation.
+        let tick = self.inner.tick.borrow().clone();
+
+        if let Some(tick) = tick {
+            tick(&value);
+        }
+    }
+
+    fn finish(&self, finished: bool) {
+        let timer = self.inner.timer.borrow_mut().take();
+
+        let timer = match timer {
+            Some(timer) => timer,
+            None => return
+        };
+
+        timer.invalidate();
+
+        let completion = self.inner.completion.borrow_mut().take();
+
+        if let Some(completion) = completion {
+            completion(finished);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::Point;
+
+    fn after(animation: &Animation<f32>, milliseconds: u64) -> Instant {
+        animation.inner.started_at.get().expect("animation wasn't started") + Duration::from_millis(milliseconds)
+    }
+
+    #[test]
+    fn test_value_at() {
+        let animation = Animation::new(Duration::from_secs(1), Point::new(0, 0), Point::new(100, -100), AnimationCurve::Linear);
+
+        assert_eq!(animation.value(), Point::new(0, 0));
+        assert_eq!(animation.value_at(-1.0), Point::new(0, 0));
+        assert_eq!(animation.value_at(0.25), Point::new(25, -25));
+        assert_eq!(animation.value_at(2.0), Point::new(100, -100));
+    }
+
+    #[test]
+    fn test_keyframes() {
+        let animation = Animation::new_keyframes(Duration::from_secs(1), vec![
+            Keyframe::new(1.0, 0.0),
+            Keyframe::new(0.0, 0.0),
+            Keyframe::new(0.5, 100.0).with_curve(AnimationCurve::EaseIn)
+        ]);
+
+        // Sorted by time, with each segment eased by its own curve.
+        assert_eq!(animation.value_at(0.25), 100.0 * AnimationCurve::EaseIn.value(0.5));
+        assert_eq!(animation.value_at(0.5), 100.0);
+        assert_eq!(animation.value_at(0.75), 50.0);
+
+        // Holds the first value until the first keyframe.
+        let animation = Animation::new_keyframes(Duration::from_secs(1), vec![
+            Keyframe::new(0.5, 10.0),
+            Keyframe::new(1.0, 20.0)
+        ]);
+
+        assert_eq!(animation.value_at(0.25), 10.0);
+        assert_eq!(animation.value_at(0.75), 15.0);
+    }
+
+    #[test]
+    fn test_ticks_and_completion() {
+        let ticks = Rc::new(RefCell::new(Vec::new()));
+        let finished = Rc::new(Cell::new(None));
+
+        let tick_values = ticks.clone();
+        let completion_finished = finished.clone();
+        let animation = Animation::new(Duration::from_millis(100), 0.0, 1.0, AnimationCurve::Linear)
+            .with_delay(Duration::from_millis(100))
+            .with_tick(move |value| tick_values.borrow_mut().push(*value))
+            .with_completion(move |finished| completion_finished.set(Some(finished)));
+
+        // Nothing happens until it's started.
+        animation.update(Instant::now() + Duration::from_secs(1));
+        assert!(ticks.borrow().is_empty());
+
+        animation.start();
+        assert!(animation.is_running());
+
+        animation.update(after(&animation, 50));
+        animation.update(after(&animation, 150));
+        animation.update(after(&animation, 250));
+        animation.update(after(&animation, 300));
+
+        assert_eq!(*ticks.borrow(), vec![0.5, 1.0]);
+        assert_eq!(animation.value(), 1.0);
+        assert_eq!(finished.get(), Some(true));
+        assert!(!animation.is_running());
+    }
+
+    #[test]
+    fn test_repeat_and_cancel() {
+        let finished = Rc::new(Cell::new(None));
+
+        let completion_finished = finished.clone();
+        let animation = Animation::new(Duration::from_millis(100), 0.0, 1.0, AnimationCurve::Linear)
+            .with_repeat(AnimationRepeat::Forever)
+            .with_autoreverse(true)
+            .with_completion(move |finished| completion_finished.set(Some(finished)));
+
+        animation.start();
+
+        animation.update(after(&animation, 1025));
+        assert_eq!(animation.value(), 0.25);
+
+        animation.update(after(&animation, 1175));
+        assert_eq!(animation.value(), 0.25);
+
+        animation.cancel();
+        animation.update(after(&animation, 1250));
+        assert_eq!(animation.value(), 0.25);
+        assert_eq!(finished.get(), Some(false));
+    }
+
+    #[test]
+    fn test_dropping_stops_the_timer() {
+        let animation = Animation::new(Duration::from_millis(100), 0.0, 1.0, AnimationCurve::Linear)
+            .with_repeat(AnimationRepeat::Forever);
+
+        animation.start();
+        let timer = animation.inner.timer.borrow().clone().expect("animation has no timer");
+        assert!(timer.is_valid());
+
+        drop(animation);
+        assert!(!timer.is_valid());
+    }
+
+    #[test]
+    fn test_tick_changing_the_animation() {
+        let ticks = Rc::new(Cell::new(0));
+        let running: Rc<RefCell<Option<Animation<f32>>>> = Rc::new(RefCell::new(None));
+
+        // The first tick swaps in another, then cancels the animation.
+        let tick_running = running.clone();
+        let tick_count = ticks.clone();
+        let animation = Animation::new(Duration::from_millis(100), 0.0, 1.0, AnimationCurve::Linear)
+            .with_tick(move |_| {
+                if let Some(animation) = tick_running.borrow().clone() {
+                    let tick_count = tick_count.clone();
+                    let animation = animation.with_tick(move |_| tick_count.set(tick_count.get() + 1));
+                    animation.cancel();
+                }
+            });
+
+        running.replace(Some(animation.clone()));
+        animation.start();
+
+        animation.update(after(&animation, 50));
+        assert!(!animation.is_running());
+
+        animation.set_value(1.0);
+        assert_eq!(ticks.get(), 1);
+
+        running.replace(None);
+    }
+}
@@ -3,17 +3,39 @@ use crate::macros::*;
 use crate::ui::touch::Touch;
 use crate::ui::run_loop::RunLoop;
 use crate::ui::gesture::recognizer::Recognizer;
-use std::rc::Weak;
+use crate::ui::timer::Timer;
+use crate::ui::ApplicationDelegate;
+use std::rc::{Rc, Weak};
+use std::time::Duration;
+
+/// How long to wait after a window loses focus before deciding the
+/// application itself is no longer active. Focus moving between two of our
+/// own windows arrives as a focus lost event followed by a focus gained event.
+const RESIGN_ACTIVE_DELAY_MS: u64 = 100;
 
 singleton!(
     Application,
     key_window_index: None,
-    windows: Vec::new()
+    windows: Vec::new(),
+    terminates_after_last_window_closed: true,
+    delegate: None,
+    active: false
 );
 
 pub struct Application {
     key_window_index: Option<usize>,
-    pub(crate) windows: Vec<Window>
+    pub(crate) windows: Vec<Window>,
+
+    /// Set by `ApplicationMain::launch`.
+    delegate: Option<Rc<dyn ApplicationDelegate>>,
+
+    /// Whether one of the application's windows has the OS focus.
+    active: bool,
+
+    /// Whether the application exits once its last window has closed.
+    ///
+    /// Defaults to `true`.
+    terminates_after_last_window_closed: bool
 }
 
 impl<'a> Application {
@@ -21,6 +43,40 @@ impl<'a> Application {
         self.windows.push(window);
     }
 
+    /// Called by `Window::close`.
+    pub(crate) fn remove_window(&mut self, window: &Window) {
+        let position = match self.windows.iter().position(|v| v == window) {
+            Some(position) => position,
+            None => return
+        };
+
+        self.windows.remove(position);
+
+        // Keep the key window index pointing at the same window.
+        if let Some(index) = self.key_window_index {
+            if index == position {
+                self.key_window_index = None;
+            } else if index > position {
+                self.key_window_index = Some(index - 1);
+            }
+        }
+
+        if self.windows.is_empty() && self.terminates_after_last_window_closed {
+            self.exit();
+        }
+    }
+
+    pub fn terminates_after_last_window_closed(&self) -> bool {
+        self.terminates_after_last_window_closed
+    }
+
+    /// Set whether the application should exit once its last window has
+    /// closed. Set to `false` for applications that keep running without any
+    /// windows open.
+    pub fn set_terminates_after_last_window_closed(&mut self, value: bool) {
+        self.terminates_after_last_window_closed = value;
+    }
+
     pub fn get_key_window(&self) -> WeakView {
         if let Some(index) = self.key_window_index {
             self.windows[index].downgrade()
@@ -33,11 +89,75 @@ impl<'a> Application {
         &self.windows
     }
 
+    /// Sets the key window without notifying any delegates. Prefer
+    /// `Window::make_key_window`.
     pub fn set_key_window(&mut self, window: &Window) {
         let position = self.windows.iter().position(|v| v == window).expect("window not found in application");
         self.key_window_index = Some(position);
     }
 
+    pub(crate) fn clear_key_window(&mut self) {
+        self.key_window_index = None;
+    }
+
+    /// Whether one of the application's windows has the OS focus.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub(crate) fn set_delegate(&mut self, delegate: Rc<dyn ApplicationDelegate>) {
+        self.delegate = Some(delegate);
+    }
+
+    /// Marks the application as active, notifying the delegate if it wasn't
+    /// already.
+    pub(crate) fn did_become_active() {
+        let delegate = {
+            let mut application = Application::borrow_mut();
+            if application.active {
+                return;
+            }
+            application.active = true;
+            application.delegate.clone()
+        };
+
+        if let Some(delegate) = delegate {
+            delegate.application_did_become_active();
+        }
+    }
+
+    /// Called by the event loop when a window loses the OS focus. If no other
+    /// window of ours picks up the focus shortly after, the application is no
+    /// longer active and the delegate is notified.
+    pub(crate) fn window_did_lose_focus() {
+        let timer = Timer::new_once_delayed(Duration::from_millis(RESIGN_ACTIVE_DELAY_MS), || {
+            let delegate = {
+                let mut application = Application::borrow_mut();
+                if !application.active || application.key_window_index.is_some() {
+                    return;
+                }
+                application.active = false;
+                application.delegate.clone()
+            };
+
+            if let Some(delegate) = delegate {
+                delegate.application_did_resign_active();
+            }
+        });
+
+        let run_loop = RunLoop::borrow();
+        run_loop.add_timer(timer);
+    }
+
+    /// SDL finger events don't say which window they're for, so they go to
+    /// the key window, or the first window if none is key.
+    pub(crate) fn window_for_finger_events(&self) -> Option<&Window> {
+        match self.key_window_index {
+            Some(index) => self.windows.get(index),
+            None => self.windows.first()
+        }
+    }
+
     pub(crate) fn get_window(&self, context_id: u32) -> Option<&Window> {
         for window in self.windows.iter() {
             if window.context().id() == context_id {
@@ -2,16 +2,26 @@ use crate::ui::timer::Timer;
 use crate::ui::event_loop;
 use std::time::Duration;
 use crate::ui::run_loop::RunLoop;
+use crate::ui::application::Application;
+use std::rc::Rc;
 
 pub trait ApplicationDelegate {
     fn application_will_finish_launching(&self) {}
     fn application_did_finish_launching(&self) {}
+
+    /// Called when the application launches, and again whenever one of its
+    /// windows regains the OS focus after the application was inactive.
     fn application_did_become_active(&self) {}
+
+    /// Called when none of the application's windows have the OS focus any
+    /// more, e.g. the user switched to another application.
+    fn application_did_resign_active(&self) {}
+
     fn application_will_terminate(&self) {}
 }
 
 pub struct ApplicationMain {
-    delegate: Box<dyn ApplicationDelegate>
+    delegate: Rc<dyn ApplicationDelegate>
 }
 
 #[cfg(target_os = "macos")]
@@ -23,7 +33,7 @@ extern "C" {
 impl ApplicationMain {
     pub fn new<T>(delegate: T) -> ApplicationMain where T: ApplicationDelegate + 'static {
         ApplicationMain {
-            delegate: Box::new(delegate)
+            delegate: Rc::new(delegate)
         }
     }
 
@@ -34,6 +44,20 @@ impl ApplicationMain {
         #[cfg(target_os = "emscripten")]
         let _ = sdl2::hint::set("SDL_EMSCRIPTEN_ASYNCIFY","1");
 
+        // Closing windows is handled per-window (see `Window::perform_close`),
+        // so SDL shouldn't also send a quit event when the last one closes.
+        let _ = sdl2::hint::set("SDL_QUIT_ON_LAST_WINDOW_CLOSE", "0");
+
+        // Touchscreens are handled through the finger events, so SDL shouldn't
+        // also pretend fingers are the mouse (or the mouse is a finger).
+        let _ = sdl2::hint::set("SDL_TOUCH_MOUSE_EVENTS", "0");
+        let _ = sdl2::hint::set("SDL_MOUSE_TOUCH_EVENTS", "0");
+
+        {
+            let mut application = Application::borrow_mut();
+            application.set_delegate(self.delegate.clone());
+        }
+
         self.delegate.application_will_finish_launching();
         self.delegate.application_did_finish_launching();
 
@@ -53,7 +77,7 @@ impl ApplicationMain {
             run_loop.add_timer(timer);
         }
 
-        self.delegate.application_did_become_active();
+        Application::did_become_active();
         run_loop.run();
 
         self.delegate.application_will_terminate();
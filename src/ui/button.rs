@@ -1,16 +1,21 @@
 use crate::macros::*;
 use crate::ui::view::DefaultBehavior;
-use crate::ui::{Touch, Label, Color};
-use crate::graphics::Rectangle;
+use crate::ui::{Touch, Label, Color, Cursor};
+use crate::graphics::{Point, Rectangle, Size};
 use std::cell::{Cell, RefCell};
 use crate::text::{HorizontalAlignment, VerticalAlignment};
 
 static DEFAULT_COLOR_NORMAL: Color = Color { red: 2, green: 117, blue: 227, alpha: 255 };
 static DEFAULT_COLOR_PRESSED: Color = Color { red: 64, green: 155, blue: 255, alpha: 255 };
+static DEFAULT_COLOR_HOVERED: Color = Color { red: 1, green: 95, blue: 186, alpha: 255 };
+
+/// Space around the text in the button's intrinsic content size.
+static CONTENT_PADDING: Size<u32> = Size { width: 12, height: 6 };
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum State {
     Normal,
+    Hovered,
     Pressed
 }
 
@@ -19,6 +24,8 @@ custom_view!(
 
     struct ButtonBehavior {
         state: Cell<State>,
+        hovered: Cell<bool>,
+        hovered_text_color: RefCell<Color>,
         pressed_text_color: RefCell<Color>,
         last_normal_text_color: RefCell<Color>,
         action: Box<dyn Fn() -> ()>
@@ -30,6 +37,8 @@ custom_view!(
             let button = Button::new_all(
                 frame.clone(),
                 state,
+                Cell::new(false),
+                RefCell::new(DEFAULT_COLOR_HOVERED.clone()),
                 RefCell::new(DEFAULT_COLOR_PRESSED.clone()),
                 RefCell::new(DEFAULT_COLOR_NORMAL.clone()),
                 Box::new(action)
@@ -59,9 +68,64 @@ custom_view!(
 
             behavior.pressed_text_color.replace(color);
         }
+
+        pub fn set_hovered_text_color(&self, color: Color) {
+            let behavior = self.behavior();
+
+            behavior.hovered_text_color.replace(color);
+        }
+
+        pub fn state(&self) -> State {
+            self.behavior().state.get()
+        }
     }
 
     impl Behavior {
+        /// The size of the text, plus some padding.
+        fn intrinsic_content_size(&self) -> Option<Size<u32>> {
+            let text_size = self.view_type().label().intrinsic_content_size()?;
+
+            Some(Size {
+                width: text_size.width + CONTENT_PADDING.width * 2,
+                height: text_size.height + CONTENT_PADDING.height * 2
+            })
+        }
+
+        fn size_that_fits(&self, size: &Size<u32>) -> Size<u32> {
+            let padding = Size::new(CONTENT_PADDING.width * 2, CONTENT_PADDING.height * 2);
+            let available = Size::new(size.width.saturating_sub(padding.width), size.height.saturating_sub(padding.height));
+            let text_size = self.view_type().label().size_that_fits(&available);
+
+            Size::new(text_size.width + padding.width, text_size.height + padding.height)
+        }
+
+        /// Keep the label filling the button.
+        fn layout_subviews(&self) {
+            let button = self.view_type();
+            let size = button.frame().size;
+            button.label().set_frame(Rectangle::new(0, 0, size.width, size.height));
+        }
+
+        fn cursor_for_point(&self, _point: &Point<i32>) -> Option<Cursor> {
+            Some(Cursor::PointingHand)
+        }
+
+        fn mouse_entered(&self) {
+            self.hovered.set(true);
+
+            if self.state.get() == State::Normal {
+                self.set_state(State::Hovered);
+            }
+        }
+
+        fn mouse_exited(&self) {
+            self.hovered.set(false);
+
+            if self.state.get() == State::Hovered {
+                self.set_state(State::Normal);
+            }
+        }
+
         fn touches_began(&self, _touches: &Vec<Touch>) {
             self.set_state(State::Pressed);
         }
@@ -79,7 +143,11 @@ custom_view!(
                 }
             }
 
-            self.set_state(State::Normal);
+            self.set_state(self.resting_state());
+        }
+
+        fn touches_cancelled(&self, _touches: &Vec<Touch>) {
+            self.set_state(self.resting_state());
         }
 
         fn touches_moved(&self, touches: &Vec<Touch>) {
@@ -92,7 +160,7 @@ custom_view!(
                 if view.bounds().contains(&position) {
                     self.set_state(State::Pressed);
                 } else {
-                    self.set_state(State::Normal);
+                    self.set_state(self.resting_state());
                 }
             }
         }
@@ -100,19 +168,36 @@ custom_view!(
 );
 
 impl ButtonBehavior {
+    /// The state when not pressed, depending on whether the mouse is over
+    /// the button.
+    fn resting_state(&self) -> State {
+        if self.hovered.get() {
+            State::Hovered
+        } else {
+            State::Normal
+        }
+    }
+
     fn set_state(&self, state: State) {
         let button = self.view_type();
+        let previous_state = self.state.get();
 
-        if self.state.get() == state {
+        if previous_state == state {
             return;
         }
 
+        if previous_state == State::Normal {
+            self.last_normal_text_color.replace(button.label().text_color());
+        }
+
         match state {
             State::Normal => {
                 button.label().set_text_color(self.last_normal_text_color.borrow().clone());
             },
+            State::Hovered => {
+                button.label().set_text_color(self.hovered_text_color.borrow().clone());
+            },
             State::Pressed => {
-                self.last_normal_text_color.replace(button.label().text_color());
                 button.label().set_text_color(self.pressed_text_color.borrow().clone());
             }
         }
@@ -124,6 +209,7 @@ impl ButtonBehavior {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ui::view::Behavior;
 
     #[test]
     fn test_button_new() {
@@ -136,4 +222,53 @@ mod tests {
         assert_eq!(button.frame(), Rectangle::new(0, 0, 100, 100));
         assert_eq!(button.label().copy_text(), String::from("Test"));
     }
+
+    #[test]
+    fn test_button_cursor() {
+        let button = Button::new(
+            Rectangle::new(0, 0, 100, 100),
+            "Test",
+            Box::new(|| {})
+        );
+
+        assert_eq!(button.cursor_for_point(&Point::new(10, 10)), Some(Cursor::PointingHand));
+    }
+
+    #[test]
+    fn test_button_hover() {
+        let button = Button::new(
+            Rectangle::new(0, 0, 100, 100),
+            "Test",
+            Box::new(|| {})
+        );
+
+        button.mouse_entered();
+        assert_eq!(button.state(), State::Hovered);
+        assert_eq!(button.label().text_color(), DEFAULT_COLOR_HOVERED);
+
+        button.behavior().touches_began(&Vec::new());
+        assert_eq!(button.state(), State::Pressed);
+
+        // Still over the button once the press is over.
+        button.behavior().touches_cancelled(&Vec::new());
+        assert_eq!(button.state(), State::Hovered);
+
+        button.mouse_exited();
+        assert_eq!(button.state(), State::Normal);
+        assert_eq!(button.label().text_color(), DEFAULT_COLOR_NORMAL);
+    }
+
+    #[test]
+    fn test_button_label_follows_frame() {
+        let button = Button::new(
+            Rectangle::new(0, 0, 100, 100),
+            "Test",
+            Box::new(|| {})
+        );
+
+        button.set_frame(Rectangle::new(10, 10, 150, 40));
+        button.layout_if_needed();
+
+        assert_eq!(button.label().frame(), Rectangle::new(0, 0, 150, 40));
+    }
 }
@@ -0,0 +1,159 @@
+use crate::graphics::{Image, Point, SdlContainer};
+use crate::macros::*;
+use sdl2::mouse::SystemCursor;
+use std::rc::Rc;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+fn next_id() -> usize {
+    static COUNTER:AtomicUsize = AtomicUsize::new(1);
+    COUNTER.fetch_add(1, Ordering::Relaxed)
+}
+
+/// The mouse cursor image.
+///
+/// Views pick the cursor shown while the mouse is over them by implementing
+/// `Behavior::cursor_for_point`. E.g. `TextField` shows the `IBeam`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cursor {
+    Arrow,
+    IBeam,
+    PointingHand,
+    Crosshair,
+    ResizeLeftRight,
+    ResizeUpDown,
+    ResizeTopLeftBottomRight,
+    ResizeTopRightBottomLeft,
+    ResizeAll,
+    NotAllowed,
+    Wait,
+    Custom(CustomCursor)
+}
+
+/// A cursor created from an `Image`. See `Cursor::from_image`.
+#[derive(Clone)]
+pub struct CustomCursor {
+    id: usize,
+    cursor: Rc<sdl2::mouse::Cursor>
+}
+
+impl PartialEq for CustomCursor {
+    fn eq(&self, rhs: &CustomCursor) -> bool {
+        self.id == rhs.id
+    }
+}
+
+impl std::fmt::Debug for CustomCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CustomCursor")
+         .field(&self.id)
+         .finish()
+    }
+}
+
+impl Cursor {
+    /// Creates a cursor that displays the given image.
+    ///
+    /// The `hotspot` is the point within the image (in points, from the top
+    /// left) that is the actual position of the mouse; e.g. the tip of an
+    /// arrow.
+    pub fn from_image(image: &Image, hotspot: Point<i32>) -> Cursor {
+        let scale = image.scale() as i32;
+        let cursor = sdl2::mouse::Cursor::from_surface(image.surface(), hotspot.x * scale, hotspot.y * scale)
+            .expect("failed to create cursor from image");
+
+        Cursor::Custom(CustomCursor {
+            id: next_id(),
+            cursor: Rc::new(cursor)
+        })
+    }
+
+    /// Show or hide the mouse cursor entirely.
+    pub fn set_visible(visible: bool) {
+        let sdl = SdlContainer::borrow();
+        sdl.mouse().show_cursor(visible);
+    }
+
+    pub fn is_visible() -> bool {
+        let sdl = SdlContainer::borrow();
+        sdl.mouse().is_cursor_showing()
+    }
+
+    /// The cursor currently being shown.
+    pub fn current() -> Cursor {
+        let active_cursor = ActiveCursor::borrow();
+        active_cursor.cursor.clone()
+    }
+
+    /// Change the cursor being shown. Does nothing if it's already showing.
+    ///
+    /// Called by the event loop as the mouse moves over views.
+    pub(crate) fn activate(&self) {
+        let mut active_cursor = ActiveCursor::borrow_mut();
+
+        if active_cursor.cursor == *self && active_cursor.sdl_cursor.is_some() {
+            return;
+        }
+
+        let sdl_cursor = match self {
+            Cursor::Custom(custom) => custom.cursor.clone(),
+            _ => {
+                let system_cursor = self.system_cursor().expect("only custom cursors have no system cursor");
+                let cursor = sdl2::mouse::Cursor::from_system(system_cursor).expect("failed to create system cursor");
+                Rc::new(cursor)
+            }
+        };
+
+        sdl_cursor.set();
+
+        // The SDL cursor must be kept alive for as long as it is shown.
+        active_cursor.sdl_cursor = Some(sdl_cursor);
+        active_cursor.cursor = self.clone();
+    }
+
+    fn system_cursor(&self) -> Option<SystemCursor> {
+        match self {
+            Cursor::Arrow => Some(SystemCursor::Arrow),
+            Cursor::IBeam => Some(SystemCursor::IBeam),
+            Cursor::PointingHand => Some(SystemCursor::Hand),
+            Cursor::Crosshair => Some(SystemCursor::Crosshair),
+            Cursor::ResizeLeftRight => Some(SystemCursor::SizeWE),
+            Cursor::ResizeUpDown => Some(SystemCursor::SizeNS),
+            Cursor::ResizeTopLeftBottomRight => Some(SystemCursor::SizeNWSE),
+            Cursor::ResizeTopRightBottomLeft => Some(SystemCursor::SizeNESW),
+            Cursor::ResizeAll => Some(SystemCursor::SizeAll),
+            Cursor::NotAllowed => Some(SystemCursor::No),
+            Cursor::Wait => Some(SystemCursor::Wait),
+            Cursor::Custom(_) => None
+        }
+    }
+}
+
+singleton!(ActiveCursor, cursor: Cursor::Arrow, sdl_cursor: None);
+
+struct ActiveCursor {
+    cursor: Cursor,
+    sdl_cursor: Option<Rc<sdl2::mouse::Cursor>>
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eq() {
+        assert_eq!(Cursor::Arrow, Cursor::Arrow);
+        assert_ne!(Cursor::Arrow, Cursor::IBeam);
+    }
+
+    #[test]
+    fn test_system_cursor() {
+        assert_eq!(Cursor::IBeam.system_cursor(), Some(SystemCursor::IBeam));
+        assert_eq!(Cursor::PointingHand.system_cursor(), Some(SystemCursor::Hand));
+        assert_eq!(Cursor::ResizeLeftRight.system_cursor(), Some(SystemCursor::SizeWE));
+    }
+
+    #[test]
+    fn test_current_defaults_to_arrow() {
+        assert_eq!(Cursor::current(), Cursor::Arrow);
+    }
+}
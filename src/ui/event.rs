@@ -1,8 +1,9 @@
-use crate::ui::Touch;
+use crate::ui::{Touch, Window};
 use crate::macros::*;
 use std::rc::Rc;
 use std::cell::{Ref, RefCell};
-use crate::ui::touch::TouchPhase;
+use std::collections::HashMap;
+use crate::ui::touch::{TouchPhase, MouseButton};
 use crate::graphics::Point;
 use crate::ui::key::Key;
 use crate::ui::press::Press;
@@ -111,12 +112,38 @@ impl Clone for PressEvent {
     }
 }
 
-singleton!(EventArena, touch_event: None, scroll_event: None, press_events: Vec::new());
+singleton!(EventArena + Default);
 
 pub(crate) struct EventArena {
     touch_event: Option<TouchEvent>,
     scroll_event: Option<ScrollEvent>,
-    press_events: Vec<PressEvent>
+    press_events: Vec<PressEvent>,
+
+    /// Touches for mouse buttons other than the left button, which are
+    /// delivered through `right_mouse_down`, `other_mouse_down`, etc. rather
+    /// than as part of the `touch_event`.
+    other_mouse_touches: Vec<Touch>,
+
+    /// Maps SDL's (touch device, finger) pairs to `Touch` ids for the fingers
+    /// currently down.
+    finger_touch_ids: HashMap<(i64, i64), usize>,
+
+    /// The next id to give a finger touch. Starts at `1`, as `0` is the
+    /// mouse.
+    next_finger_touch_id: usize
+}
+
+impl Default for EventArena {
+    fn default() -> Self {
+        EventArena {
+            touch_event: None,
+            scroll_event: None,
+            press_events: Vec::new(),
+            other_mouse_touches: Vec::new(),
+            finger_touch_ids: HashMap::new(),
+            next_finger_touch_id: 1
+        }
+    }
 }
 
 impl EventArena {
@@ -155,6 +182,31 @@ impl EventArena {
         event
     }
 
+    /// The in-progress (or just ended) touch with the given id.
+    pub(crate) fn touch(&mut self, touch_id: usize) -> Option<Touch> {
+        let event = self.touch_event();
+        let touches = event.touches();
+        touches.iter().find(|t| t.id() == touch_id).cloned()
+    }
+
+    /// Gives a finger that just went down a new touch id.
+    pub(crate) fn finger_began(&mut self, device_id: i64, finger_id: i64) -> usize {
+        let touch_id = self.next_finger_touch_id;
+        self.next_finger_touch_id += 1;
+        self.finger_touch_ids.insert((device_id, finger_id), touch_id);
+        touch_id
+    }
+
+    /// The touch id of a finger that is down, if it's known.
+    pub(crate) fn finger_touch_id(&self, device_id: i64, finger_id: i64) -> Option<usize> {
+        self.finger_touch_ids.get(&(device_id, finger_id)).copied()
+    }
+
+    /// Forgets a finger that was lifted, returning its touch id.
+    pub(crate) fn finger_ended(&mut self, device_id: i64, finger_id: i64) -> Option<usize> {
+        self.finger_touch_ids.remove(&(device_id, finger_id))
+    }
+
     pub(crate) fn touch_moved(&mut self, touch_id: usize, position: Point<i32>) {
         let event = self.touch_event();
 
@@ -187,17 +239,65 @@ impl EventArena {
         panic!("Touch just ended but it doesn't exist");
     }
 
+    /// Updates the pointer position while no touch is in progress, i.e. the
+    /// mouse moving without a button pressed. Scrolls start from this
+    /// position.
+    pub(crate) fn hover_moved(&mut self, position: Point<i32>) {
+        let scroll_event = self.scroll_event();
+        let touch = scroll_event.touch();
+        touch.set_position(position);
+    }
+
+    pub(crate) fn other_mouse_began(&mut self, touch: Touch) {
+        self.other_mouse_touches.retain(|t| t.button() != touch.button());
+        self.other_mouse_touches.push(touch);
+    }
+
+    /// Ends and returns the touch for the given (non-left) mouse button, if
+    /// it was pressed.
+    pub(crate) fn other_mouse_ended(&mut self, button: MouseButton, position: Point<i32>) -> Option<Touch> {
+        let index = self.other_mouse_touches.iter().position(|t| t.button() == Some(button))?;
+        let mut touch = self.other_mouse_touches.remove(index);
+        touch.set_phase(TouchPhase::Ended);
+        touch.set_position(position);
+        Some(touch)
+    }
+
     pub(crate) fn scroll_did_translate(&mut self, translation: Point<i32>) {
         let event = self.scroll_event();
         event.inner.borrow_mut().translation = translation;
     }
 
-    /// Clears out any touches that have ended.
+    /// Cancels all in-progress touches in the given window, returning them.
+    ///
+    /// Any fingers for those touches are forgotten, so their later motion is
+    /// ignored.
+    pub(crate) fn cancel_touches_in_window(&mut self, window: &Window) -> Vec<Touch> {
+        let event = self.touch_event();
+        let mut cancelled = Vec::new();
+
+        for t in event.inner.borrow_mut().touches.iter_mut() {
+            if t.phase() == TouchPhase::Ended || t.phase() == TouchPhase::Cancelled {
+                continue;
+            }
+
+            if t.window().as_ref() == Some(window) {
+                t.set_phase(TouchPhase::Cancelled);
+                cancelled.push(t.clone());
+            }
+        }
+
+        self.finger_touch_ids.retain(|_, id| !cancelled.iter().any(|t| t.id() == *id));
+
+        cancelled
+    }
+
+    /// Clears out any touches that have ended or were cancelled.
     ///
     /// This is called by the start of the event loop.
     pub(crate) fn cleanup_ended_touches(&mut self) {
         let event = self.touch_event();
-        event.inner.borrow_mut().touches.retain(|t| t.phase() != TouchPhase::Ended);
+        event.inner.borrow_mut().touches.retain(|t| t.phase() != TouchPhase::Ended && t.phase() != TouchPhase::Cancelled);
     }
 
     /// Updates any touches that are set as "Began" to "Stationary".
@@ -236,14 +336,14 @@ mod tests {
 
     #[test]
     fn test_event_arena_touch_event() {
-        let mut arena = EventArena { touch_event: None, scroll_event: None, press_events: Vec::new() };
+        let mut arena = EventArena::default();
         let touch_event = arena.touch_event();
         assert_eq!(touch_event.touches().len(), 0);
     }
 
     #[test]
     fn test_event_arena_touch_began() {
-        let mut arena = EventArena { touch_event: None, scroll_event: None, press_events: Vec::new() };
+        let mut arena = EventArena::default();
         let touch = Touch::new(0, Point::new(0, 0));
         arena.touch_began(touch);
         let touch_event = arena.touch_event();
@@ -253,7 +353,7 @@ mod tests {
     #[test]
     #[should_panic]
     fn test_event_arena_touch_began_twice() {
-        let mut arena = EventArena { touch_event: None, scroll_event: None, press_events: Vec::new() };
+        let mut arena = EventArena::default();
         let touch = Touch::new(0, Point::new(0, 0));
         arena.touch_began(touch);
         let touch = Touch::new(0, Point::new(0, 0));
@@ -262,7 +362,7 @@ mod tests {
 
     #[test]
     fn test_event_arena_touch_moved() {
-        let mut arena = EventArena { touch_event: None, scroll_event: None, press_events: Vec::new() };
+        let mut arena = EventArena::default();
         let touch = Touch::new(0, Point::new(0, 0));
         arena.touch_began(touch);
         arena.touch_moved(0, Point::new(10, 50));
@@ -273,7 +373,7 @@ mod tests {
 
     #[test]
     fn test_event_arena_touch_ended() {
-        let mut arena = EventArena { touch_event: None, scroll_event: None, press_events: Vec::new() };
+        let mut arena = EventArena::default();
         let touch = Touch::new(0, Point::new(0, 0));
         arena.touch_began(touch);
         arena.touch_ended(0, Point::new(10, 50));
@@ -286,13 +386,13 @@ mod tests {
     #[test]
     #[should_panic]
     fn test_event_arena_touch_ended_but_didnt_exist() {
-        let mut arena = EventArena { touch_event: None, scroll_event: None, press_events: Vec::new() };
+        let mut arena = EventArena::default();
         arena.touch_ended(0, Point::new(10, 50));
     }
 
     #[test]
     fn test_event_arena_cleanup_ended_touches() {
-        let mut arena = EventArena { touch_event: None, scroll_event: None, press_events: Vec::new() };
+        let mut arena = EventArena::default();
         let touch = Touch::new(0, Point::new(0, 0));
         arena.touch_began(touch);
         let touch = Touch::new(1, Point::new(0, 0));
@@ -305,9 +405,66 @@ mod tests {
         assert_eq!(arena.touch_event().touches().len(), 1);
     }
 
+    #[test]
+    fn test_event_arena_cleanup_cancelled_touches() {
+        let mut arena = EventArena::default();
+        let mut touch = Touch::new(0, Point::new(0, 0));
+        arena.touch_began(touch.clone());
+        touch.set_phase(TouchPhase::Cancelled);
+        arena.touch_moved(0, Point::new(10, 50));
+        assert_eq!(arena.touch(0).unwrap().phase(), TouchPhase::Cancelled);
+        arena.cleanup_ended_touches();
+        assert_eq!(arena.touch_event().touches().len(), 0);
+    }
+
+    #[test]
+    fn test_event_arena_other_mouse() {
+        let mut arena = EventArena::default();
+        let touch = Touch::new_with_mouse_button(0, Point::new(0, 0), MouseButton::Right, 1);
+        arena.other_mouse_began(touch);
+        assert_eq!(arena.touch_event().touches().len(), 0);
+
+        assert!(arena.other_mouse_ended(MouseButton::Middle, Point::new(10, 50)).is_none());
+
+        let ended = arena.other_mouse_ended(MouseButton::Right, Point::new(10, 50)).unwrap();
+        assert_eq!(ended.phase(), TouchPhase::Ended);
+        assert_eq!(ended.position(), Point::new(10, 50));
+        assert!(arena.other_mouse_ended(MouseButton::Right, Point::new(10, 50)).is_none());
+    }
+
+    #[test]
+    fn test_event_arena_hover_moved() {
+        let mut arena = EventArena::default();
+        arena.hover_moved(Point::new(10, 50));
+        assert_eq!(arena.touch_event().touches().len(), 0);
+        assert_eq!(arena.scroll_event().touch().position(), Point::new(10, 50));
+    }
+
+    #[test]
+    fn test_event_arena_fingers() {
+        let mut arena = EventArena::default();
+        let first = arena.finger_began(7, 0);
+        let second = arena.finger_began(7, 1);
+        assert_eq!(first, 1);
+        assert_eq!(second, 2);
+        assert_eq!(arena.finger_touch_id(7, 1), Some(2));
+        assert_eq!(arena.finger_touch_id(8, 1), None);
+
+        arena.touch_began(Touch::new(first, Point::new(0, 0)));
+        arena.touch_began(Touch::new(second, Point::new(5, 5)));
+        arena.touch_moved(second, Point::new(10, 50));
+        assert_eq!(arena.touch(first).unwrap().position(), Point::new(0, 0));
+        assert_eq!(arena.touch(second).unwrap().position(), Point::new(10, 50));
+        assert!(arena.touch(0).is_none());
+
+        assert_eq!(arena.finger_ended(7, 0), Some(1));
+        assert_eq!(arena.finger_ended(7, 0), None);
+        assert_eq!(arena.finger_began(7, 0), 3);
+    }
+
     #[test]
     fn test_event_arena_began_to_stationary() {
-        let mut arena = EventArena { touch_event: None, scroll_event: None, press_events: Vec::new() };
+        let mut arena = EventArena::default();
         let touch = Touch::new(0, Point::new(0, 0));
         arena.touch_began(touch);
         assert_eq!(arena.touch_event().touches().len(), 1);
@@ -1,9 +1,19 @@
 use crate::ui::application::Application;
-use crate::ui::touch::Touch;
+use crate::ui::touch::{Touch, TouchPhase, MouseButton};
+use crate::ui::gesture::recognizer::{Recognizer, RecognizerState, Arbitration, resolve_waiting};
 use crate::graphics::{Point, SdlContainer};
-use crate::ui::event::EventArena;
+use crate::ui::event::{EventArena, TouchEvent};
+use crate::ui::{View, Window};
+use std::rc::Rc;
 use crate::ui::key::{Key, ModifierFlag};
 
+/// How much one notch of the wheel zooms by while holding ctrl.
+const WHEEL_ZOOM_FACTOR: f32 = 1.1;
+
+/// Converts the change in distance between fingers on a trackpad (where the
+/// trackpad's width is `1.0`) to a change in scale.
+const TRACKPAD_PINCH_SENSITIVITY: f32 = 4.0;
+
 pub(crate) fn update() {
     let sdl = SdlContainer::borrow();
     let mut event_pump = sdl.event_pump().expect("failed to get SDL event pump");
@@ -23,71 +33,302 @@ pub(crate) fn update() {
                 let mut application = Application::borrow_mut();
                 application.exit();
             },
-            sdl2::event::Event::MouseButtonDown { window_id, x, y, .. } => {
-                let touch = Touch::new(
-                    0,
-                    Point { x, y },
-                );
+            sdl2::event::Event::Window {
+                window_id,
+                win_event: sdl2::event::WindowEvent::Moved(..) |
+                    sdl2::event::WindowEvent::SizeChanged(..) |
+                    sdl2::event::WindowEvent::Maximized |
+                    sdl2::event::WindowEvent::Restored,
+                ..
+            } => {
+                // Keep the window view's frame in sync when the OS moves or
+                // resizes the window.
+                let window = {
+                    let application = Application::borrow();
+                    application.get_window(window_id).cloned()
+                };
 
-                let application = Application::borrow();
-                application.assign_targets_to_touch(window_id, &touch);
-                let event = event_arena.touch_began(touch.clone());
+                if let Some(window) = window {
+                    window.update_frame_from_context();
+                }
+            },
+            sdl2::event::Event::Window { window_id, win_event: sdl2::event::WindowEvent::FocusGained, .. } => {
+                let window = {
+                    let application = Application::borrow();
+                    application.get_window(window_id).cloned()
+                };
 
-                for gesture_recognizer in touch.gesture_recognizers().iter() {
-                    if let Some(gesture_recognizer) = gesture_recognizer.upgrade() {
-                        gesture_recognizer.touches_began(&event.touches(), &event);
+                if let Some(window) = window {
+                    window.make_key_window();
+                    Application::did_become_active();
+                }
+            },
+            sdl2::event::Event::Window { window_id, win_event: sdl2::event::WindowEvent::FocusLost, .. } => {
+                let window = {
+                    let application = Application::borrow();
+                    application.get_window(window_id).cloned()
+                };
+
+                if let Some(window) = window {
+                    // Any drag in progress won't see its button or finger
+                    // lifted, so it's cancelled instead.
+                    let touches = event_arena.cancel_touches_in_window(&window);
+                    let event = event_arena.touch_event();
+                    for touch in touches.iter() {
+                        deliver_touches_cancelled(touch, &event);
                     }
+
+                    window.resign_key_window();
+                    Application::window_did_lose_focus();
+                }
+            },
+            sdl2::event::Event::Window { window_id, win_event: sdl2::event::WindowEvent::Leave, .. } => {
+                let window = {
+                    let application = Application::borrow();
+                    application.get_window(window_id).cloned()
+                };
+
+                if let Some(window) = window {
+                    window.clear_hover();
                 }
+            },
+            sdl2::event::Event::Window { window_id, win_event: sdl2::event::WindowEvent::Close, .. } => {
+                let window = {
+                    let application = Application::borrow();
+                    application.get_window(window_id).cloned()
+                };
 
-                if let Some(view) = touch.view() {
-                    view.touches_began(&event.touches(), &event);
+                if let Some(window) = window {
+                    window.perform_close();
                 }
             },
-            sdl2::event::Event::MouseButtonUp { x, y, .. } => {
-                event_arena.touch_ended(0, Point { x, y });
+            sdl2::event::Event::MouseButtonDown { window_id, mouse_btn, clicks, x, y, .. } => {
+                let button = match MouseButton::from_sdl(mouse_btn) {
+                    Some(button) => button,
+                    None => return
+                };
 
-                let event = event_arena.touch_event();
+                let touch = Touch::new_with_mouse_button(
+                    0,
+                    Point { x, y },
+                    button,
+                    clicks
+                );
 
-                if let Some(existing_touch) = event_arena.touch_event().touches().first() {
-                    for gesture_recognizer in existing_touch.gesture_recognizers().iter() {
-                        if let Some(gesture_recognizer) = gesture_recognizer.upgrade() {
-                            gesture_recognizer.touches_ended(&event.touches(), &event);
+                // Only the left button is a touch; the others go straight to
+                // the view under the mouse.
+                if button != MouseButton::Left {
+                    {
+                        let application = Application::borrow();
+                        application.assign_targets_to_touch(window_id, &touch);
+                    }
+
+                    event_arena.other_mouse_began(touch.clone());
+
+                    if let Some(view) = touch.view() {
+                        if button == MouseButton::Right {
+                            view.right_mouse_down(&touch);
+                        } else {
+                            view.other_mouse_down(&touch);
                         }
                     }
 
-                    if let Some(view) = existing_touch.view() {
-                        view.touches_ended(&event.touches(), &event);
+                    return;
+                }
+
+                {
+                    let application = Application::borrow();
+                    application.assign_targets_to_touch(window_id, &touch);
+                }
+
+                let event = event_arena.touch_began(touch.clone());
+                deliver_touches_began(&touch, &event);
+            },
+            sdl2::event::Event::MouseButtonUp { mouse_btn, x, y, .. } => {
+                let button = match MouseButton::from_sdl(mouse_btn) {
+                    Some(button) => button,
+                    None => return
+                };
+
+                if button != MouseButton::Left {
+                    if let Some(touch) = event_arena.other_mouse_ended(button, Point { x, y }) {
+                        if let Some(view) = touch.view() {
+                            if button == MouseButton::Right {
+                                view.right_mouse_up(&touch);
+                            } else {
+                                view.other_mouse_up(&touch);
+                            }
+                        }
                     }
+
+                    return;
+                }
+
+                // The button may have been pressed outside of the window.
+                if let Some(touch) = event_arena.touch(0) {
+                    event_arena.touch_ended(0, Point { x, y });
+                    let event = event_arena.touch_event();
+                    deliver_touches_ended(&touch, &event);
                 }
             },
-            sdl2::event::Event::MouseMotion { x, y, .. } => {
+            sdl2::event::Event::MouseMotion { window_id, mousestate, x, y, .. } => {
+                // Only the left button makes a touch to drag; the others
+                // are just sent down and up (see `View::other_mouse_down`).
+                let is_dragging = mousestate.left();
+
+                let window = {
+                    let application = Application::borrow();
+                    application.get_window(window_id).cloned()
+                };
+
+                if let Some(window) = &window {
+                    window.update_cursor(&Point { x, y });
+                    window.update_hover(&Point { x, y }, is_dragging);
+                }
+
+                // Without a button pressed, the mouse is only hovering; there
+                // is no touch to move.
+                if !is_dragging {
+                    event_arena.hover_moved(Point { x, y });
+                    return;
+                }
+
                 event_arena.touch_moved(0, Point { x, y });
 
+                if let Some(touch) = event_arena.touch(0) {
+                    let event = event_arena.touch_event();
+                    deliver_touches_moved(&touch, &event);
+                }
+            },
+            sdl2::event::Event::FingerDown { touch_id, finger_id, x, y, pressure, .. } => {
+                // Only touchscreens; e.g. trackpads also report fingers, but
+                // their positions aren't on the screen.
+                if !is_touchscreen(touch_id) {
+                    return;
+                }
+
+                let window = {
+                    let application = Application::borrow();
+                    application.window_for_finger_events().cloned()
+                };
+
+                let window = match window {
+                    Some(window) => window,
+                    None => return
+                };
+
+                let id = event_arena.finger_began(touch_id, finger_id);
+                let touch = Touch::new_with_finger(id, finger_position(&window, x, y), pressure);
+
+                {
+                    let application = Application::borrow();
+                    application.assign_targets_to_touch(window.context().id(), &touch);
+                }
+
+                let event = event_arena.touch_began(touch.clone());
+                deliver_touches_began(&touch, &event);
+            },
+            sdl2::event::Event::FingerMotion { touch_id, finger_id, x, y, pressure, .. } => {
+                let id = match event_arena.finger_touch_id(touch_id, finger_id) {
+                    Some(id) => id,
+                    None => return
+                };
+
+                let touch = match event_arena.touch(id) {
+                    Some(touch) => touch,
+                    None => return
+                };
+
+                if let Some(window) = touch.window() {
+                    event_arena.touch_moved(id, finger_position(&window, x, y));
+                }
+                touch.set_pressure(pressure);
+
                 let event = event_arena.touch_event();
+                deliver_touches_moved(&touch, &event);
+            },
+            sdl2::event::Event::FingerUp { touch_id, finger_id, x, y, pressure, .. } => {
+                let id = match event_arena.finger_ended(touch_id, finger_id) {
+                    Some(id) => id,
+                    None => return
+                };
+
+                let touch = match event_arena.touch(id) {
+                    Some(touch) => touch,
+                    None => return
+                };
+
+                let position = match touch.window() {
+                    Some(window) => finger_position(&window, x, y),
+                    None => touch.position()
+                };
+                event_arena.touch_ended(id, position);
+                touch.set_pressure(pressure);
 
-                if let Some(existing_touch) = event_arena.touch_event().touches().first() {
-                    for gesture_recognizer in existing_touch.gesture_recognizers().iter() {
-                        if let Some(gesture_recognizer) = gesture_recognizer.upgrade() {
-                            gesture_recognizer.touches_moved(&event.touches(), &event);
+                let event = event_arena.touch_event();
+                deliver_touches_ended(&touch, &event);
+            },
+            // Pinching and rotating on a trackpad. On touchscreens, gesture
+            // recognizers see each finger as its own touch instead.
+            sdl2::event::Event::MultiGesture { touch_id, d_theta, d_dist, .. } => {
+                if is_touchscreen(touch_id) {
+                    return;
+                }
+
+                let window = {
+                    let application = Application::borrow();
+                    application.window_for_finger_events().cloned()
+                };
+
+                let window = match window {
+                    Some(window) => window,
+                    None => return
+                };
+
+                let event = event_arena.scroll_event();
+                let touch = event.touch();
+
+                {
+                    let application = Application::borrow();
+                    application.assign_targets_to_touch(window.context().id(), &touch);
+                }
+
+                for gesture_recognizer in touch.gesture_recognizers().iter() {
+                    if let Some(gesture_recognizer) = gesture_recognizer.upgrade() {
+                        if d_dist != 0.0 {
+                            gesture_recognizer.scroll_did_zoom(1.0 + d_dist * TRACKPAD_PINCH_SENSITIVITY, &event);
                         }
-                    }
 
-                    if let Some(view) = existing_touch.view() {
-                        view.touches_moved(&event.touches(), &event);
+                        if d_theta != 0.0 {
+                            gesture_recognizer.scroll_did_rotate(d_theta, &event);
+                        }
                     }
                 }
             },
-            sdl2::event::Event::MultiGesture { .. } => {
-                println!("SDL_MultiGestureEvent")
-            },
 
             // https://stackoverflow.com/a/47597200/869367
-            sdl2::event::Event::MouseWheel { window_id, x, y, .. } => {
+            sdl2::event::Event::MouseWheel { window_id, x, y, precise_y, .. } => {
                 let event = event_arena.scroll_event();
                 let touch = event.touch();
 
-                let application = Application::borrow();
-                application.assign_targets_to_touch(window_id, &touch);
+                {
+                    let application = Application::borrow();
+                    application.assign_targets_to_touch(window_id, &touch);
+                }
+
+                // Holding ctrl while scrolling zooms, the same as pinching.
+                let keymod = sdl.keyboard().mod_state();
+                if keymod.intersects(sdl2::keyboard::Mod::LCTRLMOD | sdl2::keyboard::Mod::RCTRLMOD) {
+                    let scale = WHEEL_ZOOM_FACTOR.powf(precise_y);
+
+                    for gesture_recognizer in touch.gesture_recognizers().iter() {
+                        if let Some(gesture_recognizer) = gesture_recognizer.upgrade() {
+                            gesture_recognizer.scroll_did_zoom(scale, &event);
+                        }
+                    }
+
+                    return;
+                }
 
                 event_arena.scroll_did_translate(Point::new(x, y));
 
@@ -190,3 +431,232 @@ pub(crate) fn update() {
         }
     }
 }
+
+/// Whether the touch device is a touchscreen, as opposed to e.g. a trackpad.
+fn is_touchscreen(touch_id: i64) -> bool {
+    let device_type = unsafe { sdl2::sys::SDL_GetTouchDeviceType(touch_id) };
+    device_type == sdl2::sys::SDL_TouchDeviceType::SDL_TOUCH_DEVICE_DIRECT
+}
+
+/// Converts a finger position, normalised from `0.0` to `1.0` across the
+/// window, to a point in the window.
+fn finger_position(window: &Window, x: f32, y: f32) -> Point<i32> {
+    let size = window.context().size();
+    Point {
+        x: (x * size.width as f32).round() as i32,
+        y: (y * size.height as f32).round() as i32
+    }
+}
+
+// Only the touch that changed is passed along; the event has all of the
+// touches in progress.
+//
+// Gesture recognizers see the touch first. Once one of them has recognized
+// its gesture, the others fail (unless a delegate allows them to recognize
+// simultaneously). If the recognizer cancels touches in view, the view is
+// sent `touches_cancelled` (once) and then no longer receives the touch.
+
+fn deliver_touches_began(touch: &Touch, event: &TouchEvent) {
+    let touches = vec![touch.clone()];
+    let recognizers = recognizers_for_touch(touch);
+
+    // Every recognizer not already following other touches goes back to
+    // `Possible` on its first touch, so they're all given the touch before
+    // arbitrating; otherwise one still `Ended` from the last gesture would
+    // make the ones given the touch before it fail.
+    for gesture_recognizer in recognizers.iter() {
+        gesture_recognizer.touches_began(&touches, event);
+    }
+    arbitrate(&recognizers);
+
+    view_receiving_touch(touch, event);
+}
+
+fn deliver_touches_moved(touch: &Touch, event: &TouchEvent) {
+    let touches = vec![touch.clone()];
+    let recognizers = recognizers_for_touch(touch);
+
+    for gesture_recognizer in recognizers.iter() {
+        gesture_recognizer.touches_moved(&touches, event);
+        arbitrate(&recognizers);
+    }
+
+    if let Some(view) = view_receiving_touch(touch, event) {
+        view.touches_moved(&touches, event);
+    }
+}
+
+fn deliver_touches_ended(touch: &Touch, event: &TouchEvent) {
+    let touches = vec![touch.clone()];
+    let recognizers = recognizers_for_touch(touch);
+
+    for gesture_recognizer in recognizers.iter() {
+        gesture_recognizer.touches_ended(&touches, event);
+        arbitrate(&recognizers);
+    }
+
+    if let Some(view) = view_receiving_touch(touch, event) {
+        view.touches_ended(&touches, event);
+    }
+}
+
+fn deliver_touches_cancelled(touch: &Touch, event: &TouchEvent) {
+    let touches = vec![touch.clone()];
+
+    for gesture_recognizer in recognizers_for_touch(touch).iter() {
+        gesture_recognizer.touches_cancelled(&touches, event);
+        resolve_waiting(gesture_recognizer.as_ref().as_ref());
+    }
+
+    if touch.is_cancelled_in_view() || !touch.has_began_in_view() {
+        return;
+    }
+    touch.set_cancelled_in_view();
+
+    if let Some(view) = touch.view() {
+        view.touches_cancelled(&touches, event);
+    }
+}
+
+fn recognizers_for_touch(touch: &Touch) -> Vec<Rc<Box<dyn Recognizer>>> {
+    touch.gesture_recognizers().iter()
+        .filter_map(|gesture_recognizer| gesture_recognizer.upgrade())
+        .collect()
+}
+
+/// Makes the recognizers still `Possible` fail if another has recognized
+/// its gesture, unless a delegate allows them to recognize simultaneously.
+/// Then the recognizers waiting on any that are no longer `Possible` (see
+/// `Recognizer::require_to_fail`) are resolved again.
+///
+/// The recognizers are given each moved or lifted touch in order from the
+/// touched view outwards, and arbitrated after each one, so when several
+/// recognize their gestures from the same touch the inner-most wins; e.g. a
+/// pan on a view inside a `ScrollView` over the scroll view's own pan, when
+/// both pass their thresholds on the same move. Otherwise the first to
+/// recognize wins, wherever it is; use `Recognizer::require_to_fail` to make
+/// an outer recognizer wait for an inner one.
+fn arbitrate(recognizers: &[Rc<Box<dyn Recognizer>>]) {
+    for recognizer in recognizers.iter() {
+        if !recognizer.has_recognized() {
+            continue;
+        }
+
+        for other in recognizers.iter() {
+            if Rc::ptr_eq(recognizer, other) || other.state() != RecognizerState::Possible {
+                continue;
+            }
+
+            if Arbitration::allows_simultaneous(recognizer.as_ref().as_ref(), other.as_ref().as_ref()) {
+                continue;
+            }
+
+            other.fail();
+        }
+    }
+
+    for recognizer in recognizers.iter() {
+        resolve_waiting(recognizer.as_ref().as_ref());
+    }
+}
+
+/// The view to send the touch on to, if it should receive it. Sends the view
+/// `touches_began` first if it hasn't been yet.
+///
+/// If a recognizer that `delays_touches_began` is still deciding, the view
+/// isn't sent anything yet; it's sent `touches_began` once the recognizer
+/// fails, or when the touch ends.
+fn view_receiving_touch(touch: &Touch, event: &TouchEvent) -> Option<View> {
+    if cancel_in_view_if_recognized(touch, event) {
+        return None;
+    }
+
+    let view = touch.view()?;
+
+    let touches = vec![touch.clone()];
+
+    if !touch.has_began_in_view() {
+        let is_ending = touch.phase() == TouchPhase::Ended;
+        let is_delayed = recognizers_for_touch(touch).iter().any(|gesture_recognizer| {
+            gesture_recognizer.delays_touches_began() && gesture_recognizer.state() == RecognizerState::Possible
+        });
+
+        if is_delayed && !is_ending {
+            return None;
+        }
+
+        touch.set_began_in_view();
+        view.touches_began(&touches, event);
+    }
+
+    Some(view)
+}
+
+/// Sends the view `touches_cancelled` if a gesture recognizer has claimed
+/// the touch. Returns `true` if the view shouldn't receive the touch (i.e.
+/// it is, or already was, cancelled).
+fn cancel_in_view_if_recognized(touch: &Touch, event: &TouchEvent) -> bool {
+    if touch.is_cancelled_in_view() {
+        return true;
+    }
+
+    let claimed = recognizers_for_touch(touch).iter()
+        .any(|gesture_recognizer| gesture_recognizer.cancels_touches_in_view() && gesture_recognizer.has_recognized());
+
+    if !claimed {
+        return false;
+    }
+
+    touch.set_cancelled_in_view();
+
+    // A view that was never sent `touches_began` (see
+    // `Recognizer::delays_touches_began`) has nothing to cancel.
+    if !touch.has_began_in_view() {
+        return true;
+    }
+
+    if let Some(view) = touch.view() {
+        view.touches_cancelled(&vec![touch.clone()], event);
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::gesture::pan_recognizer::PanRecognizer;
+    use std::rc::Weak;
+
+    fn pan(touch: &Touch, recognizers: &[Rc<Box<dyn Recognizer>>], to: Point<i32>) {
+        let event = TouchEvent::new();
+        touch.set_gesture_recognizers(recognizers.iter().map(Rc::downgrade).collect::<Vec<Weak<Box<dyn Recognizer>>>>());
+
+        deliver_touches_began(touch, &event);
+        touch.set_position(to);
+        deliver_touches_moved(touch, &event);
+        deliver_touches_ended(touch, &event);
+    }
+
+    #[test]
+    fn test_nested_pans_in_a_row() {
+        let inner = PanRecognizer::new(|_pan_recognizer| {});
+        let outer = PanRecognizer::new(|_pan_recognizer| {});
+        let inner_recognizer: Rc<Box<dyn Recognizer>> = Rc::new(Box::new(inner.clone()));
+        let outer_recognizer: Rc<Box<dyn Recognizer>> = Rc::new(Box::new(outer.clone()));
+
+        // Panning outside the inner view leaves the outer pan `Ended`...
+        pan(&Touch::new(0, Point::new(10, 10)), &[outer_recognizer.clone()], Point::new(50, 10));
+        assert_eq!(outer.state(), RecognizerState::Ended);
+
+        // ...which mustn't make the inner pan fail on the next gesture.
+        let recognizers = [inner_recognizer, outer_recognizer];
+        pan(&Touch::new(1, Point::new(10, 10)), &recognizers, Point::new(50, 10));
+        assert_eq!(inner.state(), RecognizerState::Ended);
+        assert_eq!(outer.state(), RecognizerState::Failed);
+
+        pan(&Touch::new(2, Point::new(10, 10)), &recognizers, Point::new(50, 10));
+        assert_eq!(inner.state(), RecognizerState::Ended);
+        assert_eq!(outer.state(), RecognizerState::Failed);
+    }
+}
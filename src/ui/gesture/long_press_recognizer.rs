@@ -0,0 +1,452 @@
+use crate::ui::{View, WeakView, Window};
+use crate::graphics::Point;
+use std::cell::RefCell;
+use crate::ui::gesture::recognizer::{Recognizer, RecognizerState, Arbitration, Resolution, moved_beyond, resolve_waiting};
+use crate::ui::Touch;
+use crate::ui::timer::Timer;
+use crate::ui::run_loop::RunLoop;
+use std::rc::{Rc, Weak};
+use std::time::Duration;
+use crate::ui::event::TouchEvent;
+
+type Action = Rc<Box<dyn Fn(&LongPressRecognizer)>>;
+
+/// Recognizes touches held down without moving for a minimum duration.
+///
+/// The action is called when the press begins (once the minimum duration
+/// has passed), for every move after that, and when it ends.
+pub struct LongPressRecognizer {
+    inner: Rc<RefCell<LongPressRecognizerInner>>
+}
+
+struct LongPressRecognizerInner {
+    view: WeakView,
+    state: RecognizerState,
+    action: Action,
+    arbitration: Rc<Arbitration>,
+    minimum_duration: Duration,
+    allowable_movement: u32,
+    number_of_touches_required: usize,
+
+    /// The touches currently down, with the position they went down at.
+    touches: Vec<(usize, Point<i32>)>,
+
+    position: Point<i32>,
+    window: Option<Window>,
+
+    /// Begins the press once the minimum duration has passed.
+    timer: Option<Timer>,
+
+    /// Set while held long enough, but waiting on recognizers this one
+    /// requires to fail.
+    waiting_for_required_failures: bool
+}
+
+impl LongPressRecognizer {
+    pub fn new(action: impl Fn(&LongPressRecognizer) + 'static) -> LongPressRecognizer {
+        LongPressRecognizer {
+            inner: Rc::new(RefCell::new(LongPressRecognizerInner {
+                view: WeakView::none(),
+                state: RecognizerState::Possible,
+                action: Rc::new(Box::new(action)),
+                arbitration: Arbitration::new(),
+                minimum_duration: Duration::from_millis(500),
+                allowable_movement: 10,
+                number_of_touches_required: 1,
+                touches: Vec::new(),
+                position: Point::new(0, 0),
+                window: None,
+                timer: None,
+                waiting_for_required_failures: false
+            }))
+        }
+    }
+
+    pub fn minimum_duration(&self) -> Duration {
+        self.inner.borrow().minimum_duration
+    }
+
+    /// How long the touches need to be held down for.
+    ///
+    /// Defaults to half a second.
+    pub fn set_minimum_duration(&self, duration: Duration) {
+        self.inner.borrow_mut().minimum_duration = duration;
+    }
+
+    pub fn allowable_movement(&self) -> u32 {
+        self.inner.borrow().allowable_movement
+    }
+
+    /// How far a touch can move (in points) before the press has begun,
+    /// without failing. Once the press has begun it can move freely.
+    ///
+    /// Defaults to `10`.
+    pub fn set_allowable_movement(&self, distance: u32) {
+        self.inner.borrow_mut().allowable_movement = distance;
+    }
+
+    pub fn number_of_touches_required(&self) -> usize {
+        self.inner.borrow().number_of_touches_required
+    }
+
+    /// The number of fingers that need to be held down.
+    ///
+    /// Defaults to `1`.
+    pub fn set_number_of_touches_required(&self, touches: usize) {
+        self.inner.borrow_mut().number_of_touches_required = touches;
+    }
+
+    pub fn view(&self) -> WeakView {
+        self.inner.borrow().view.clone()
+    }
+
+    /// Where the press currently is, in the coordinates of the given view.
+    pub fn location_in(&self, view: &View) -> Point<i32> {
+        let inner = self.inner.borrow();
+        if let Some(window) = &inner.window {
+            window.convert_point_to(&inner.position, view)
+        } else {
+            inner.position.clone()
+        }
+    }
+
+    fn invalidate_timer(&self) {
+        if let Some(timer) = self.inner.borrow_mut().timer.take() {
+            timer.invalidate();
+        }
+    }
+
+    fn start_timer(&self, delay: Duration) {
+        self.invalidate_timer();
+
+        let weak_inner: Weak<RefCell<LongPressRecognizerInner>> = Rc::downgrade(&self.inner);
+        let timer = Timer::new_once_delayed(delay, move || {
+            if let Some(inner) = weak_inner.upgrade() {
+                let recognizer = LongPressRecognizer { inner };
+                recognizer.minimum_duration_elapsed();
+            }
+        });
+
+        self.inner.borrow_mut().timer = Some(timer.clone());
+
+        let run_loop = RunLoop::borrow();
+        run_loop.add_timer(timer);
+    }
+
+    /// Called by the timer once the touches have been held down long enough,
+    /// and again by `Arbitration::wait` while waiting on recognizers it
+    /// requires to fail.
+    fn minimum_duration_elapsed(&self) {
+        {
+            let mut inner = self.inner.borrow_mut();
+            inner.timer = None;
+            inner.waiting_for_required_failures = false;
+
+            if inner.state != RecognizerState::Possible || inner.touches.len() != inner.number_of_touches_required {
+                return;
+            }
+        }
+
+        match self.arbitration().resolve(self) {
+            Resolution::Begin => {
+                self.transition(RecognizerState::Began);
+                resolve_waiting(self);
+            },
+            Resolution::Wait => {
+                self.inner.borrow_mut().waiting_for_required_failures = true;
+
+                let weak_inner: Weak<RefCell<LongPressRecognizerInner>> = Rc::downgrade(&self.inner);
+                self.arbitration().wait(move || {
+                    if let Some(inner) = weak_inner.upgrade() {
+                        let recognizer = LongPressRecognizer { inner };
+                        if recognizer.inner.borrow().waiting_for_required_failures {
+                            recognizer.minimum_duration_elapsed();
+                        }
+                    }
+                });
+            },
+            Resolution::Fail => self.fail()
+        }
+    }
+
+    fn transition(&self, state: RecognizerState) {
+        let action: Action;
+        {
+            let mut inner = self.inner.borrow_mut();
+            inner.state = state;
+            action = inner.action.clone();
+        }
+
+        action(self);
+    }
+}
+
+impl LongPressRecognizerInner {
+    /// Tracks the touches that began, returning whether the required number
+    /// are now down, for the minimum duration to start.
+    fn add_touches(&mut self, touches: &[Touch]) -> bool {
+        // A new gesture starts with the first touch after the last one
+        // finished.
+        if self.touches.is_empty() {
+            self.state = RecognizerState::Possible;
+        }
+
+        for touch in touches.iter() {
+            self.touches.push((touch.id(), touch.position()));
+            self.position = touch.position();
+            self.window = touch.window();
+        }
+
+        if self.state != RecognizerState::Possible {
+            return false;
+        }
+
+        if self.touches.len() > self.number_of_touches_required {
+            self.fail();
+            return false;
+        }
+
+        self.touches.len() == self.number_of_touches_required
+    }
+
+    fn fail(&mut self) {
+        if let Some(timer) = self.timer.take() {
+            timer.invalidate();
+        }
+        self.waiting_for_required_failures = false;
+
+        if self.state == RecognizerState::Possible {
+            self.state = RecognizerState::Failed;
+        }
+    }
+}
+
+impl Recognizer for LongPressRecognizer {
+    fn touches_began(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        let is_ready = self.inner.borrow_mut().add_touches(touches);
+
+        if is_ready {
+            self.start_timer(self.minimum_duration());
+        }
+    }
+
+    fn touches_moved(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        let action: Action;
+        {
+            let mut inner = self.inner.borrow_mut();
+
+            if let Some(touch) = touches.first() {
+                inner.position = touch.position();
+            }
+
+            match inner.state {
+                RecognizerState::Possible => {
+                    let moved_too_far = touches.iter().any(|touch| {
+                        inner.touches.iter().any(|(id, initial_position)| {
+                            *id == touch.id() && moved_beyond(initial_position, &touch.position(), inner.allowable_movement)
+                        })
+                    });
+
+                    if moved_too_far {
+                        drop(inner);
+                        self.fail();
+                    }
+                    return;
+                },
+                RecognizerState::Began => { inner.state = RecognizerState::Changed },
+                RecognizerState::Changed => {},
+                _ => {
+                    return;
+                }
+            }
+
+            action = inner.action.clone();
+        }
+
+        action(self);
+    }
+
+    fn touches_ended(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        let action: Action;
+        {
+            let mut inner = self.inner.borrow_mut();
+
+            for touch in touches.iter() {
+                inner.touches.retain(|(id, _)| *id != touch.id());
+                inner.position = touch.position();
+            }
+
+            match inner.state {
+                RecognizerState::Possible => {
+                    drop(inner);
+                    self.fail();
+                    return;
+                },
+                RecognizerState::Began | RecognizerState::Changed => {
+                    inner.state = RecognizerState::Ended;
+                },
+                _ => {
+                    return;
+                }
+            }
+
+            action = inner.action.clone();
+        }
+
+        action(self);
+    }
+
+    fn touches_cancelled(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        let action: Action;
+        {
+            let mut inner = self.inner.borrow_mut();
+
+            for touch in touches.iter() {
+                inner.touches.retain(|(id, _)| *id != touch.id());
+            }
+
+            match inner.state {
+                RecognizerState::Began | RecognizerState::Changed => {
+                    inner.state = RecognizerState::Cancelled;
+                },
+                RecognizerState::Possible => {
+                    drop(inner);
+                    self.fail();
+                    return;
+                },
+                _ => {
+                    return;
+                }
+            }
+
+            action = inner.action.clone();
+        }
+
+        action(self);
+    }
+
+    fn state(&self) -> RecognizerState {
+        self.inner.borrow().state
+    }
+
+    fn fail(&self) {
+        self.inner.borrow_mut().fail();
+        resolve_waiting(self);
+    }
+
+    fn arbitration(&self) -> Rc<Arbitration> {
+        self.inner.borrow().arbitration.clone()
+    }
+
+    fn set_view(&self, view: WeakView) {
+        let mut inner = self.inner.borrow_mut();
+        inner.view = view;
+    }
+}
+
+impl Clone for LongPressRecognizer {
+    fn clone(&self) -> LongPressRecognizer {
+        LongPressRecognizer {
+            inner: self.inner.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Puts touches down without scheduling the minimum duration timer,
+    /// which can only run on the main thread.
+    fn press(recognizer: &LongPressRecognizer, touches: &[Touch]) -> bool {
+        recognizer.inner.borrow_mut().add_touches(touches)
+    }
+
+    #[test]
+    fn test_default_state() {
+        let recognizer = LongPressRecognizer::new(|_long_press_recognizer| {});
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
+        assert_eq!(recognizer.minimum_duration(), Duration::from_millis(500));
+        assert!(recognizer.view().is_none());
+    }
+
+    #[test]
+    fn test_long_press() {
+        let states = Rc::new(RefCell::new(Vec::new()));
+        let states_clone = states.clone();
+        let recognizer = LongPressRecognizer::new(move |long_press_recognizer| {
+            states_clone.borrow_mut().push(long_press_recognizer.state());
+        });
+
+        let event = TouchEvent::new();
+        assert!(press(&recognizer, &[Touch::new(0, Point::new(10, 10))]));
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
+
+        recognizer.minimum_duration_elapsed();
+        assert_eq!(recognizer.state(), RecognizerState::Began);
+        assert!(recognizer.has_recognized());
+
+        recognizer.touches_moved(&vec![Touch::new(0, Point::new(100, 10))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Changed);
+
+        recognizer.touches_ended(&vec![Touch::new(0, Point::new(100, 10))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Ended);
+
+        assert_eq!(*states.borrow(), vec![RecognizerState::Began, RecognizerState::Changed, RecognizerState::Ended]);
+    }
+
+    #[test]
+    fn test_released_too_soon() {
+        let pressed = Rc::new(Cell::new(false));
+        let pressed_clone = pressed.clone();
+        let recognizer = LongPressRecognizer::new(move |_long_press_recognizer| {
+            pressed_clone.set(true);
+        });
+
+        let event = TouchEvent::new();
+        let touches = vec![Touch::new(0, Point::new(10, 10))];
+        press(&recognizer, &touches);
+        recognizer.touches_ended(&touches, &event);
+        assert_eq!(recognizer.state(), RecognizerState::Failed);
+
+        recognizer.minimum_duration_elapsed();
+        assert_eq!(recognizer.state(), RecognizerState::Failed);
+        assert!(!pressed.get());
+    }
+
+    #[test]
+    fn test_requires_other_to_fail() {
+        let recognizer = LongPressRecognizer::new(|_long_press_recognizer| {});
+        let other = LongPressRecognizer::new(|_long_press_recognizer| {});
+        recognizer.require_to_fail(Box::new(other.clone()));
+
+        press(&recognizer, &[Touch::new(0, Point::new(10, 10))]);
+        recognizer.minimum_duration_elapsed();
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
+
+        // Begins as soon as the other fails.
+        other.fail();
+        assert_eq!(recognizer.state(), RecognizerState::Began);
+    }
+
+    #[test]
+    fn test_moved_too_far() {
+        let recognizer = LongPressRecognizer::new(|_long_press_recognizer| {});
+        let event = TouchEvent::new();
+        press(&recognizer, &[Touch::new(0, Point::new(10, 10))]);
+        recognizer.touches_moved(&vec![Touch::new(0, Point::new(10, 50))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Failed);
+    }
+
+    #[test]
+    fn test_too_many_touches() {
+        let recognizer = LongPressRecognizer::new(|_long_press_recognizer| {});
+        recognizer.set_number_of_touches_required(2);
+
+        assert!(!press(&recognizer, &[Touch::new(1, Point::new(10, 10))]));
+        assert!(press(&recognizer, &[Touch::new(2, Point::new(40, 10))]));
+        assert!(!press(&recognizer, &[Touch::new(3, Point::new(70, 10))]));
+        assert_eq!(recognizer.state(), RecognizerState::Failed);
+    }
+}
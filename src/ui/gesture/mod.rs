@@ -1,2 +1,8 @@
 pub mod recognizer;
 pub mod pan_recognizer;
+pub mod tap_recognizer;
+pub mod long_press_recognizer;
+pub mod pinch_recognizer;
+pub mod rotation_recognizer;
+pub mod swipe_recognizer;
+mod tracked_touches;
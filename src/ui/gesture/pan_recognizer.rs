@@ -1,20 +1,24 @@
 use crate::ui::{View, WeakView};
 use crate::graphics::Point;
 use std::cell::RefCell;
-use crate::ui::gesture::recognizer::Recognizer;
+use crate::ui::gesture::recognizer::{Recognizer, RecognizerState, Arbitration, may_begin};
+use crate::ui::gesture::recognizer::{ContinuousRecognizerInner, RecognizerAction, transition, restart_scroll_gesture_end_timer};
+use crate::ui::gesture::tracked_touches::TrackedTouches;
 use crate::ui::Touch;
+use crate::ui::timer::Timer;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 use crate::ui::event::{TouchEvent, ScrollEvent};
 
-#[derive(PartialEq, Eq, Hash, Debug, Clone)]
-pub enum PanState {
-    Possible,
-    Began,
-    Changed,
-    Ended,
-    Cancelled,
-    Failed
-}
+/// How far back the velocity looks. Only the most recent movement counts, so
+/// that pausing before lifting the finger gives no velocity.
+const VELOCITY_SAMPLE_WINDOW_MS: u64 = 100;
+
+type Action = RecognizerAction<PanRecognizer>;
+
+/// The pan's state, now shared by every recognizer.
+#[deprecated(note = "use `RecognizerState` instead")]
+pub type PanState = RecognizerState;
 
 pub struct PanRecognizer {
     inner: Rc<RefCell<PanRecognizerInner>>
@@ -22,69 +26,229 @@ pub struct PanRecognizer {
 
 struct PanRecognizerInner {
     view: WeakView,
-    state: PanState,
-    action: Rc<Box<dyn Fn(&PanRecognizer) -> ()>>,
+    state: RecognizerState,
+    action: Action,
+    arbitration: Rc<Arbitration>,
+    touches: TrackedTouches,
     translation: Point<i32>,
     initial_position: Point<i32>,
-    last_position: Point<i32>
+    last_position: Point<i32>,
+
+    /// Recent positions of the touches (in window coordinates) and when they
+    /// were there, oldest first.
+    samples: Vec<(Instant, Point<i32>)>,
+
+    /// Ends a pan made by scrolling, which has no touch lifting to end it.
+    end_timer: Option<Timer>
 }
 
 impl PanRecognizer {
-    pub fn new(action: impl Fn(&PanRecognizer) -> () + 'static) -> PanRecognizer {
+    pub fn new(action: impl Fn(&PanRecognizer) + 'static) -> PanRecognizer {
         PanRecognizer {
             inner: Rc::new(RefCell::new(PanRecognizerInner {
                 view: WeakView::none(),
-                state: PanState::Possible,
+                state: RecognizerState::Possible,
                 action: Rc::new(Box::new(action)),
+                arbitration: Arbitration::new(),
+                touches: TrackedTouches::new(),
                 translation: Point::new(0, 0),
                 initial_position: Point::new(0, 0),
-                last_position: Point::new(0, 0)
+                last_position: Point::new(0, 0),
+                samples: Vec::new(),
+                end_timer: None
             }))
         }
     }
 
-    pub fn translation_in(&self, _view: &View) -> Point<i32> {
+    /// How far the touches have moved since the pan began (or since the
+    /// translation was last set), in the coordinate system of the given view.
+    pub fn translation_in(&self, view: &View) -> Point<i32> {
         let inner = self.inner.borrow();
-        let translation = &inner.translation;
-        translation.clone()
+        let origin = PanRecognizer::convert_to(&inner, &Point::new(0, 0), view);
+        let translation = PanRecognizer::convert_to(&inner, &inner.translation, view);
+
+        Point::new(translation.x - origin.x, translation.y - origin.y)
     }
 
-    pub fn set_translation(&self, translation: Point<i32>, _view: &View) {
+    /// Sets the translation, given in the coordinate system of the given
+    /// view. Usually set back to zero after applying it, so that the next
+    /// translation is just the movement since.
+    pub fn set_translation(&self, translation: Point<i32>, view: &View) {
         let mut inner = self.inner.borrow_mut();
-        inner.translation = translation;
-        inner.initial_position = inner.last_position.clone();
+        let origin = PanRecognizer::convert_from(&inner, &Point::new(0, 0), view);
+        let translation = PanRecognizer::convert_from(&inner, &translation, view);
+
+        inner.translation = Point::new(translation.x - origin.x, translation.y - origin.y);
+        inner.initial_position = Point::new(
+            inner.last_position.x - inner.translation.x,
+            inner.last_position.y - inner.translation.y
+        );
     }
 
-    pub fn state(&self) -> PanState {
-        self.inner.borrow().state.clone()
+    /// How fast the touches are moving, in points per second in the
+    /// coordinate system of the given view.
+    ///
+    /// Measured over the last moment of movement; e.g. read it when the pan
+    /// ends to fling the view in the direction it was thrown.
+    pub fn velocity_in(&self, view: &View) -> Point<f32> {
+        let inner = self.inner.borrow();
+
+        let (newest_time, newest_position) = match inner.samples.last() {
+            Some(sample) => sample,
+            None => return Point::new(0.0, 0.0)
+        };
+
+        let window = Duration::from_millis(VELOCITY_SAMPLE_WINDOW_MS);
+        let oldest = inner.samples.iter()
+            .find(|(time, _)| newest_time.duration_since(*time) <= window);
+
+        let (oldest_time, oldest_position) = match oldest {
+            Some(sample) => sample,
+            None => return Point::new(0.0, 0.0)
+        };
+
+        let elapsed = newest_time.duration_since(*oldest_time).as_secs_f32();
+        if elapsed <= 0.0 {
+            return Point::new(0.0, 0.0);
+        }
+
+        let from = PanRecognizer::convert_to(&inner, oldest_position, view);
+        let to = PanRecognizer::convert_to(&inner, newest_position, view);
+
+        Point::new(
+            (to.x - from.x) as f32 / elapsed,
+            (to.y - from.y) as f32 / elapsed
+        )
     }
 
     pub fn view(&self) -> WeakView {
         self.inner.borrow().view.clone()
     }
+
+    /// Converts a point from the window the touches are in to the given
+    /// view.
+    fn convert_to(inner: &PanRecognizerInner, point: &Point<i32>, view: &View) -> Point<i32> {
+        match inner.touches.window() {
+            Some(window) => window.convert_point_to(point, view),
+            None => point.clone()
+        }
+    }
+
+    /// Converts a point from the given view to the window the touches are in.
+    fn convert_from(inner: &PanRecognizerInner, point: &Point<i32>, view: &View) -> Point<i32> {
+        match inner.touches.window() {
+            Some(window) => view.convert_point_to(point, &window),
+            None => point.clone()
+        }
+    }
+
+    /// Records the last position, at the time of the latest of the touches.
+    fn add_sample(inner: &mut PanRecognizerInner, touches: &[Touch]) {
+        let timestamp = touches.iter()
+            .map(|t| t.timestamp())
+            .max()
+            .unwrap_or_else(Instant::now);
+
+        PanRecognizer::add_sample_at(inner, timestamp);
+    }
+
+    fn add_sample_at(inner: &mut PanRecognizerInner, timestamp: Instant) {
+        let window = Duration::from_millis(VELOCITY_SAMPLE_WINDOW_MS);
+        inner.samples.retain(|(time, _)| timestamp.saturating_duration_since(*time) <= window);
+        inner.samples.push((timestamp, inner.last_position.clone()));
+    }
+
+    /// A finger was added or lifted, moving the point between the fingers.
+    /// Keeps the translation where it was, rather than jumping.
+    fn touches_did_change(inner: &mut PanRecognizerInner, touches: &[Touch]) {
+        let position = inner.touches.centroid();
+        inner.initial_position = Point::new(
+            position.x - inner.translation.x,
+            position.y - inner.translation.y
+        );
+        inner.last_position = position;
+
+        // The jump isn't movement either.
+        inner.samples.clear();
+        PanRecognizer::add_sample(inner, touches);
+    }
+}
+
+impl ContinuousRecognizerInner<PanRecognizer> for PanRecognizerInner {
+    fn state(&self) -> RecognizerState {
+        self.state
+    }
+
+    fn set_state(&mut self, state: RecognizerState) {
+        self.state = state;
+    }
+
+    fn action(&self) -> Action {
+        self.action.clone()
+    }
+
+    fn has_touches(&self) -> bool {
+        !self.touches.is_empty()
+    }
+
+    fn end_timer(&mut self) -> &mut Option<Timer> {
+        &mut self.end_timer
+    }
 }
 
 impl Recognizer for PanRecognizer {
     fn touches_began(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
         let mut inner = self.inner.borrow_mut();
 
-        inner.state = PanState::Possible;
-
-        inner.last_position = touches.first().expect("touches list was empty").position().clone();
-        inner.initial_position = inner.last_position.clone();
-        inner.translation = Point::new(0, 0);
+        // A new gesture starts with the first touch after the last one
+        // finished.
+        if inner.touches.is_empty() {
+            inner.touches.began(touches);
+            inner.state = RecognizerState::Possible;
+            inner.last_position = inner.touches.centroid();
+            inner.initial_position = inner.last_position.clone();
+            inner.translation = Point::new(0, 0);
+            inner.samples.clear();
+            PanRecognizer::add_sample(&mut inner, touches);
+        } else {
+            inner.touches.began(touches);
+            PanRecognizer::touches_did_change(&mut inner, touches);
+        }
     }
 
-    fn touches_ended(&self, _touches: &Vec<Touch>, _event: &TouchEvent) {
-        let mut inner = self.inner.borrow_mut();
-        inner.state = PanState::Ended;
+    fn touches_ended(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        {
+            let mut inner = self.inner.borrow_mut();
+            // Where the fingers were lifted counts towards the velocity.
+            inner.touches.moved(touches);
+            inner.last_position = inner.touches.centroid();
+            PanRecognizer::add_sample(&mut inner, touches);
+            inner.touches.ended(touches);
+
+            if !inner.touches.is_empty() {
+                PanRecognizer::touches_did_change(&mut inner, touches);
+                return;
+            }
+
+            match inner.state {
+                RecognizerState::Began | RecognizerState::Changed => {},
+                RecognizerState::Possible => {
+                    inner.state = RecognizerState::Failed;
+                    return;
+                },
+                _ => return
+            }
+        }
+
+        transition(self, &self.inner, RecognizerState::Ended);
     }
 
     fn touches_moved(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
-        let action: Rc<Box<dyn Fn(&PanRecognizer) -> ()>>;
-        {
-            let touch_position = touches.first().expect("touches list was empty").position();
+        let state = {
             let mut inner = self.inner.borrow_mut();
+            inner.touches.moved(touches);
+
+            let touch_position = inner.touches.centroid();
 
             inner.translation = Point::new(
                 touch_position.x - inner.initial_position.x,
@@ -92,26 +256,40 @@ impl Recognizer for PanRecognizer {
             );
 
             inner.last_position = touch_position;
+            PanRecognizer::add_sample(&mut inner, touches);
 
             match inner.state {
-                PanState::Possible => {
-                    if inner.translation.x.abs() > 10 || inner.translation.y.abs() > 10 {
-                        inner.state = PanState::Began;
-                    } else {
-                        return;
-                    }
+                RecognizerState::Possible if inner.translation.x.abs() > 10 || inner.translation.y.abs() > 10 => {
+                    RecognizerState::Began
                 },
-                PanState::Began => { inner.state = PanState::Changed },
-                PanState::Changed => {},
-                _ => {
-                    return;
-                }
+                RecognizerState::Began | RecognizerState::Changed => RecognizerState::Changed,
+                _ => return
             }
+        };
+
+        if state == RecognizerState::Began && !may_begin(self) {
+            return;
+        }
 
-            action = inner.action.clone();
+        transition(self, &self.inner, state);
+    }
+
+    fn touches_cancelled(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        {
+            let mut inner = self.inner.borrow_mut();
+            inner.touches.ended(touches);
+
+            match inner.state {
+                RecognizerState::Began | RecognizerState::Changed => {},
+                RecognizerState::Possible => {
+                    inner.state = RecognizerState::Failed;
+                    return;
+                },
+                _ => return
+            }
         }
 
-        action(self);
+        transition(self, &self.inner, RecognizerState::Cancelled);
     }
 
     fn scroll_did_translate(&self, translation: &Point<i32>, _: &ScrollEvent) {
@@ -128,17 +306,61 @@ impl Recognizer for PanRecognizer {
             }
         }
 
-        let action: Rc<Box<dyn Fn(&PanRecognizer) -> ()>>;
-        {
+        let state = {
             let mut inner = self.inner.borrow_mut();
+
+            if !inner.touches.is_empty() {
+                return;
+            }
+
+            let state = match inner.state {
+                RecognizerState::Began | RecognizerState::Changed => RecognizerState::Changed,
+                _ => {
+                    inner.state = RecognizerState::Possible;
+                    inner.translation = Point::new(0, 0);
+                    inner.initial_position = Point::new(0, 0);
+                    inner.last_position = Point::new(0, 0);
+                    inner.samples.clear();
+                    RecognizerState::Began
+                }
+            };
+
+            // There are no touches to follow, so the position is made up of
+            // the distance scrolled.
+            inner.last_position = Point::new(
+                inner.last_position.x - (translation.x as f32 * render_scale).round() as i32,
+                inner.last_position.y + (translation.y as f32 * render_scale).round() as i32
+            );
             inner.translation = Point::new(
-                -(translation.x as f32 * render_scale).round() as i32,
-                (translation.y as f32 * render_scale).round() as i32
+                inner.last_position.x - inner.initial_position.x,
+                inner.last_position.y - inner.initial_position.y
             );
+            PanRecognizer::add_sample_at(&mut inner, Instant::now());
+
+            state
+        };
+
+        if state == RecognizerState::Began && !may_begin(self) {
+            return;
+        }
+
+        restart_scroll_gesture_end_timer(&self.inner, |inner| PanRecognizer { inner });
+        transition(self, &self.inner, state);
+    }
+
+    fn state(&self) -> RecognizerState {
+        self.inner.borrow().state
+    }
 
-            action = inner.action.clone();
+    fn fail(&self) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.state == RecognizerState::Possible {
+            inner.state = RecognizerState::Failed;
         }
-        action(self);
+    }
+
+    fn arbitration(&self) -> Rc<Arbitration> {
+        self.inner.borrow().arbitration.clone()
     }
 
     fn set_view(&self, view: WeakView) {
@@ -158,12 +380,13 @@ impl Clone for PanRecognizer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ui::gesture::recognizer::RecognizerDelegate;
     use crate::graphics::Rectangle;
 
     #[test]
     fn test_default_state() {
         let recognizer = PanRecognizer::new(|_pan_recognizer| {});
-        assert_eq!(recognizer.state(), PanState::Possible);
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
     }
 
     #[test]
@@ -222,11 +445,124 @@ mod tests {
         let touches = vec![Touch::new(0, Point::new(10, 10))];
         recognizer.touches_began(&touches, &event);
 
-        assert_eq!(recognizer.state(), PanState::Possible);
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
+    }
+
+    #[test]
+    fn test_touches_ended_without_panning() {
+        let recognizer = PanRecognizer::new(|_pan_recognizer| {});
+        let event = TouchEvent::new();
+        let touches = vec![Touch::new(0, Point::new(10, 10))];
+        recognizer.touches_began(&touches, &event);
+        assert!(!recognizer.has_recognized());
+
+        recognizer.touches_ended(&touches, &event);
+        assert_eq!(recognizer.state(), RecognizerState::Failed);
+        assert!(!recognizer.has_recognized());
+    }
+
+    #[test]
+    fn test_touches_cancelled_while_panning() {
+        let recognizer = PanRecognizer::new(|_pan_recognizer| {});
+        let event = TouchEvent::new();
+        recognizer.touches_began(&vec![Touch::new(0, Point::new(10, 10))], &event);
+        recognizer.touches_moved(&vec![Touch::new(0, Point::new(50, 10))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Began);
+        assert!(recognizer.has_recognized());
+
+        recognizer.touches_cancelled(&vec![Touch::new(0, Point::new(50, 10))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Cancelled);
+        assert!(!recognizer.has_recognized());
     }
 
     #[test]
     fn test_touches_began_when_previously_failed() {
-        //TODO: assert_eq!(true, false)
+        let recognizer = PanRecognizer::new(|_pan_recognizer| {});
+        let event = TouchEvent::new();
+        let touches = vec![Touch::new(0, Point::new(10, 10))];
+        recognizer.touches_began(&touches, &event);
+        recognizer.touches_ended(&touches, &event);
+        assert_eq!(recognizer.state(), RecognizerState::Failed);
+
+        recognizer.touches_began(&touches, &event);
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
+    }
+
+    #[test]
+    fn test_require_to_fail() {
+        let recognizer = PanRecognizer::new(|_pan_recognizer| {});
+        let other = PanRecognizer::new(|_pan_recognizer| {});
+        recognizer.require_to_fail(Box::new(other.clone()));
+
+        let event = TouchEvent::new();
+        recognizer.touches_began(&vec![Touch::new(0, Point::new(10, 10))], &event);
+        other.touches_began(&vec![Touch::new(0, Point::new(10, 10))], &event);
+
+        // Waits while the other is still possible.
+        recognizer.touches_moved(&vec![Touch::new(0, Point::new(50, 10))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
+
+        other.fail();
+        recognizer.touches_moved(&vec![Touch::new(0, Point::new(60, 10))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Began);
+    }
+
+    #[test]
+    fn test_velocity() {
+        let recognizer = PanRecognizer::new(|_pan_recognizer| {});
+        let view = View::new(Rectangle::new(0, 0, 100, 100));
+        let event = TouchEvent::new();
+        let start = Instant::now();
+
+        let touch = Touch::new(0, Point::new(10, 10));
+        touch.set_timestamp(start);
+        recognizer.touches_began(&vec![touch.clone()], &event);
+        assert_eq!(recognizer.velocity_in(&view), Point::new(0.0, 0.0));
+
+        touch.set_position(Point::new(30, 0));
+        touch.set_timestamp(start + Duration::from_micros(31_250));
+        recognizer.touches_moved(&vec![touch.clone()], &event);
+
+        touch.set_position(Point::new(50, -10));
+        touch.set_timestamp(start + Duration::from_micros(62_500));
+        recognizer.touches_moved(&vec![touch.clone()], &event);
+        assert_eq!(recognizer.velocity_in(&view), Point::new(640.0, -320.0));
+
+        // Pausing before lifting the finger means it wasn't thrown.
+        touch.set_timestamp(start + Duration::from_millis(500));
+        recognizer.touches_ended(&vec![touch.clone()], &event);
+        assert_eq!(recognizer.velocity_in(&view), Point::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_set_translation_continues_from_it() {
+        let recognizer = PanRecognizer::new(|_pan_recognizer| {});
+        let view = View::new(Rectangle::new(0, 0, 100, 100));
+        let event = TouchEvent::new();
+        recognizer.touches_began(&vec![Touch::new(0, Point::new(10, 10))], &event);
+        recognizer.touches_moved(&vec![Touch::new(0, Point::new(40, 10))], &event);
+        assert_eq!(recognizer.translation_in(&view), Point::new(30, 0));
+
+        recognizer.set_translation(Point::new(0, 0), &view);
+        recognizer.touches_moved(&vec![Touch::new(0, Point::new(45, 12))], &event);
+        assert_eq!(recognizer.translation_in(&view), Point::new(5, 2));
+    }
+
+    struct NeverBegin {}
+    impl RecognizerDelegate for NeverBegin {
+        fn should_begin(&self, _recognizer: &dyn Recognizer) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_delegate_should_begin() {
+        let recognizer = PanRecognizer::new(|_pan_recognizer| {});
+        recognizer.set_delegate(Rc::new(NeverBegin {}));
+
+        let event = TouchEvent::new();
+        recognizer.touches_began(&vec![Touch::new(0, Point::new(10, 10))], &event);
+        recognizer.touches_moved(&vec![Touch::new(0, Point::new(50, 10))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Failed);
     }
 }
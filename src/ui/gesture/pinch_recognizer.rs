@@ -0,0 +1,367 @@
+use crate::ui::{View, WeakView, Window};
+use crate::graphics::Point;
+use std::cell::RefCell;
+use crate::ui::gesture::recognizer::{Recognizer, RecognizerState, Arbitration, may_begin};
+use crate::ui::gesture::recognizer::{ContinuousRecognizerInner, RecognizerAction, transition, restart_scroll_gesture_end_timer};
+use crate::ui::gesture::tracked_touches::TrackedTouches;
+use crate::ui::Touch;
+use crate::ui::timer::Timer;
+use std::rc::Rc;
+use std::time::Instant;
+use crate::ui::event::{TouchEvent, ScrollEvent};
+
+/// How far (in points) the fingers need to move apart or together before the
+/// pinch begins.
+const PINCH_THRESHOLD: f32 = 5.0;
+
+type Action = RecognizerAction<PinchRecognizer>;
+
+/// Recognizes two fingers moving apart or together, for zooming.
+///
+/// Also recognizes ctrl+wheel and pinching on a trackpad, for mouse users.
+pub struct PinchRecognizer {
+    inner: Rc<RefCell<PinchRecognizerInner>>
+}
+
+struct PinchRecognizerInner {
+    view: WeakView,
+    state: RecognizerState,
+    action: Action,
+    arbitration: Rc<Arbitration>,
+    touches: TrackedTouches,
+
+    /// The distance between the fingers that gives a scale of `1.0`.
+    initial_distance: f32,
+
+    scale: f32,
+
+    /// Scale factor per second.
+    velocity: f32,
+
+    last_changed_at: Instant,
+
+    /// Where the mouse was, for pinches without touches.
+    position: Point<i32>,
+    window: Option<Window>,
+
+    /// Ends pinches without touches (see `SCROLL_GESTURE_END_DELAY_MS`).
+    end_timer: Option<Timer>
+}
+
+impl PinchRecognizer {
+    pub fn new(action: impl Fn(&PinchRecognizer) + 'static) -> PinchRecognizer {
+        PinchRecognizer {
+            inner: Rc::new(RefCell::new(PinchRecognizerInner {
+                view: WeakView::none(),
+                state: RecognizerState::Possible,
+                action: Rc::new(Box::new(action)),
+                arbitration: Arbitration::new(),
+                touches: TrackedTouches::new(),
+                initial_distance: 0.0,
+                scale: 1.0,
+                velocity: 0.0,
+                last_changed_at: Instant::now(),
+                position: Point::new(0, 0),
+                window: None,
+                end_timer: None
+            }))
+        }
+    }
+
+    /// The scale relative to where the fingers started; e.g. `2.0` when
+    /// they're twice as far apart.
+    pub fn scale(&self) -> f32 {
+        self.inner.borrow().scale
+    }
+
+    /// Reset the scale; e.g. to `1.0` after applying it, so the next change
+    /// is relative to the current position of the fingers.
+    pub fn set_scale(&self, scale: f32) {
+        let mut inner = self.inner.borrow_mut();
+        if let Some(distance) = inner.touches.distance() {
+            inner.initial_distance = distance / scale;
+        }
+        inner.scale = scale;
+    }
+
+    /// How fast the scale is changing, in scale factor per second.
+    pub fn velocity(&self) -> f32 {
+        self.inner.borrow().velocity
+    }
+
+    pub fn view(&self) -> WeakView {
+        self.inner.borrow().view.clone()
+    }
+
+    /// The point between the fingers, in the coordinates of the given view.
+    pub fn location_in(&self, view: &View) -> Point<i32> {
+        let inner = self.inner.borrow();
+
+        let (position, window) = if inner.touches.is_empty() {
+            (inner.position.clone(), inner.window.clone())
+        } else {
+            (inner.touches.centroid(), inner.touches.window())
+        };
+
+        if let Some(window) = window {
+            window.convert_point_to(&position, view)
+        } else {
+            position
+        }
+    }
+
+    fn update_scale(inner: &mut PinchRecognizerInner, scale: f32) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(inner.last_changed_at).as_secs_f32();
+
+        if elapsed > 0.0 {
+            inner.velocity = (scale - inner.scale) / elapsed;
+        }
+
+        inner.scale = scale;
+        inner.last_changed_at = now;
+    }
+}
+
+impl ContinuousRecognizerInner<PinchRecognizer> for PinchRecognizerInner {
+    fn state(&self) -> RecognizerState {
+        self.state
+    }
+
+    fn set_state(&mut self, state: RecognizerState) {
+        self.state = state;
+    }
+
+    fn action(&self) -> Action {
+        self.action.clone()
+    }
+
+    fn has_touches(&self) -> bool {
+        !self.touches.is_empty()
+    }
+
+    fn end_timer(&mut self) -> &mut Option<Timer> {
+        &mut self.end_timer
+    }
+}
+
+impl Recognizer for PinchRecognizer {
+    fn touches_began(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        let mut inner = self.inner.borrow_mut();
+
+        // A new gesture starts with the first touch after the last one
+        // finished.
+        if inner.touches.is_empty() {
+            inner.state = RecognizerState::Possible;
+        }
+
+        inner.touches.began(touches);
+
+        if inner.state == RecognizerState::Possible && inner.touches.len() == 2 {
+            inner.initial_distance = inner.touches.distance().expect("two touches have a distance");
+            inner.scale = 1.0;
+            inner.velocity = 0.0;
+            inner.last_changed_at = Instant::now();
+        }
+    }
+
+    fn touches_moved(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        let state = {
+            let mut inner = self.inner.borrow_mut();
+            inner.touches.moved(touches);
+
+            let distance = match inner.touches.distance() {
+                Some(distance) => distance,
+                None => return
+            };
+
+            let state = match inner.state {
+                RecognizerState::Possible if (distance - inner.initial_distance).abs() > PINCH_THRESHOLD => {
+                    RecognizerState::Began
+                },
+                RecognizerState::Began | RecognizerState::Changed => RecognizerState::Changed,
+                _ => return
+            };
+
+            if inner.initial_distance > 0.0 {
+                let scale = distance / inner.initial_distance;
+                PinchRecognizer::update_scale(&mut inner, scale);
+            }
+
+            state
+        };
+
+        if state == RecognizerState::Began && !may_begin(self) {
+            return;
+        }
+
+        transition(self, &self.inner, state);
+    }
+
+    fn touches_ended(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        {
+            let mut inner = self.inner.borrow_mut();
+            inner.touches.ended(touches);
+
+            if inner.touches.len() >= 2 {
+                return;
+            }
+
+            match inner.state {
+                RecognizerState::Began | RecognizerState::Changed => {},
+                RecognizerState::Possible => {
+                    inner.state = RecognizerState::Failed;
+                    return;
+                },
+                _ => return
+            }
+        }
+
+        transition(self, &self.inner, RecognizerState::Ended);
+    }
+
+    fn touches_cancelled(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        {
+            let mut inner = self.inner.borrow_mut();
+            inner.touches.ended(touches);
+
+            match inner.state {
+                RecognizerState::Began | RecognizerState::Changed => {},
+                RecognizerState::Possible => {
+                    inner.state = RecognizerState::Failed;
+                    return;
+                },
+                _ => return
+            }
+        }
+
+        transition(self, &self.inner, RecognizerState::Cancelled);
+    }
+
+    fn scroll_did_zoom(&self, scale: f32, event: &ScrollEvent) {
+        let state = {
+            let mut inner = self.inner.borrow_mut();
+
+            if !inner.touches.is_empty() {
+                return;
+            }
+
+            let state = match inner.state {
+                RecognizerState::Began | RecognizerState::Changed => RecognizerState::Changed,
+                _ => {
+                    inner.state = RecognizerState::Possible;
+                    inner.scale = 1.0;
+                    inner.velocity = 0.0;
+                    inner.last_changed_at = Instant::now();
+                    RecognizerState::Began
+                }
+            };
+
+            let new_scale = inner.scale * scale;
+            PinchRecognizer::update_scale(&mut inner, new_scale);
+
+            let touch = event.touch();
+            inner.position = touch.position();
+            inner.window = touch.window();
+
+            state
+        };
+
+        if state == RecognizerState::Began && !may_begin(self) {
+            return;
+        }
+
+        restart_scroll_gesture_end_timer(&self.inner, |inner| PinchRecognizer { inner });
+        transition(self, &self.inner, state);
+    }
+
+    fn state(&self) -> RecognizerState {
+        self.inner.borrow().state
+    }
+
+    fn fail(&self) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.state == RecognizerState::Possible {
+            inner.state = RecognizerState::Failed;
+        }
+    }
+
+    fn arbitration(&self) -> Rc<Arbitration> {
+        self.inner.borrow().arbitration.clone()
+    }
+
+    fn set_view(&self, view: WeakView) {
+        let mut inner = self.inner.borrow_mut();
+        inner.view = view;
+    }
+}
+
+impl Clone for PinchRecognizer {
+    fn clone(&self) -> PinchRecognizer {
+        PinchRecognizer {
+            inner: self.inner.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::gesture::recognizer::end_scroll_gesture;
+
+    #[test]
+    fn test_default_state() {
+        let recognizer = PinchRecognizer::new(|_pinch_recognizer| {});
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
+        assert_eq!(recognizer.scale(), 1.0);
+        assert!(recognizer.view().is_none());
+    }
+
+    #[test]
+    fn test_pinch() {
+        let recognizer = PinchRecognizer::new(|_pinch_recognizer| {});
+        let event = TouchEvent::new();
+        recognizer.touches_began(&vec![Touch::new(1, Point::new(0, 0))], &event);
+        recognizer.touches_began(&vec![Touch::new(2, Point::new(100, 0))], &event);
+
+        recognizer.touches_moved(&vec![Touch::new(2, Point::new(102, 0))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
+
+        recognizer.touches_moved(&vec![Touch::new(2, Point::new(200, 0))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Began);
+        assert_eq!(recognizer.scale(), 2.0);
+
+        recognizer.set_scale(1.0);
+        recognizer.touches_moved(&vec![Touch::new(2, Point::new(100, 0))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Changed);
+        assert_eq!(recognizer.scale(), 0.5);
+
+        recognizer.touches_ended(&vec![Touch::new(2, Point::new(100, 0))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Ended);
+    }
+
+    #[test]
+    fn test_single_touch_fails() {
+        let recognizer = PinchRecognizer::new(|_pinch_recognizer| {});
+        let event = TouchEvent::new();
+        let touches = vec![Touch::new(1, Point::new(0, 0))];
+        recognizer.touches_began(&touches, &event);
+        recognizer.touches_ended(&touches, &event);
+        assert_eq!(recognizer.state(), RecognizerState::Failed);
+        assert!(!recognizer.has_recognized());
+    }
+
+    #[test]
+    fn test_scroll_did_zoom() {
+        let recognizer = PinchRecognizer::new(|_pinch_recognizer| {});
+        let event = ScrollEvent::new();
+        recognizer.scroll_did_zoom(1.5, &event);
+        assert_eq!(recognizer.state(), RecognizerState::Began);
+        recognizer.scroll_did_zoom(2.0, &event);
+        assert_eq!(recognizer.state(), RecognizerState::Changed);
+        assert_eq!(recognizer.scale(), 3.0);
+
+        end_scroll_gesture(&recognizer, &recognizer.inner);
+        assert_eq!(recognizer.state(), RecognizerState::Ended);
+    }
+}
@@ -2,14 +2,72 @@ use crate::ui::Touch;
 use crate::ui::WeakView;
 use crate::ui::event::{TouchEvent, ScrollEvent};
 use crate::graphics::Point;
+use crate::ui::timer::Timer;
+use crate::ui::run_loop::RunLoop;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+/// The state machine shared by every recognizer.
+///
+/// Every recognizer starts out as `Possible`. Discrete gestures (e.g. a tap)
+/// go straight to `Ended` once recognized. Continuous gestures (e.g. a pan)
+/// go through `Began` and `Changed` before `Ended`, or `Cancelled` if the
+/// touches were interrupted. Recognizers that don't see their gesture in the
+/// touches, or lose out to another recognizer, go to `Failed`.
+///
+/// Recognizers go back to `Possible` when the next gesture begins.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum RecognizerState {
+    Possible,
+    Began,
+    Changed,
+    Ended,
+    Cancelled,
+    Failed
+}
+
+/// Decides which recognizers may recognize their gestures, when several
+/// are given the same touches.
+pub trait RecognizerDelegate {
+    /// Whether the recognizer may recognize its gesture. Return `false` to
+    /// make it fail instead.
+    fn should_begin(&self, _recognizer: &dyn Recognizer) -> bool {
+        true
+    }
+
+    /// Whether the two recognizers may both recognize their gestures in the
+    /// same touches; e.g. pinching and rotating a photo at the same time.
+    ///
+    /// By default, the first recognizer to recognize its gesture makes the
+    /// others fail.
+    fn should_recognize_simultaneously(&self, _recognizer: &dyn Recognizer, _other: &dyn Recognizer) -> bool {
+        false
+    }
+}
 
 pub trait Recognizer {
     fn touches_began(&self, touches: &Vec<Touch>, event: &TouchEvent);
     fn touches_ended(&self, touches: &Vec<Touch>, event: &TouchEvent);
     fn touches_moved(&self, touches: &Vec<Touch>, event: &TouchEvent);
 
+    /// The touches were interrupted, e.g. by the window losing focus. The
+    /// recognizer should give up on any gesture in progress.
+    fn touches_cancelled(&self, _touches: &Vec<Touch>, _event: &TouchEvent) {}
+
     fn scroll_did_translate(&self, _translation: &Point<i32>, _event: &ScrollEvent) {}
 
+    /// Zooming without touches; e.g. ctrl+wheel, or pinching on a trackpad.
+    ///
+    /// The `scale` is relative to the last call; e.g. `1.1` to zoom in by
+    /// 10%.
+    fn scroll_did_zoom(&self, _scale: f32, _event: &ScrollEvent) {}
+
+    /// Rotating without touches; e.g. with two fingers on a trackpad.
+    ///
+    /// The `rotation` is in radians, relative to the last call.
+    fn scroll_did_rotate(&self, _rotation: f32, _event: &ScrollEvent) {}
+
     /// If `true`, the recognizer can cancel touches sent to the view if it
     /// recognizes the touch is its gesture.
     ///
@@ -23,6 +81,267 @@ pub trait Recognizer {
         true
     }
 
+    fn state(&self) -> RecognizerState;
+
+    /// Makes the recognizer fail if it's still `Possible`.
+    ///
+    /// Called by the event loop when another recognizer has recognized its
+    /// gesture in the same touches.
+    fn fail(&self);
+
+    /// The relationships between this recognizer and others. See
+    /// `Arbitration`.
+    fn arbitration(&self) -> Rc<Arbitration>;
+
+    /// Whether the recognizer has recognized its gesture in the touches it
+    /// has been given so far. E.g. a pan that has begun.
+    ///
+    /// Checked by the event loop after each touch event to decide whether
+    /// to cancel the touches in the view.
+    fn has_recognized(&self) -> bool {
+        matches!(self.state(), RecognizerState::Began | RecognizerState::Changed | RecognizerState::Ended)
+    }
+
+    fn set_delegate(&self, delegate: Rc<dyn RecognizerDelegate>) {
+        self.arbitration().delegate.replace(Some(delegate));
+    }
+
+    /// Don't recognize this gesture unless the other recognizer fails. E.g.
+    /// a single tap that shouldn't fire for a double-tap:
+    ///
+    /// ```ignore
+    /// single_tap.require_to_fail(Box::new(double_tap.clone()));
+    /// ```
+    fn require_to_fail(&self, other: Box<dyn Recognizer>) {
+        self.arbitration().required_to_fail.borrow_mut().push(other);
+    }
+
+    fn delays_touches_began(&self) -> bool {
+        self.arbitration().delays_touches_began.get()
+    }
+
+    /// If `true`, the view isn't sent `touches_began` until this recognizer
+    /// fails, so the view doesn't react to touches that turn out to be this
+    /// gesture (it is never sent them at all if the gesture is recognized).
+    ///
+    /// Defaults to `false`.
+    fn set_delays_touches_began(&self, delays: bool) {
+        self.arbitration().delays_touches_began.set(delays);
+    }
+
     /// This should only be called by `View.add_gesture_recognizer`.
     fn set_view(&self, view: WeakView);
 }
+
+/// What a recognizer should do, having seen its gesture. See
+/// `Arbitration::resolve`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum Resolution {
+    Begin,
+    Wait,
+    Fail
+}
+
+/// The delegate and failure requirements of a recognizer. Every recognizer
+/// has one, returned by `Recognizer::arbitration`.
+pub struct Arbitration {
+    delegate: RefCell<Option<Rc<dyn RecognizerDelegate>>>,
+    required_to_fail: RefCell<Vec<Box<dyn Recognizer>>>,
+    delays_touches_began: Cell<bool>,
+
+    /// Called once this recognizer is no longer `Possible`, by the
+    /// recognizers waiting for it to fail. See `Arbitration::wait`.
+    waiting: RefCell<Vec<Box<dyn FnOnce()>>>
+}
+
+impl Arbitration {
+    pub(crate) fn new() -> Rc<Arbitration> {
+        Rc::new(Arbitration {
+            delegate: RefCell::new(None),
+            required_to_fail: RefCell::new(Vec::new()),
+            delays_touches_began: Cell::new(false),
+            waiting: RefCell::new(Vec::new())
+        })
+    }
+
+    /// Called by a recognizer that has seen its gesture, before leaving
+    /// `Possible`. It should only recognize the gesture once this returns
+    /// `Begin`, and fail on `Fail`.
+    ///
+    /// `Wait` means a recognizer it requires to fail hasn't yet; it should
+    /// stay `Possible` and try again later, e.g. when called back by
+    /// `Arbitration::wait`.
+    ///
+    /// The recognizer mustn't be borrowed while calling this, as the
+    /// delegate may look at it.
+    pub(crate) fn resolve(&self, recognizer: &dyn Recognizer) -> Resolution {
+        for other in self.required_to_fail.borrow().iter() {
+            match other.state() {
+                RecognizerState::Failed | RecognizerState::Cancelled => {},
+                RecognizerState::Possible => return Resolution::Wait,
+                _ => return Resolution::Fail
+            }
+        }
+
+        let delegate = self.delegate.borrow().clone();
+        if let Some(delegate) = delegate {
+            if !delegate.should_begin(recognizer) {
+                return Resolution::Fail;
+            }
+        }
+
+        Resolution::Begin
+    }
+
+    /// Called by a recognizer after `resolve` returns `Wait`. Calls
+    /// `resolve_again` once the recognizer it's waiting on has failed or
+    /// recognized its gesture, to resolve it again.
+    pub(crate) fn wait(&self, resolve_again: impl FnOnce() + 'static) {
+        let required_to_fail = self.required_to_fail.borrow();
+        let possible = required_to_fail.iter().find(|other| other.state() == RecognizerState::Possible);
+
+        if let Some(other) = possible {
+            other.arbitration().waiting.borrow_mut().push(Box::new(resolve_again));
+        }
+    }
+
+    /// Whether either recognizer's delegate allows the two to recognize
+    /// their gestures at the same time.
+    pub(crate) fn allows_simultaneous(recognizer: &dyn Recognizer, other: &dyn Recognizer) -> bool {
+        let delegate = recognizer.arbitration().delegate.borrow().clone();
+        if let Some(delegate) = delegate {
+            if delegate.should_recognize_simultaneously(recognizer, other) {
+                return true;
+            }
+        }
+
+        let delegate = other.arbitration().delegate.borrow().clone();
+        if let Some(delegate) = delegate {
+            if delegate.should_recognize_simultaneously(other, recognizer) {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+/// Whether the recognizer, having seen its gesture, may recognize it now
+/// (see `Arbitration::resolve`). Makes the recognizer fail if it may not.
+///
+/// The recognizer mustn't be borrowed while calling this.
+pub(crate) fn may_begin(recognizer: &dyn Recognizer) -> bool {
+    match recognizer.arbitration().resolve(recognizer) {
+        Resolution::Begin => true,
+        Resolution::Wait => false,
+        Resolution::Fail => {
+            recognizer.fail();
+            false
+        }
+    }
+}
+
+/// Lets the recognizers waiting for this one to fail (see
+/// `Arbitration::wait`) carry on, once it's no longer `Possible`.
+///
+/// The recognizer mustn't be borrowed while calling this.
+pub(crate) fn resolve_waiting(recognizer: &dyn Recognizer) {
+    if recognizer.state() == RecognizerState::Possible {
+        return;
+    }
+
+    let waiting = std::mem::take(&mut *recognizer.arbitration().waiting.borrow_mut());
+    for resolve_again in waiting {
+        resolve_again();
+    }
+}
+
+/// Wheel and trackpad gestures (see `Recognizer::scroll_did_zoom`) have no
+/// end event, so they are considered ended after this long without another.
+pub(crate) const SCROLL_GESTURE_END_DELAY_MS: u64 = 150;
+
+/// A recognizer's action, called as its state changes.
+pub(crate) type RecognizerAction<R> = Rc<Box<dyn Fn(&R)>>;
+
+/// The inner state of a continuous recognizer that also recognizes wheel or
+/// trackpad gestures (e.g. `PinchRecognizer`), for `transition` and
+/// `restart_scroll_gesture_end_timer`.
+pub(crate) trait ContinuousRecognizerInner<R> {
+    fn state(&self) -> RecognizerState;
+    fn set_state(&mut self, state: RecognizerState);
+    fn action(&self) -> RecognizerAction<R>;
+
+    /// Whether any touches are down. Gestures made with touches end with
+    /// the touches, rather than with the end timer.
+    fn has_touches(&self) -> bool;
+
+    fn end_timer(&mut self) -> &mut Option<Timer>;
+}
+
+/// Moves the recognizer to the given state and calls its action.
+///
+/// The inner state mustn't be borrowed while calling this.
+pub(crate) fn transition<R, I: ContinuousRecognizerInner<R>>(recognizer: &R, inner: &RefCell<I>, state: RecognizerState) {
+    let action = {
+        let mut inner = inner.borrow_mut();
+        inner.set_state(state);
+        inner.action()
+    };
+
+    action(recognizer);
+}
+
+/// Ends the recognizer's wheel or trackpad gesture once another event
+/// hasn't come for `SCROLL_GESTURE_END_DELAY_MS`, replacing the timer
+/// started by the last event.
+///
+/// `recognizer` makes the recognizer from its inner state, for the timer to
+/// call `end_scroll_gesture` with.
+pub(crate) fn restart_scroll_gesture_end_timer<R, I>(inner: &Rc<RefCell<I>>, recognizer: fn(Rc<RefCell<I>>) -> R)
+    where R: 'static, I: ContinuousRecognizerInner<R> + 'static
+{
+    if let Some(timer) = inner.borrow_mut().end_timer().take() {
+        timer.invalidate();
+    }
+
+    let weak_inner = Rc::downgrade(inner);
+    let timer = Timer::new_once_delayed(Duration::from_millis(SCROLL_GESTURE_END_DELAY_MS), move || {
+        if let Some(inner) = weak_inner.upgrade() {
+            end_scroll_gesture(&recognizer(inner.clone()), &inner);
+        }
+    });
+
+    *inner.borrow_mut().end_timer() = Some(timer.clone());
+
+    let run_loop = RunLoop::borrow();
+    run_loop.add_timer(timer);
+}
+
+/// Ends the recognizer's wheel or trackpad gesture, if one is in progress.
+/// Called by the timer started by `restart_scroll_gesture_end_timer`.
+pub(crate) fn end_scroll_gesture<R, I: ContinuousRecognizerInner<R>>(recognizer: &R, inner: &RefCell<I>) {
+    {
+        let mut inner = inner.borrow_mut();
+        *inner.end_timer() = None;
+
+        if inner.has_touches() {
+            return;
+        }
+
+        match inner.state() {
+            RecognizerState::Began | RecognizerState::Changed => {},
+            _ => return
+        }
+    }
+
+    transition(recognizer, inner, RecognizerState::Ended);
+}
+
+/// Whether `to` is further than `distance` away from `from`.
+pub(crate) fn moved_beyond(from: &Point<i32>, to: &Point<i32>, distance: u32) -> bool {
+    let x = (to.x - from.x) as i64;
+    let y = (to.y - from.y) as i64;
+    let distance = distance as i64;
+
+    x * x + y * y > distance * distance
+}
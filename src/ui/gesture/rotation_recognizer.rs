@@ -0,0 +1,377 @@
+use crate::ui::{View, WeakView, Window};
+use crate::graphics::Point;
+use std::cell::RefCell;
+use crate::ui::gesture::recognizer::{Recognizer, RecognizerState, Arbitration, may_begin};
+use crate::ui::gesture::recognizer::{ContinuousRecognizerInner, RecognizerAction, transition, restart_scroll_gesture_end_timer};
+use crate::ui::gesture::tracked_touches::TrackedTouches;
+use crate::ui::Touch;
+use crate::ui::timer::Timer;
+use std::rc::Rc;
+use std::time::Instant;
+use std::f32::consts::PI;
+use crate::ui::event::{TouchEvent, ScrollEvent};
+
+/// How far (in radians) the fingers need to turn before the rotation
+/// begins.
+const ROTATION_THRESHOLD: f32 = 0.1;
+
+type Action = RecognizerAction<RotationRecognizer>;
+
+/// Recognizes two fingers turning around each other.
+///
+/// Also recognizes rotating with two fingers on a trackpad.
+pub struct RotationRecognizer {
+    inner: Rc<RefCell<RotationRecognizerInner>>
+}
+
+struct RotationRecognizerInner {
+    view: WeakView,
+    state: RecognizerState,
+    action: Action,
+    arbitration: Rc<Arbitration>,
+    touches: TrackedTouches,
+
+    /// The angle between the fingers the last time they moved.
+    last_angle: f32,
+
+    /// Radians, clockwise.
+    rotation: f32,
+
+    /// Radians per second.
+    velocity: f32,
+
+    last_changed_at: Instant,
+
+    /// Where the mouse was, for rotations without touches.
+    position: Point<i32>,
+    window: Option<Window>,
+
+    /// Ends rotations without touches (see `SCROLL_GESTURE_END_DELAY_MS`).
+    end_timer: Option<Timer>
+}
+
+impl RotationRecognizer {
+    pub fn new(action: impl Fn(&RotationRecognizer) + 'static) -> RotationRecognizer {
+        RotationRecognizer {
+            inner: Rc::new(RefCell::new(RotationRecognizerInner {
+                view: WeakView::none(),
+                state: RecognizerState::Possible,
+                action: Rc::new(Box::new(action)),
+                arbitration: Arbitration::new(),
+                touches: TrackedTouches::new(),
+                last_angle: 0.0,
+                rotation: 0.0,
+                velocity: 0.0,
+                last_changed_at: Instant::now(),
+                position: Point::new(0, 0),
+                window: None,
+                end_timer: None
+            }))
+        }
+    }
+
+    /// The rotation (in radians, clockwise) since the fingers went down.
+    pub fn rotation(&self) -> f32 {
+        self.inner.borrow().rotation
+    }
+
+    /// Reset the rotation; e.g. to `0.0` after applying it, so the next
+    /// change is relative to the current position of the fingers.
+    pub fn set_rotation(&self, rotation: f32) {
+        self.inner.borrow_mut().rotation = rotation;
+    }
+
+    /// How fast the rotation is changing, in radians per second.
+    pub fn velocity(&self) -> f32 {
+        self.inner.borrow().velocity
+    }
+
+    pub fn view(&self) -> WeakView {
+        self.inner.borrow().view.clone()
+    }
+
+    /// The point between the fingers, in the coordinates of the given view.
+    pub fn location_in(&self, view: &View) -> Point<i32> {
+        let inner = self.inner.borrow();
+
+        let (position, window) = if inner.touches.is_empty() {
+            (inner.position.clone(), inner.window.clone())
+        } else {
+            (inner.touches.centroid(), inner.touches.window())
+        };
+
+        if let Some(window) = window {
+            window.convert_point_to(&position, view)
+        } else {
+            position
+        }
+    }
+
+    fn update_rotation(inner: &mut RotationRecognizerInner, rotation: f32) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(inner.last_changed_at).as_secs_f32();
+
+        if elapsed > 0.0 {
+            inner.velocity = (rotation - inner.rotation) / elapsed;
+        }
+
+        inner.rotation = rotation;
+        inner.last_changed_at = now;
+    }
+}
+
+/// The difference between two angles, wrapped to be within -π to π. E.g.
+/// the fingers turning from just below π to just above -π is a small turn,
+/// not almost a full one.
+fn angle_difference(from: f32, to: f32) -> f32 {
+    let mut difference = to - from;
+
+    while difference > PI {
+        difference -= 2.0 * PI;
+    }
+
+    while difference < -PI {
+        difference += 2.0 * PI;
+    }
+
+    difference
+}
+
+impl ContinuousRecognizerInner<RotationRecognizer> for RotationRecognizerInner {
+    fn state(&self) -> RecognizerState {
+        self.state
+    }
+
+    fn set_state(&mut self, state: RecognizerState) {
+        self.state = state;
+    }
+
+    fn action(&self) -> Action {
+        self.action.clone()
+    }
+
+    fn has_touches(&self) -> bool {
+        !self.touches.is_empty()
+    }
+
+    fn end_timer(&mut self) -> &mut Option<Timer> {
+        &mut self.end_timer
+    }
+}
+
+impl Recognizer for RotationRecognizer {
+    fn touches_began(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        let mut inner = self.inner.borrow_mut();
+
+        // A new gesture starts with the first touch after the last one
+        // finished.
+        if inner.touches.is_empty() {
+            inner.state = RecognizerState::Possible;
+        }
+
+        inner.touches.began(touches);
+
+        if inner.state == RecognizerState::Possible && inner.touches.len() == 2 {
+            inner.last_angle = inner.touches.angle().expect("two touches have an angle");
+            inner.rotation = 0.0;
+            inner.velocity = 0.0;
+            inner.last_changed_at = Instant::now();
+        }
+    }
+
+    fn touches_moved(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        let state = {
+            let mut inner = self.inner.borrow_mut();
+            inner.touches.moved(touches);
+
+            let angle = match inner.touches.angle() {
+                Some(angle) => angle,
+                None => return
+            };
+
+            let rotation = inner.rotation + angle_difference(inner.last_angle, angle);
+            inner.last_angle = angle;
+
+            let state = match inner.state {
+                RecognizerState::Possible => {
+                    // Until it begins, the rotation only accumulates.
+                    inner.rotation = rotation;
+
+                    if rotation.abs() > ROTATION_THRESHOLD {
+                        RecognizerState::Began
+                    } else {
+                        return;
+                    }
+                },
+                RecognizerState::Began | RecognizerState::Changed => RecognizerState::Changed,
+                _ => return
+            };
+
+            RotationRecognizer::update_rotation(&mut inner, rotation);
+
+            state
+        };
+
+        if state == RecognizerState::Began && !may_begin(self) {
+            return;
+        }
+
+        transition(self, &self.inner, state);
+    }
+
+    fn touches_ended(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        {
+            let mut inner = self.inner.borrow_mut();
+            inner.touches.ended(touches);
+
+            if inner.touches.len() >= 2 {
+                return;
+            }
+
+            match inner.state {
+                RecognizerState::Began | RecognizerState::Changed => {},
+                RecognizerState::Possible => {
+                    inner.state = RecognizerState::Failed;
+                    return;
+                },
+                _ => return
+            }
+        }
+
+        transition(self, &self.inner, RecognizerState::Ended);
+    }
+
+    fn touches_cancelled(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        {
+            let mut inner = self.inner.borrow_mut();
+            inner.touches.ended(touches);
+
+            match inner.state {
+                RecognizerState::Began | RecognizerState::Changed => {},
+                RecognizerState::Possible => {
+                    inner.state = RecognizerState::Failed;
+                    return;
+                },
+                _ => return
+            }
+        }
+
+        transition(self, &self.inner, RecognizerState::Cancelled);
+    }
+
+    fn scroll_did_rotate(&self, rotation: f32, event: &ScrollEvent) {
+        let state = {
+            let mut inner = self.inner.borrow_mut();
+
+            if !inner.touches.is_empty() {
+                return;
+            }
+
+            let state = match inner.state {
+                RecognizerState::Began | RecognizerState::Changed => RecognizerState::Changed,
+                _ => {
+                    inner.state = RecognizerState::Possible;
+                    inner.rotation = 0.0;
+                    inner.velocity = 0.0;
+                    inner.last_changed_at = Instant::now();
+                    RecognizerState::Began
+                }
+            };
+
+            let new_rotation = inner.rotation + rotation;
+            RotationRecognizer::update_rotation(&mut inner, new_rotation);
+
+            let touch = event.touch();
+            inner.position = touch.position();
+            inner.window = touch.window();
+
+            state
+        };
+
+        if state == RecognizerState::Began && !may_begin(self) {
+            return;
+        }
+
+        restart_scroll_gesture_end_timer(&self.inner, |inner| RotationRecognizer { inner });
+        transition(self, &self.inner, state);
+    }
+
+    fn state(&self) -> RecognizerState {
+        self.inner.borrow().state
+    }
+
+    fn fail(&self) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.state == RecognizerState::Possible {
+            inner.state = RecognizerState::Failed;
+        }
+    }
+
+    fn arbitration(&self) -> Rc<Arbitration> {
+        self.inner.borrow().arbitration.clone()
+    }
+
+    fn set_view(&self, view: WeakView) {
+        let mut inner = self.inner.borrow_mut();
+        inner.view = view;
+    }
+}
+
+impl Clone for RotationRecognizer {
+    fn clone(&self) -> RotationRecognizer {
+        RotationRecognizer {
+            inner: self.inner.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::gesture::recognizer::end_scroll_gesture;
+
+    #[test]
+    fn test_default_state() {
+        let recognizer = RotationRecognizer::new(|_rotation_recognizer| {});
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
+        assert_eq!(recognizer.rotation(), 0.0);
+    }
+
+    #[test]
+    fn test_angle_difference() {
+        assert_eq!(angle_difference(0.0, 1.0), 1.0);
+        assert!((angle_difference(PI - 0.1, -PI + 0.1) - 0.2).abs() < 0.0001);
+        assert!((angle_difference(-PI + 0.1, PI - 0.1) + 0.2).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_rotation() {
+        let recognizer = RotationRecognizer::new(|_rotation_recognizer| {});
+        let event = TouchEvent::new();
+        recognizer.touches_began(&vec![Touch::new(1, Point::new(0, 0))], &event);
+        recognizer.touches_began(&vec![Touch::new(2, Point::new(100, 0))], &event);
+
+        recognizer.touches_moved(&vec![Touch::new(2, Point::new(100, 5))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
+
+        recognizer.touches_moved(&vec![Touch::new(2, Point::new(0, 100))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Began);
+        assert!((recognizer.rotation() - PI / 2.0).abs() < 0.0001);
+
+        recognizer.touches_ended(&vec![Touch::new(1, Point::new(0, 0))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Ended);
+    }
+
+    #[test]
+    fn test_scroll_did_rotate() {
+        let recognizer = RotationRecognizer::new(|_rotation_recognizer| {});
+        let event = ScrollEvent::new();
+        recognizer.scroll_did_rotate(0.5, &event);
+        recognizer.scroll_did_rotate(0.25, &event);
+        assert_eq!(recognizer.state(), RecognizerState::Changed);
+        assert_eq!(recognizer.rotation(), 0.75);
+
+        end_scroll_gesture(&recognizer, &recognizer.inner);
+        assert_eq!(recognizer.state(), RecognizerState::Ended);
+    }
+}
@@ -0,0 +1,344 @@
+use crate::ui::{View, WeakView};
+use crate::graphics::Point;
+use std::cell::RefCell;
+use crate::ui::gesture::recognizer::{Recognizer, RecognizerState, Arbitration, may_begin};
+use crate::ui::gesture::tracked_touches::TrackedTouches;
+use crate::ui::Touch;
+use std::rc::Rc;
+use std::time::Instant;
+use crate::ui::event::TouchEvent;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SwipeDirection {
+    Right,
+    Left,
+    Up,
+    Down
+}
+
+type Action = Rc<Box<dyn Fn(&SwipeRecognizer)>>;
+
+/// Recognizes a quick movement in one direction; e.g. flicking between the
+/// pages of a carousel.
+///
+/// The action is called once, as soon as the swipe is recognized.
+pub struct SwipeRecognizer {
+    inner: Rc<RefCell<SwipeRecognizerInner>>
+}
+
+struct SwipeRecognizerInner {
+    view: WeakView,
+    state: RecognizerState,
+    action: Action,
+    arbitration: Rc<Arbitration>,
+    directions: Vec<SwipeDirection>,
+    number_of_touches_required: usize,
+    minimum_distance: u32,
+    minimum_velocity: f32,
+    touches: TrackedTouches,
+
+    /// The most touches that were down at once during the swipe.
+    touches_in_swipe: usize,
+
+    initial_position: Point<i32>,
+    began_at: Instant,
+    direction: Option<SwipeDirection>
+}
+
+impl SwipeRecognizer {
+    pub fn new(action: impl Fn(&SwipeRecognizer) + 'static) -> SwipeRecognizer {
+        SwipeRecognizer {
+            inner: Rc::new(RefCell::new(SwipeRecognizerInner {
+                view: WeakView::none(),
+                state: RecognizerState::Possible,
+                action: Rc::new(Box::new(action)),
+                arbitration: Arbitration::new(),
+                directions: vec![SwipeDirection::Right],
+                number_of_touches_required: 1,
+                minimum_distance: 50,
+                minimum_velocity: 300.0,
+                touches: TrackedTouches::new(),
+                touches_in_swipe: 0,
+                initial_position: Point::new(0, 0),
+                began_at: Instant::now(),
+                direction: None
+            }))
+        }
+    }
+
+    pub fn directions(&self) -> Vec<SwipeDirection> {
+        self.inner.borrow().directions.clone()
+    }
+
+    /// The directions the swipe can be in. Swipes in other directions fail.
+    ///
+    /// Defaults to `Right`.
+    pub fn set_directions(&self, directions: &[SwipeDirection]) {
+        self.inner.borrow_mut().directions = directions.to_vec();
+    }
+
+    /// The direction of the recognized swipe, or `None` if it hasn't been
+    /// recognized.
+    pub fn direction(&self) -> Option<SwipeDirection> {
+        self.inner.borrow().direction
+    }
+
+    pub fn number_of_touches_required(&self) -> usize {
+        self.inner.borrow().number_of_touches_required
+    }
+
+    /// Defaults to `1`.
+    pub fn set_number_of_touches_required(&self, touches: usize) {
+        self.inner.borrow_mut().number_of_touches_required = touches;
+    }
+
+    pub fn minimum_distance(&self) -> u32 {
+        self.inner.borrow().minimum_distance
+    }
+
+    /// How far (in points) the touches need to move to be a swipe.
+    ///
+    /// Defaults to `50`.
+    pub fn set_minimum_distance(&self, distance: u32) {
+        self.inner.borrow_mut().minimum_distance = distance;
+    }
+
+    pub fn minimum_velocity(&self) -> f32 {
+        self.inner.borrow().minimum_velocity
+    }
+
+    /// How fast (in points per second) the touches need to move to be a
+    /// swipe rather than a drag.
+    ///
+    /// Defaults to `300.0`.
+    pub fn set_minimum_velocity(&self, velocity: f32) {
+        self.inner.borrow_mut().minimum_velocity = velocity;
+    }
+
+    pub fn view(&self) -> WeakView {
+        self.inner.borrow().view.clone()
+    }
+
+    /// Where the swipe started, in the coordinates of the given view.
+    pub fn location_in(&self, view: &View) -> Point<i32> {
+        let inner = self.inner.borrow();
+        if let Some(window) = inner.touches.window() {
+            window.convert_point_to(&inner.initial_position, view)
+        } else {
+            inner.initial_position.clone()
+        }
+    }
+
+    /// Works out whether the touches so far make a swipe, returning its
+    /// direction. Fails if they're going too slowly or in the wrong
+    /// direction.
+    fn detect(inner: &mut SwipeRecognizerInner, elapsed: f32) -> Option<SwipeDirection> {
+        let position = inner.touches.centroid();
+        let x = position.x - inner.initial_position.x;
+        let y = position.y - inner.initial_position.y;
+
+        let (distance, direction) = if x.abs() >= y.abs() {
+            (x.abs(), if x > 0 { SwipeDirection::Right } else { SwipeDirection::Left })
+        } else {
+            (y.abs(), if y > 0 { SwipeDirection::Down } else { SwipeDirection::Up })
+        };
+
+        if (distance as u32) < inner.minimum_distance {
+            return None;
+        }
+
+        let velocity = if elapsed > 0.0 { distance as f32 / elapsed } else { f32::MAX };
+
+        if velocity < inner.minimum_velocity || !inner.directions.contains(&direction) {
+            inner.state = RecognizerState::Failed;
+            return None;
+        }
+
+        Some(direction)
+    }
+
+    /// Recognizes the swipe, if no other recognizer prevents it. Returns
+    /// `true` if it was recognized.
+    fn recognize(&self, direction: SwipeDirection) -> bool {
+        if !may_begin(self) {
+            return false;
+        }
+
+        let action: Action;
+        {
+            let mut inner = self.inner.borrow_mut();
+            inner.state = RecognizerState::Ended;
+            inner.direction = Some(direction);
+            action = inner.action.clone();
+        }
+
+        action(self);
+        true
+    }
+}
+
+impl Recognizer for SwipeRecognizer {
+    fn touches_began(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        let mut inner = self.inner.borrow_mut();
+
+        // A new gesture starts with the first touch after the last one
+        // finished.
+        if inner.touches.is_empty() {
+            inner.state = RecognizerState::Possible;
+            inner.touches_in_swipe = 0;
+            inner.direction = None;
+            inner.began_at = Instant::now();
+        }
+
+        inner.touches.began(touches);
+        inner.touches_in_swipe = inner.touches_in_swipe.max(inner.touches.len());
+        inner.initial_position = inner.touches.centroid();
+
+        if inner.touches_in_swipe > inner.number_of_touches_required {
+            inner.state = RecognizerState::Failed;
+        }
+    }
+
+    fn touches_moved(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        let direction = {
+            let mut inner = self.inner.borrow_mut();
+            inner.touches.moved(touches);
+
+            if inner.state != RecognizerState::Possible || inner.touches.len() != inner.number_of_touches_required {
+                return;
+            }
+
+            let elapsed = inner.began_at.elapsed().as_secs_f32();
+            match SwipeRecognizer::detect(&mut inner, elapsed) {
+                Some(direction) => direction,
+                None => return
+            }
+        };
+
+        self.recognize(direction);
+    }
+
+    fn touches_ended(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        let direction = {
+            let mut inner = self.inner.borrow_mut();
+
+            if inner.state != RecognizerState::Possible {
+                inner.touches.ended(touches);
+                return;
+            }
+
+            // The last move may have happened along with the touch lifting.
+            inner.touches.moved(touches);
+
+            let direction = if inner.touches_in_swipe == inner.number_of_touches_required {
+                let elapsed = inner.began_at.elapsed().as_secs_f32();
+                SwipeRecognizer::detect(&mut inner, elapsed)
+            } else {
+                None
+            };
+
+            inner.touches.ended(touches);
+            direction
+        };
+
+        // There won't be another chance to recognize the swipe.
+        let recognized = match direction {
+            Some(direction) => self.recognize(direction),
+            None => false
+        };
+
+        if !recognized {
+            self.fail();
+        }
+    }
+
+    fn touches_cancelled(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        let mut inner = self.inner.borrow_mut();
+        inner.touches.ended(touches);
+
+        if inner.state == RecognizerState::Possible {
+            inner.state = RecognizerState::Failed;
+        }
+    }
+
+    fn state(&self) -> RecognizerState {
+        self.inner.borrow().state
+    }
+
+    fn fail(&self) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.state == RecognizerState::Possible {
+            inner.state = RecognizerState::Failed;
+        }
+    }
+
+    fn arbitration(&self) -> Rc<Arbitration> {
+        self.inner.borrow().arbitration.clone()
+    }
+
+    fn set_view(&self, view: WeakView) {
+        let mut inner = self.inner.borrow_mut();
+        inner.view = view;
+    }
+}
+
+impl Clone for SwipeRecognizer {
+    fn clone(&self) -> SwipeRecognizer {
+        SwipeRecognizer {
+            inner: self.inner.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_default_state() {
+        let recognizer = SwipeRecognizer::new(|_swipe_recognizer| {});
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
+        assert_eq!(recognizer.directions(), vec![SwipeDirection::Right]);
+        assert_eq!(recognizer.direction(), None);
+    }
+
+    #[test]
+    fn test_swipe() {
+        let swiped = Rc::new(Cell::new(false));
+        let swiped_clone = swiped.clone();
+        let recognizer = SwipeRecognizer::new(move |_swipe_recognizer| {
+            swiped_clone.set(true);
+        });
+        recognizer.set_directions(&[SwipeDirection::Left, SwipeDirection::Right]);
+
+        let event = TouchEvent::new();
+        recognizer.touches_began(&vec![Touch::new(0, Point::new(100, 100))], &event);
+        recognizer.touches_moved(&vec![Touch::new(0, Point::new(80, 105))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
+
+        recognizer.touches_moved(&vec![Touch::new(0, Point::new(20, 105))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Ended);
+        assert_eq!(recognizer.direction(), Some(SwipeDirection::Left));
+        assert!(swiped.get());
+    }
+
+    #[test]
+    fn test_wrong_direction() {
+        let recognizer = SwipeRecognizer::new(|_swipe_recognizer| {});
+        let event = TouchEvent::new();
+        recognizer.touches_began(&vec![Touch::new(0, Point::new(100, 100))], &event);
+        recognizer.touches_moved(&vec![Touch::new(0, Point::new(100, 10))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Failed);
+        assert_eq!(recognizer.direction(), None);
+    }
+
+    #[test]
+    fn test_too_short() {
+        let recognizer = SwipeRecognizer::new(|_swipe_recognizer| {});
+        let event = TouchEvent::new();
+        recognizer.touches_began(&vec![Touch::new(0, Point::new(100, 100))], &event);
+        recognizer.touches_ended(&vec![Touch::new(0, Point::new(120, 100))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Failed);
+    }
+}
@@ -0,0 +1,465 @@
+use crate::ui::{View, WeakView, Window};
+use crate::graphics::Point;
+use std::cell::RefCell;
+use crate::ui::gesture::recognizer::{Recognizer, RecognizerState, Arbitration, Resolution, moved_beyond, resolve_waiting};
+use crate::ui::Touch;
+use crate::ui::timer::Timer;
+use crate::ui::run_loop::RunLoop;
+use std::rc::{Rc, Weak};
+use std::time::Duration;
+use crate::ui::event::TouchEvent;
+
+/// How long to wait for the next tap of a multi-tap (e.g. the second tap of
+/// a double-tap) before giving up.
+const MAXIMUM_TAP_INTERVAL_MS: u64 = 300;
+
+type Action = Rc<Box<dyn Fn(&TapRecognizer)>>;
+
+/// Where the taps are at once the touches of a tap have ended.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum TapProgress {
+    /// The gesture has failed or touches are still down.
+    Incomplete,
+
+    /// A tap ended, but more are required.
+    NeedsAnotherTap,
+
+    /// The last tap required ended.
+    Complete
+}
+
+/// Recognizes one or more taps, e.g. a double-tap with two fingers.
+///
+/// The action is called once the required number of taps have ended. If it
+/// requires other recognizers to fail (see `Recognizer::require_to_fail`),
+/// e.g. a single tap waiting on a double-tap, it's called once they have.
+pub struct TapRecognizer {
+    inner: Rc<RefCell<TapRecognizerInner>>
+}
+
+struct TapRecognizerInner {
+    view: WeakView,
+    state: RecognizerState,
+    action: Action,
+    arbitration: Rc<Arbitration>,
+    number_of_taps_required: usize,
+    number_of_touches_required: usize,
+    maximum_movement: u32,
+
+    /// The touches currently down, with the position they went down at.
+    touches: Vec<(usize, Point<i32>)>,
+
+    /// The most touches that were down at once during the current tap.
+    touches_in_tap: usize,
+
+    taps: usize,
+    position: Point<i32>,
+    window: Option<Window>,
+
+    /// Fails the gesture if the next tap doesn't begin in time.
+    tap_interval_timer: Option<Timer>,
+
+    /// Set while the taps are done, but waiting on recognizers this one
+    /// requires to fail.
+    waiting_for_required_failures: bool
+}
+
+impl TapRecognizer {
+    pub fn new(action: impl Fn(&TapRecognizer) + 'static) -> TapRecognizer {
+        TapRecognizer {
+            inner: Rc::new(RefCell::new(TapRecognizerInner {
+                view: WeakView::none(),
+                state: RecognizerState::Possible,
+                action: Rc::new(Box::new(action)),
+                arbitration: Arbitration::new(),
+                number_of_taps_required: 1,
+                number_of_touches_required: 1,
+                maximum_movement: 10,
+                touches: Vec::new(),
+                touches_in_tap: 0,
+                taps: 0,
+                position: Point::new(0, 0),
+                window: None,
+                tap_interval_timer: None,
+                waiting_for_required_failures: false
+            }))
+        }
+    }
+
+    pub fn number_of_taps_required(&self) -> usize {
+        self.inner.borrow().number_of_taps_required
+    }
+
+    /// The number of taps in a row needed; e.g. `2` for a double-tap.
+    ///
+    /// Defaults to `1`.
+    pub fn set_number_of_taps_required(&self, taps: usize) {
+        self.inner.borrow_mut().number_of_taps_required = taps;
+    }
+
+    pub fn number_of_touches_required(&self) -> usize {
+        self.inner.borrow().number_of_touches_required
+    }
+
+    /// The number of fingers each tap needs.
+    ///
+    /// Defaults to `1`.
+    pub fn set_number_of_touches_required(&self, touches: usize) {
+        self.inner.borrow_mut().number_of_touches_required = touches;
+    }
+
+    pub fn maximum_movement(&self) -> u32 {
+        self.inner.borrow().maximum_movement
+    }
+
+    /// How far a touch can move (in points) before it's no longer a tap.
+    ///
+    /// Defaults to `10`.
+    pub fn set_maximum_movement(&self, distance: u32) {
+        self.inner.borrow_mut().maximum_movement = distance;
+    }
+
+    pub fn view(&self) -> WeakView {
+        self.inner.borrow().view.clone()
+    }
+
+    /// Where the last tap happened, in the coordinates of the given view.
+    pub fn location_in(&self, view: &View) -> Point<i32> {
+        let inner = self.inner.borrow();
+        if let Some(window) = &inner.window {
+            window.convert_point_to(&inner.position, view)
+        } else {
+            inner.position.clone()
+        }
+    }
+
+    fn wait_for_next_tap(&self) {
+        let weak_inner: Weak<RefCell<TapRecognizerInner>> = Rc::downgrade(&self.inner);
+        let timer = Timer::new_once_delayed(Duration::from_millis(MAXIMUM_TAP_INTERVAL_MS), move || {
+            if let Some(inner) = weak_inner.upgrade() {
+                let recognizer = TapRecognizer { inner };
+                recognizer.fail();
+            }
+        });
+
+        self.inner.borrow_mut().tap_interval_timer = Some(timer.clone());
+
+        let run_loop = RunLoop::borrow();
+        run_loop.add_timer(timer);
+    }
+
+    /// The taps are done. Recognizes them, unless a recognizer this one
+    /// requires to fail hasn't yet, in which case it tries again once that
+    /// one has failed or recognized its gesture.
+    fn recognize(&self) {
+        match self.arbitration().resolve(self) {
+            Resolution::Begin => {
+                let action: Action;
+                {
+                    let mut inner = self.inner.borrow_mut();
+                    inner.waiting_for_required_failures = false;
+                    inner.state = RecognizerState::Ended;
+                    action = inner.action.clone();
+                }
+
+                action(self);
+                resolve_waiting(self);
+            },
+            Resolution::Fail => self.fail(),
+            Resolution::Wait => {
+                self.inner.borrow_mut().waiting_for_required_failures = true;
+
+                let weak_inner: Weak<RefCell<TapRecognizerInner>> = Rc::downgrade(&self.inner);
+                self.arbitration().wait(move || {
+                    if let Some(inner) = weak_inner.upgrade() {
+                        let recognizer = TapRecognizer { inner };
+                        if recognizer.is_waiting_for_required_failures() {
+                            recognizer.recognize();
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    fn is_waiting_for_required_failures(&self) -> bool {
+        self.inner.borrow().waiting_for_required_failures
+    }
+}
+
+impl TapRecognizerInner {
+    /// Forgets the ended touches, counting the tap once the last one has
+    /// ended.
+    fn end_touches(&mut self, touches: &[Touch]) -> TapProgress {
+        for touch in touches.iter() {
+            self.touches.retain(|(id, _)| *id != touch.id());
+            self.position = touch.position();
+        }
+
+        if self.state != RecognizerState::Possible || !self.touches.is_empty() {
+            return TapProgress::Incomplete;
+        }
+
+        if self.touches_in_tap != self.number_of_touches_required {
+            self.state = RecognizerState::Failed;
+            self.touches_in_tap = 0;
+            return TapProgress::Incomplete;
+        }
+
+        self.touches_in_tap = 0;
+        self.taps += 1;
+
+        if self.taps < self.number_of_taps_required {
+            TapProgress::NeedsAnotherTap
+        } else {
+            TapProgress::Complete
+        }
+    }
+}
+
+impl Recognizer for TapRecognizer {
+    fn touches_began(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        if self.is_waiting_for_required_failures() {
+            return;
+        }
+
+        let mut inner = self.inner.borrow_mut();
+
+        // A new gesture starts with the first touch after the last one
+        // finished.
+        if inner.touches.is_empty() && inner.state != RecognizerState::Possible {
+            inner.state = RecognizerState::Possible;
+            inner.taps = 0;
+            inner.touches_in_tap = 0;
+        }
+
+        if let Some(timer) = inner.tap_interval_timer.take() {
+            timer.invalidate();
+        }
+
+        for touch in touches.iter() {
+            inner.touches.push((touch.id(), touch.position()));
+            inner.window = touch.window();
+        }
+
+        inner.touches_in_tap = inner.touches_in_tap.max(inner.touches.len());
+
+        if inner.touches_in_tap > inner.number_of_touches_required {
+            inner.state = RecognizerState::Failed;
+        }
+    }
+
+    fn touches_moved(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        let moved_too_far = {
+            let inner = self.inner.borrow();
+
+            touches.iter().any(|touch| {
+                inner.touches.iter().any(|(id, initial_position)| {
+                    *id == touch.id() && moved_beyond(initial_position, &touch.position(), inner.maximum_movement)
+                })
+            })
+        };
+
+        if moved_too_far {
+            self.fail();
+        }
+    }
+
+    fn touches_ended(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        if self.is_waiting_for_required_failures() {
+            return;
+        }
+
+        let progress = self.inner.borrow_mut().end_touches(touches);
+
+        match progress {
+            TapProgress::Incomplete => {},
+            TapProgress::NeedsAnotherTap => self.wait_for_next_tap(),
+            TapProgress::Complete => self.recognize()
+        }
+    }
+
+    fn touches_cancelled(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        {
+            let mut inner = self.inner.borrow_mut();
+            for touch in touches.iter() {
+                inner.touches.retain(|(id, _)| *id != touch.id());
+            }
+            inner.touches_in_tap = 0;
+        }
+
+        self.fail();
+    }
+
+    fn state(&self) -> RecognizerState {
+        self.inner.borrow().state
+    }
+
+    fn fail(&self) {
+        {
+            let mut inner = self.inner.borrow_mut();
+
+            if inner.state == RecognizerState::Possible {
+                inner.state = RecognizerState::Failed;
+            }
+
+            if let Some(timer) = inner.tap_interval_timer.take() {
+                timer.invalidate();
+            }
+
+            inner.waiting_for_required_failures = false;
+        }
+
+        resolve_waiting(self);
+    }
+
+    fn arbitration(&self) -> Rc<Arbitration> {
+        self.inner.borrow().arbitration.clone()
+    }
+
+    fn set_view(&self, view: WeakView) {
+        let mut inner = self.inner.borrow_mut();
+        inner.view = view;
+    }
+}
+
+impl Clone for TapRecognizer {
+    fn clone(&self) -> TapRecognizer {
+        TapRecognizer {
+            inner: self.inner.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Taps without scheduling the timer that waits for the next tap, which
+    /// can only run on the main thread.
+    fn tap(recognizer: &TapRecognizer, id: usize, position: Point<i32>) -> TapProgress {
+        let event = TouchEvent::new();
+        let touches = vec![Touch::new(id, position)];
+        recognizer.touches_began(&touches, &event);
+        recognizer.inner.borrow_mut().end_touches(&touches)
+    }
+
+    #[test]
+    fn test_default_state() {
+        let recognizer = TapRecognizer::new(|_tap_recognizer| {});
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
+        assert_eq!(recognizer.number_of_taps_required(), 1);
+        assert_eq!(recognizer.number_of_touches_required(), 1);
+        assert!(recognizer.view().is_none());
+    }
+
+    #[test]
+    fn test_single_tap() {
+        let tapped = Rc::new(Cell::new(0));
+        let tapped_clone = tapped.clone();
+        let recognizer = TapRecognizer::new(move |_tap_recognizer| {
+            tapped_clone.set(tapped_clone.get() + 1);
+        });
+
+        let event = TouchEvent::new();
+        let touches = vec![Touch::new(0, Point::new(10, 10))];
+        recognizer.touches_began(&touches, &event);
+        recognizer.touches_ended(&touches, &event);
+        assert_eq!(recognizer.state(), RecognizerState::Ended);
+        assert!(recognizer.has_recognized());
+        assert_eq!(tapped.get(), 1);
+
+        recognizer.touches_began(&touches, &event);
+        recognizer.touches_ended(&touches, &event);
+        assert_eq!(tapped.get(), 2);
+    }
+
+    #[test]
+    fn test_moved_too_far() {
+        let recognizer = TapRecognizer::new(|_tap_recognizer| {});
+        let event = TouchEvent::new();
+        recognizer.touches_began(&vec![Touch::new(0, Point::new(10, 10))], &event);
+        recognizer.touches_moved(&vec![Touch::new(0, Point::new(15, 15))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
+        recognizer.touches_moved(&vec![Touch::new(0, Point::new(30, 10))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Failed);
+        recognizer.touches_ended(&vec![Touch::new(0, Point::new(30, 10))], &event);
+        assert_eq!(recognizer.state(), RecognizerState::Failed);
+    }
+
+    #[test]
+    fn test_double_tap() {
+        let tapped = Rc::new(Cell::new(false));
+        let tapped_clone = tapped.clone();
+        let recognizer = TapRecognizer::new(move |_tap_recognizer| {
+            tapped_clone.set(true);
+        });
+        recognizer.set_number_of_taps_required(2);
+
+        assert_eq!(tap(&recognizer, 0, Point::new(10, 10)), TapProgress::NeedsAnotherTap);
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
+        assert!(!tapped.get());
+
+        assert_eq!(tap(&recognizer, 0, Point::new(12, 10)), TapProgress::Complete);
+        recognizer.recognize();
+        assert_eq!(recognizer.state(), RecognizerState::Ended);
+        assert!(tapped.get());
+    }
+
+    #[test]
+    fn test_two_finger_tap() {
+        let recognizer = TapRecognizer::new(|_tap_recognizer| {});
+        recognizer.set_number_of_touches_required(2);
+
+        assert_eq!(tap(&recognizer, 1, Point::new(10, 10)), TapProgress::Incomplete);
+        assert_eq!(recognizer.state(), RecognizerState::Failed);
+
+        let event = TouchEvent::new();
+        let first = vec![Touch::new(1, Point::new(10, 10))];
+        let second = vec![Touch::new(2, Point::new(40, 10))];
+        recognizer.touches_began(&first, &event);
+        recognizer.touches_began(&second, &event);
+        recognizer.touches_ended(&first, &event);
+        assert_eq!(recognizer.state(), RecognizerState::Possible);
+        recognizer.touches_ended(&second, &event);
+        assert_eq!(recognizer.state(), RecognizerState::Ended);
+    }
+
+    #[test]
+    fn test_single_tap_requires_double_tap_to_fail() {
+        let single_tapped = Rc::new(Cell::new(false));
+        let single_tapped_clone = single_tapped.clone();
+        let single_tap = TapRecognizer::new(move |_tap_recognizer| {
+            single_tapped_clone.set(true);
+        });
+
+        let double_tap = TapRecognizer::new(|_tap_recognizer| {});
+        double_tap.set_number_of_taps_required(2);
+        single_tap.require_to_fail(Box::new(double_tap.clone()));
+
+        assert_eq!(tap(&single_tap, 0, Point::new(10, 10)), TapProgress::Complete);
+        assert_eq!(tap(&double_tap, 0, Point::new(10, 10)), TapProgress::NeedsAnotherTap);
+
+        // Waiting to see if it's a double-tap.
+        assert_eq!(single_tap.arbitration().resolve(&single_tap), Resolution::Wait);
+        single_tap.recognize();
+        assert_eq!(single_tap.state(), RecognizerState::Possible);
+        assert!(!single_tapped.get());
+
+        // Recognized as soon as the double-tap fails.
+        double_tap.fail();
+        assert_eq!(single_tap.state(), RecognizerState::Ended);
+        assert!(single_tapped.get());
+    }
+
+    #[test]
+    fn test_touches_cancelled() {
+        let recognizer = TapRecognizer::new(|_tap_recognizer| {});
+        let event = TouchEvent::new();
+        let touches = vec![Touch::new(0, Point::new(10, 10))];
+        recognizer.touches_began(&touches, &event);
+        recognizer.touches_cancelled(&touches, &event);
+        assert_eq!(recognizer.state(), RecognizerState::Failed);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::graphics::Point;
+use crate::ui::{Touch, Window};
+
+struct TrackedTouch {
+    id: usize,
+    position: Point<i32>
+}
+
+/// The touches a recognizer has been given that are still down, for
+/// recognizers that work with several touches at once; e.g. the distance
+/// between two fingers when pinching.
+pub(crate) struct TrackedTouches {
+    touches: Vec<TrackedTouch>,
+    window: Option<Window>
+}
+
+impl TrackedTouches {
+    pub(crate) fn new() -> TrackedTouches {
+        TrackedTouches {
+            touches: Vec::new(),
+            window: None
+        }
+    }
+
+    pub(crate) fn began(&mut self, touches: &[Touch]) {
+        for touch in touches.iter() {
+            self.touches.retain(|t| t.id != touch.id());
+            self.touches.push(TrackedTouch {
+                id: touch.id(),
+                position: touch.position()
+            });
+            self.window = touch.window();
+        }
+    }
+
+    pub(crate) fn moved(&mut self, touches: &[Touch]) {
+        for touch in touches.iter() {
+            for t in self.touches.iter_mut() {
+                if t.id == touch.id() {
+                    t.position = touch.position();
+                }
+            }
+        }
+    }
+
+    pub(crate) fn ended(&mut self, touches: &[Touch]) {
+        for touch in touches.iter() {
+            self.touches.retain(|t| t.id != touch.id());
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.touches.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.touches.is_empty()
+    }
+
+    /// The window the touches are in.
+    pub(crate) fn window(&self) -> Option<Window> {
+        self.window.clone()
+    }
+
+    /// The point in the middle of all of the touches.
+    pub(crate) fn centroid(&self) -> Point<i32> {
+        if self.touches.is_empty() {
+            return Point::new(0, 0);
+        }
+
+        let count = self.touches.len() as i32;
+        let x: i32 = self.touches.iter().map(|t| t.position.x).sum();
+        let y: i32 = self.touches.iter().map(|t| t.position.y).sum();
+
+        Point::new(x / count, y / count)
+    }
+
+    /// The distance between the first two touches.
+    pub(crate) fn distance(&self) -> Option<f32> {
+        let (x, y) = self.first_two_delta()?;
+        Some((x * x + y * y).sqrt())
+    }
+
+    /// The angle (in radians) of the line from the first touch to the
+    /// second.
+    pub(crate) fn angle(&self) -> Option<f32> {
+        let (x, y) = self.first_two_delta()?;
+        Some(y.atan2(x))
+    }
+
+    fn first_two_delta(&self) -> Option<(f32, f32)> {
+        if self.touches.len() < 2 {
+            return None;
+        }
+
+        let first = &self.touches[0].position;
+        let second = &self.touches[1].position;
+
+        Some(((second.x - first.x) as f32, (second.y - first.y) as f32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_touches() {
+        let mut tracked = TrackedTouches::new();
+        tracked.began(&[Touch::new(1, Point::new(0, 0))]);
+        assert_eq!(tracked.distance(), None);
+
+        tracked.began(&[Touch::new(2, Point::new(30, 40))]);
+        assert_eq!(tracked.len(), 2);
+        assert_eq!(tracked.distance(), Some(50.0));
+        assert_eq!(tracked.centroid(), Point::new(15, 20));
+
+        tracked.moved(&[Touch::new(2, Point::new(0, 10))]);
+        assert_eq!(tracked.distance(), Some(10.0));
+        assert_eq!(tracked.angle(), Some(std::f32::consts::FRAC_PI_2));
+
+        tracked.ended(&[Touch::new(1, Point::new(0, 0))]);
+        assert_eq!(tracked.len(), 1);
+        assert_eq!(tracked.centroid(), Point::new(0, 10));
+    }
+}
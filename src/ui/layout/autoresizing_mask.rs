@@ -0,0 +1,211 @@
+use crate::graphics::{Rectangle, Size};
+
+/// How a view is resized and moved when its superview changes size. See
+/// `View::set_autoresizing_mask`.
+///
+/// Combine flags with `|`. Along each axis, the change in the superview's
+/// size is shared between the flexible margins and dimension, in proportion
+/// to their current sizes. With no flexible parts on an axis, the view keeps
+/// its distance from the top left.
+///
+/// ```ignore
+/// // Stay in the bottom right corner.
+/// button.set_autoresizing_mask(AutoresizingMask::FLEXIBLE_LEFT_MARGIN | AutoresizingMask::FLEXIBLE_TOP_MARGIN);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct AutoresizingMask(u8);
+
+impl AutoresizingMask {
+    pub const NONE: AutoresizingMask = AutoresizingMask(0);
+    pub const FLEXIBLE_LEFT_MARGIN: AutoresizingMask = AutoresizingMask(1);
+    pub const FLEXIBLE_WIDTH: AutoresizingMask = AutoresizingMask(1 << 1);
+    pub const FLEXIBLE_RIGHT_MARGIN: AutoresizingMask = AutoresizingMask(1 << 2);
+    pub const FLEXIBLE_TOP_MARGIN: AutoresizingMask = AutoresizingMask(1 << 3);
+    pub const FLEXIBLE_HEIGHT: AutoresizingMask = AutoresizingMask(1 << 4);
+    pub const FLEXIBLE_BOTTOM_MARGIN: AutoresizingMask = AutoresizingMask(1 << 5);
+
+    /// Fill the superview, keeping the margins; e.g. a view covering the
+    /// whole window.
+    pub const FLEXIBLE_SIZE: AutoresizingMask = AutoresizingMask(1 << 1 | 1 << 4);
+
+    pub fn contains(&self, mask: AutoresizingMask) -> bool {
+        self.0 & mask.0 == mask.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The frame of a view with this mask, after its superview is resized
+    /// from `old_size` to `new_size`.
+    pub fn apply(&self, frame: &Rectangle<i32, u32>, old_size: &Size<u32>, new_size: &Size<u32>) -> Rectangle<i32, u32> {
+        let (x, width) = resize(
+            frame.origin.x,
+            frame.size.width,
+            old_size.width,
+            new_size.width,
+            [
+                self.contains(AutoresizingMask::FLEXIBLE_LEFT_MARGIN),
+                self.contains(AutoresizingMask::FLEXIBLE_WIDTH),
+                self.contains(AutoresizingMask::FLEXIBLE_RIGHT_MARGIN)
+            ]
+        );
+
+        let (y, height) = resize(
+            frame.origin.y,
+            frame.size.height,
+            old_size.height,
+            new_size.height,
+            [
+                self.contains(AutoresizingMask::FLEXIBLE_TOP_MARGIN),
+                self.contains(AutoresizingMask::FLEXIBLE_HEIGHT),
+                self.contains(AutoresizingMask::FLEXIBLE_BOTTOM_MARGIN)
+            ]
+        );
+
+        Rectangle::new(x, y, width, height)
+    }
+}
+
+impl std::ops::BitOr for AutoresizingMask {
+    type Output = AutoresizingMask;
+
+    fn bitor(self, rhs: AutoresizingMask) -> AutoresizingMask {
+        AutoresizingMask(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for AutoresizingMask {
+    fn bitor_assign(&mut self, rhs: AutoresizingMask) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// The new origin and length along one axis. `flexible` is whether the
+/// leading margin, the length and the trailing margin can change.
+fn resize(origin: i32, length: u32, old_size: u32, new_size: u32, flexible: [bool; 3]) -> (i32, u32) {
+    let change = new_size as f32 - old_size as f32;
+    let parts = [
+        origin as f32,
+        length as f32,
+        old_size as f32 - origin as f32 - length as f32
+    ];
+
+    let count = flexible.iter().filter(|flexible| **flexible).count();
+
+    if count == 0 || change == 0.0 {
+        return (origin, length);
+    }
+
+    let total: f32 = parts.iter().zip(flexible)
+        .filter(|(_, flexible)| *flexible)
+        .map(|(part, _)| part.max(0.0))
+        .sum();
+
+    let share = |index: usize| {
+        if !flexible[index] {
+            0.0
+        } else if total > 0.0 {
+            change * parts[index].max(0.0) / total
+        } else {
+            change / count as f32
+        }
+    };
+
+    // Round the edges, so views sharing an edge keep sharing it.
+    let leading = (origin as f32 + share(0)).round();
+    let trailing = (origin as f32 + share(0) + length as f32 + share(1)).round();
+
+    (leading as i32, (trailing - leading).max(0.0) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::View;
+
+    fn apply(mask: AutoresizingMask, frame: Rectangle<i32, u32>) -> Rectangle<i32, u32> {
+        mask.apply(&frame, &Size::new(200, 100), &Size::new(300, 200))
+    }
+
+    #[test]
+    fn test_fixed() {
+        let frame = Rectangle::new(10, 10, 50, 20);
+        assert_eq!(apply(AutoresizingMask::NONE, frame.clone()), frame);
+    }
+
+    #[test]
+    fn test_flexible_size() {
+        let frame = Rectangle::new(10, 10, 180, 80);
+        assert_eq!(apply(AutoresizingMask::FLEXIBLE_SIZE, frame), Rectangle::new(10, 10, 280, 180));
+    }
+
+    #[test]
+    fn test_pinned_to_bottom_right() {
+        let mask = AutoresizingMask::FLEXIBLE_LEFT_MARGIN | AutoresizingMask::FLEXIBLE_TOP_MARGIN;
+        let frame = Rectangle::new(140, 70, 50, 20);
+        assert_eq!(apply(mask, frame), Rectangle::new(240, 170, 50, 20));
+    }
+
+    #[test]
+    fn test_proportional() {
+        // Centred, and keeping its share of the width.
+        let mut mask = AutoresizingMask::FLEXIBLE_LEFT_MARGIN | AutoresizingMask::FLEXIBLE_RIGHT_MARGIN;
+        mask |= AutoresizingMask::FLEXIBLE_WIDTH;
+        let frame = Rectangle::new(50, 0, 100, 100);
+        assert_eq!(apply(mask, frame), Rectangle::new(75, 0, 150, 100));
+
+        // Only the margins: stays centred.
+        let mask = AutoresizingMask::FLEXIBLE_LEFT_MARGIN | AutoresizingMask::FLEXIBLE_RIGHT_MARGIN;
+        let frame = Rectangle::new(50, 0, 100, 100);
+        assert_eq!(apply(mask, frame), Rectangle::new(100, 0, 100, 100));
+    }
+
+    #[test]
+    fn test_shrinking() {
+        let frame = Rectangle::new(10, 10, 180, 80);
+        let shrunk = AutoresizingMask::FLEXIBLE_WIDTH.apply(&frame, &Size::new(200, 100), &Size::new(100, 100));
+        assert_eq!(shrunk, Rectangle::new(10, 10, 80, 80));
+
+        let gone = AutoresizingMask::FLEXIBLE_WIDTH.apply(&frame, &Size::new(200, 100), &Size::new(0, 100));
+        assert_eq!(gone, Rectangle::new(10, 10, 0, 80));
+    }
+
+    #[test]
+    fn test_superview_resized() {
+        let view = View::new(Rectangle::new(0, 0, 200, 100));
+
+        let content = View::new(Rectangle::new(10, 10, 180, 80));
+        content.set_autoresizing_mask(AutoresizingMask::FLEXIBLE_SIZE);
+        view.add_subview(content.clone());
+
+        let corner = View::new(Rectangle::new(130, 50, 40, 20));
+        corner.set_autoresizing_mask(AutoresizingMask::FLEXIBLE_LEFT_MARGIN | AutoresizingMask::FLEXIBLE_TOP_MARGIN);
+        content.add_subview(corner.clone());
+
+        let fixed = View::new(Rectangle::new(5, 5, 10, 10));
+        view.add_subview(fixed.clone());
+
+        // Applied straight away, once, even though both the frame and bounds
+        // change.
+        view.set_frame(Rectangle::new(0, 0, 300, 200));
+        view.set_bounds(Rectangle::new(0, 0, 300, 200));
+
+        assert_eq!(content.frame(), Rectangle::new(10, 10, 280, 180));
+        assert_eq!(content.bounds(), Rectangle::new(0, 0, 280, 180));
+        assert_eq!(corner.frame(), Rectangle::new(230, 150, 40, 20));
+        assert_eq!(fixed.frame(), Rectangle::new(5, 5, 10, 10));
+    }
+
+    #[test]
+    fn test_ignored_with_constraints() {
+        let view = View::new(Rectangle::new(0, 0, 200, 100));
+        let subview = View::new(Rectangle::new(10, 10, 180, 80));
+        subview.set_autoresizing_mask(AutoresizingMask::FLEXIBLE_SIZE);
+        subview.set_translates_frame_into_constraints(false);
+        view.add_subview(subview.clone());
+
+        view.set_frame(Rectangle::new(0, 0, 300, 200));
+        assert_eq!(subview.frame(), Rectangle::new(10, 10, 180, 80));
+    }
+}
@@ -0,0 +1,344 @@
+use crate::ui::view::{View, WeakView};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Which edge, centre or dimension of a view an `Anchor` refers to.
+///
+/// There is no right-to-left support, so leading is always the left edge
+/// and trailing the right edge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Attribute {
+    Leading,
+    Trailing,
+    Top,
+    Bottom,
+    Width,
+    Height,
+    CenterX,
+    CenterY
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    Horizontal,
+    Vertical
+}
+
+impl Attribute {
+    pub fn axis(&self) -> Axis {
+        match self {
+            Attribute::Leading | Attribute::Trailing | Attribute::Width | Attribute::CenterX => Axis::Horizontal,
+            Attribute::Top | Attribute::Bottom | Attribute::Height | Attribute::CenterY => Axis::Vertical
+        }
+    }
+
+    pub fn is_dimension(&self) -> bool {
+        *self == Attribute::Width || *self == Attribute::Height
+    }
+}
+
+/// How the first anchor of a constraint relates to the second.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Relation {
+    Equal,
+    LessThanOrEqual,
+    GreaterThanOrEqual
+}
+
+/// How important it is for a constraint to be satisfied, from `0` to
+/// `1000`. When constraints conflict, the ones with a lower priority give
+/// way. Required constraints must always be satisfied.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Priority(pub f32);
+
+impl Priority {
+    pub const REQUIRED: Priority = Priority(1000.0);
+
+    /// The priority with which views resist being made smaller than their
+    /// intrinsic content size.
+    pub const HIGH: Priority = Priority(750.0);
+
+    /// The priority with which views resist being made larger than their
+    /// intrinsic content size.
+    pub const LOW: Priority = Priority(250.0);
+
+    pub fn is_required(&self) -> bool {
+        self.0 >= Priority::REQUIRED.0
+    }
+}
+
+impl From<f32> for Priority {
+    fn from(priority: f32) -> Priority {
+        Priority(priority)
+    }
+}
+
+/// An edge, centre or dimension of a view, for building constraints. Get
+/// one from `View::leading_anchor`, `View::width_anchor`, etc.
+///
+/// Positions can only be constrained to positions on the same axis, e.g. a
+/// leading anchor to another view's centre x anchor. Dimensions can be
+/// constrained to any dimension, e.g. a width to a height for an aspect
+/// ratio, or to a constant.
+#[derive(Clone)]
+pub struct Anchor {
+    view: WeakView,
+    attribute: Attribute
+}
+
+impl Anchor {
+    pub(crate) fn new(view: &View, attribute: Attribute) -> Anchor {
+        Anchor { view: view.downgrade(), attribute }
+    }
+
+    pub fn view(&self) -> Option<View> {
+        self.view.upgrade()
+    }
+
+    pub fn attribute(&self) -> Attribute {
+        self.attribute
+    }
+
+    pub fn constraint_equal_to(&self, anchor: &Anchor) -> Constraint {
+        self.constraint_to(Relation::Equal, anchor)
+    }
+
+    pub fn constraint_greater_than_or_equal_to(&self, anchor: &Anchor) -> Constraint {
+        self.constraint_to(Relation::GreaterThanOrEqual, anchor)
+    }
+
+    pub fn constraint_less_than_or_equal_to(&self, anchor: &Anchor) -> Constraint {
+        self.constraint_to(Relation::LessThanOrEqual, anchor)
+    }
+
+    /// Constrain a width or height to a fixed size.
+    pub fn constraint_equal_to_constant(&self, constant: f32) -> Constraint {
+        self.constraint_to_constant(Relation::Equal, constant)
+    }
+
+    pub fn constraint_greater_than_or_equal_to_constant(&self, constant: f32) -> Constraint {
+        self.constraint_to_constant(Relation::GreaterThanOrEqual, constant)
+    }
+
+    pub fn constraint_less_than_or_equal_to_constant(&self, constant: f32) -> Constraint {
+        self.constraint_to_constant(Relation::LessThanOrEqual, constant)
+    }
+
+    fn constraint_to(&self, relation: Relation, anchor: &Anchor) -> Constraint {
+        let compatible = if self.attribute.is_dimension() {
+            anchor.attribute.is_dimension()
+        } else {
+            !anchor.attribute.is_dimension() && self.attribute.axis() == anchor.attribute.axis()
+        };
+
+        assert!(compatible, "cannot constrain {:?} anchor to {:?} anchor", self.attribute, anchor.attribute);
+
+        Constraint::new(self.clone(), relation, Some(anchor.clone()), 0.0)
+    }
+
+    fn constraint_to_constant(&self, relation: Relation, constant: f32) -> Constraint {
+        assert!(self.attribute.is_dimension(), "only width and height anchors can be constrained to a constant");
+
+        Constraint::new(self.clone(), relation, None, constant)
+    }
+}
+
+impl std::fmt::Debug for Anchor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Anchor")
+         .field(&self.view.debug_name)
+         .field(&self.view.id())
+         .field(&self.attribute)
+         .finish()
+    }
+}
+
+struct ConstraintInner {
+    first: Anchor,
+    relation: Relation,
+    second: Option<Anchor>,
+    multiplier: f32,
+    constant: f32,
+    priority: Priority,
+    active: bool
+}
+
+/// A linear relationship between two anchors, which layout keeps true:
+///
+/// `first <relation> second * multiplier + constant`
+///
+/// Or, without a second anchor, `first <relation> constant`.
+///
+/// A constraint does nothing until it's activated. It's then kept by the
+/// view of its first anchor, and that view is no longer positioned by its
+/// frame; see `View::set_translates_frame_into_constraints`.
+///
+/// ```ignore
+/// label.leading_anchor().constraint_equal_to(&view.leading_anchor())
+///     .with_constant(20.0)
+///     .activate();
+/// ```
+#[derive(Clone)]
+pub struct Constraint {
+    inner: Rc<RefCell<ConstraintInner>>
+}
+
+impl Constraint {
+    fn new(first: Anchor, relation: Relation, second: Option<Anchor>, constant: f32) -> Constraint {
+        let inner = ConstraintInner {
+            first,
+            relation,
+            second,
+            multiplier: 1.0,
+            constant,
+            priority: Priority::REQUIRED,
+            active: false
+        };
+
+        Constraint { inner: Rc::new(RefCell::new(inner)) }
+    }
+
+    pub fn with_constant(self, constant: f32) -> Constraint {
+        self.set_constant(constant);
+        self
+    }
+
+    pub fn with_multiplier(self, multiplier: f32) -> Constraint {
+        self.inner.borrow_mut().multiplier = multiplier;
+        self.set_needs_layout();
+        self
+    }
+
+    pub fn with_priority(self, priority: impl Into<Priority>) -> Constraint {
+        self.set_priority(priority);
+        self
+    }
+
+    pub fn first_anchor(&self) -> Anchor {
+        self.inner.borrow().first.clone()
+    }
+
+    pub fn second_anchor(&self) -> Option<Anchor> {
+        self.inner.borrow().second.clone()
+    }
+
+    pub fn relation(&self) -> Relation {
+        self.inner.borrow().relation
+    }
+
+    pub fn multiplier(&self) -> f32 {
+        self.inner.borrow().multiplier
+    }
+
+    pub fn constant(&self) -> f32 {
+        self.inner.borrow().constant
+    }
+
+    /// Change the constant, e.g. to animate a view sliding in. The layout is
+    /// updated before the window is next drawn.
+    pub fn set_constant(&self, constant: f32) {
+        {
+            let mut inner = self.inner.borrow_mut();
+
+            if inner.constant == constant {
+                return;
+            }
+
+            inner.constant = constant;
+        }
+
+        self.set_needs_layout();
+    }
+
+    pub fn priority(&self) -> Priority {
+        self.inner.borrow().priority
+    }
+
+    pub fn set_priority(&self, priority: impl Into<Priority>) {
+        self.inner.borrow_mut().priority = priority.into();
+        self.set_needs_layout();
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.inner.borrow().active
+    }
+
+    /// Start laying out views with this constraint.
+    ///
+    /// The view of the first anchor stops translating its frame into
+    /// constraints.
+    pub fn activate(&self) {
+        if self.is_active() {
+            return;
+        }
+
+        let view = self.first_anchor().view().expect("constraint's view was deallocated");
+
+        {
+            let mut inner_view = view.inner_self.borrow_mut();
+            inner_view.constraints.push(self.clone());
+            inner_view.translates_frame_into_constraints = false;
+        }
+
+        self.inner.borrow_mut().active = true;
+        view.set_needs_window_layout();
+    }
+
+    /// Stop laying out views with this constraint. The views keep their
+    /// current frames until something else moves them.
+    pub fn deactivate(&self) {
+        if !self.is_active() {
+            return;
+        }
+
+        self.inner.borrow_mut().active = false;
+
+        if let Some(view) = self.first_anchor().view() {
+            view.inner_self.borrow_mut().constraints.retain(|constraint| constraint != self);
+            view.set_needs_window_layout();
+        }
+    }
+
+    pub fn activate_all(constraints: &[Constraint]) {
+        for constraint in constraints.iter() {
+            constraint.activate();
+        }
+    }
+
+    pub fn deactivate_all(constraints: &[Constraint]) {
+        for constraint in constraints.iter() {
+            constraint.deactivate();
+        }
+    }
+
+    fn set_needs_layout(&self) {
+        if !self.is_active() {
+            return;
+        }
+
+        if let Some(view) = self.first_anchor().view() {
+            view.set_needs_window_layout();
+        }
+    }
+}
+
+impl PartialEq for Constraint {
+    fn eq(&self, rhs: &Constraint) -> bool {
+        Rc::ptr_eq(&self.inner, &rhs.inner)
+    }
+}
+
+impl std::fmt::Debug for Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inner = self.inner.borrow();
+
+        f.debug_struct("Constraint")
+         .field("first", &inner.first)
+         .field("relation", &inner.relation)
+         .field("second", &inner.second)
+         .field("multiplier", &inner.multiplier)
+         .field("constant", &inner.constant)
+         .field("priority", &inner.priority.0)
+         .finish()
+    }
+}
@@ -0,0 +1,623 @@
+//! Constraint-based layout. Views are positioned by `Constraint`s between
+//! their `Anchor`s, solved before the window is drawn.
+//!
+//! Each pass solves every active constraint in the window together. Views
+//! that aren't constrained keep their frames; see
+//! `View::set_translates_frame_into_constraints`.
+//!
+//! Frame-based views can instead follow their superview's size with an
+//! `AutoresizingMask`, applied as soon as the superview is resized.
+
+pub mod autoresizing_mask;
+pub mod constraint;
+mod solver;
+
+pub use autoresizing_mask::AutoresizingMask;
+pub use constraint::{Anchor, Attribute, Axis, Constraint, Priority, Relation};
+
+use crate::graphics::{Point, Rectangle};
+use crate::ui::View;
+use solver::{tiered_strength, Expression, Solver, Variable, REQUIRED};
+use std::cell::Cell;
+use std::collections::HashMap;
+
+/// How strongly a view constrained on only some sides keeps the rest of its
+/// previous frame.
+const STAY_STRENGTH: f64 = 0.01;
+
+thread_local! {
+    /// Whether `layout` is running. The views it moves are already being
+    /// laid out, so they don't ask for another pass.
+    static LAYING_OUT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The position (within the root view) and size of a view, as variables.
+struct FrameVariables {
+    left: Variable,
+    top: Variable,
+    width: Variable,
+    height: Variable
+}
+
+impl FrameVariables {
+    fn new(solver: &mut Solver) -> FrameVariables {
+        FrameVariables {
+            left: solver.new_variable(),
+            top: solver.new_variable(),
+            width: solver.new_variable(),
+            height: solver.new_variable()
+        }
+    }
+
+    fn expression(&self, attribute: Attribute) -> Expression {
+        match attribute {
+            Attribute::Leading => Expression::variable(self.left),
+            Attribute::Trailing => Expression::variable(self.left) + Expression::variable(self.width),
+            Attribute::CenterX => Expression::variable(self.left) + Expression::variable(self.width) * 0.5,
+            Attribute::Top => Expression::variable(self.top),
+            Attribute::Bottom => Expression::variable(self.top) + Expression::variable(self.height),
+            Attribute::CenterY => Expression::variable(self.top) + Expression::variable(self.height) * 0.5,
+            Attribute::Width => Expression::variable(self.width),
+            Attribute::Height => Expression::variable(self.height)
+        }
+    }
+}
+
+/// How many points of priority each tier of solver strengths covers, so
+/// `Priority::LOW` and `Priority::HIGH` are a tier apart.
+const PRIORITY_TIER: f32 = 250.0;
+
+/// The solver strength for a priority. Priorities are strict across tiers
+/// of `PRIORITY_TIER` points: any number of low priority constraints give
+/// way to a high priority one, rather than adding up to outweigh it.
+fn strength(priority: Priority) -> f64 {
+    if priority.is_required() {
+        return REQUIRED;
+    }
+
+    let tiers = priority.0.max(0.0) / PRIORITY_TIER;
+    let tier = tiers.floor();
+
+    tiered_strength(tier as u32, 1.0 + (tiers - tier) as f64)
+}
+
+/// Solves the constraints of `root` and its subviews, and moves the
+/// constrained views to match. `root` keeps its own frame.
+///
+/// Constraints to views outside `root` are ignored. Required constraints
+/// that conflict with each other are dropped (with a warning) until the rest
+/// can be satisfied.
+pub(crate) fn layout_constraints(root: &View) {
+    // Every view, parents before their children.
+    let mut views: Vec<(View, Option<usize>)> = vec![(root.clone(), None)];
+    let mut indices = HashMap::new();
+    let mut constraints = Vec::new();
+
+    let mut index = 0;
+    while index < views.len() {
+        let view = views[index].0.clone();
+        indices.insert(view.id(), index);
+
+        let inner_view = view.inner_self.borrow();
+        constraints.extend(inner_view.constraints.iter().cloned());
+
+        for subview in inner_view.subviews.iter() {
+            views.push((subview.clone(), Some(index)));
+        }
+
+        index += 1;
+    }
+
+    if constraints.is_empty() {
+        return;
+    }
+
+    let index_of = |anchor: &Anchor| {
+        anchor.view().and_then(|view| indices.get(&view.id()).copied())
+    };
+
+    // Constrained views, and their superviews.
+    let mut involved = vec![false; views.len()];
+    involved[0] = true;
+
+    let constraints: Vec<(Constraint, usize, Option<usize>)> = constraints.into_iter().filter_map(|constraint| {
+        let first = index_of(&constraint.first_anchor())?;
+        let second = match constraint.second_anchor() {
+            Some(anchor) => Some(index_of(&anchor)?),
+            None => None
+        };
+
+        for mut index in std::iter::once(first).chain(second) {
+            while !involved[index] {
+                involved[index] = true;
+                index = views[index].1.expect("non-root view without superview");
+            }
+        }
+
+        Some((constraint, first, second))
+    }).collect();
+
+    let mut solver = Solver::new();
+    let mut variables: Vec<Option<FrameVariables>> = views.iter().map(|_| None).collect();
+
+    for (index, (view, parent)) in views.iter().enumerate() {
+        if !involved[index] {
+            continue;
+        }
+
+        let frame_variables = FrameVariables::new(&mut solver);
+        let frame = view.frame();
+
+        // The root stays where it is, as the origin of the layout.
+        let (parent_left, parent_top, origin) = match parent {
+            Some(parent) => {
+                let parent_variables = variables[*parent].as_ref().expect("superview missing layout variables");
+                (Expression::variable(parent_variables.left), Expression::variable(parent_variables.top), frame.origin.clone())
+            },
+            None => (Expression::constant(0.0), Expression::constant(0.0), Point::new(0, 0))
+        };
+
+        let frame_constraints = [
+            Expression::variable(frame_variables.left) - parent_left - Expression::constant(origin.x as f64),
+            Expression::variable(frame_variables.top) - parent_top - Expression::constant(origin.y as f64),
+            Expression::variable(frame_variables.width) - Expression::constant(frame.size.width as f64),
+            Expression::variable(frame_variables.height) - Expression::constant(frame.size.height as f64)
+        ];
+
+        if parent.is_none() || view.translates_frame_into_constraints() {
+            for expression in frame_constraints {
+                solver.add_constraint(expression, Relation::Equal, REQUIRED).expect("frame constraints conflict");
+            }
+        } else {
+            for expression in frame_constraints {
+                solver.add_constraint(expression, Relation::Equal, STAY_STRENGTH).expect("stay constraints conflict");
+            }
+
+            for dimension in [frame_variables.width, frame_variables.height] {
+                solver.add_constraint(Expression::variable(dimension), Relation::GreaterThanOrEqual, REQUIRED).expect("size constraints conflict");
+            }
+
+            if let Some(size) = view.intrinsic_content_size() {
+                let intrinsic = [
+                    (frame_variables.width, size.width as f64),
+                    (frame_variables.height, size.height as f64)
+                ];
+
+                for (dimension, length) in intrinsic {
+                    let expression = Expression::variable(dimension) - Expression::constant(length);
+
+                    // Hugging the content, and resisting compression.
+                    solver.add_constraint(expression.clone(), Relation::LessThanOrEqual, strength(Priority::LOW)).expect("hugging constraint conflicts");
+                    solver.add_constraint(expression, Relation::GreaterThanOrEqual, strength(Priority::HIGH)).expect("compression constraint conflicts");
+                }
+            }
+        }
+
+        variables[index] = Some(frame_variables);
+    }
+
+    let expression_for = |index: usize, attribute: Attribute| {
+        variables[index].as_ref().expect("constrained view missing layout variables").expression(attribute)
+    };
+
+    for (constraint, first, second) in constraints.iter() {
+        let mut expression = expression_for(*first, constraint.first_anchor().attribute())
+            - Expression::constant(constraint.constant() as f64);
+
+        if let (Some(second), Some(anchor)) = (second, constraint.second_anchor()) {
+            expression = expression - expression_for(*second, anchor.attribute()) * constraint.multiplier() as f64;
+        }
+
+        if solver.add_constraint(expression, constraint.relation(), strength(constraint.priority())).is_err() {
+            println!("Warning: unable to satisfy {:?} alongside the other required constraints; it was ignored.", constraint);
+        }
+    }
+
+    // Round the edges rather than the sizes, so neighbouring views don't
+    // end up with gaps between them.
+    let edges = |index: usize| {
+        let frame_variables = variables[index].as_ref().expect("constrained view missing layout variables");
+        let left = solver.value(frame_variables.left);
+        let top = solver.value(frame_variables.top);
+        let right = left + solver.value(frame_variables.width);
+        let bottom = top + solver.value(frame_variables.height);
+        (left.round() as i32, top.round() as i32, right.round() as i32, bottom.round() as i32)
+    };
+
+    for (index, (view, parent)) in views.iter().enumerate() {
+        let parent = match parent {
+            Some(parent) if involved[index] && !view.translates_frame_into_constraints() => *parent,
+            _ => continue
+        };
+
+        let (left, top, right, bottom) = edges(index);
+        let (parent_left, parent_top, _, _) = edges(parent);
+
+        let frame = Rectangle::new(
+            left - parent_left,
+            top - parent_top,
+            (right - left).max(0) as u32,
+            (bottom - top).max(0) as u32
+        );
+
+        if view.frame() != frame {
+            view.set_frame(frame);
+        }
+    }
+}
+
+/// Laying out a view can change the intrinsic size of its superview (e.g.
+/// nested stack views), needing another pass. This many passes is plenty.
+const MAXIMUM_LAYOUT_PASSES: usize = 8;
+
+/// Lays out `root` and the views inside it: solves the constraints, then
+/// calls `Behavior::layout_subviews` for the views that need it, and again
+/// until no views need laying out, as laying them out can change the
+/// constraints (e.g. an intrinsic content size) and solving them can resize
+/// more views.
+///
+/// Returns `false` if views still need laying out after
+/// `MAXIMUM_LAYOUT_PASSES`; they're left to be laid out next time.
+pub(crate) fn layout(root: &View) -> bool {
+    let was_laying_out = LAYING_OUT.with(|laying_out| laying_out.replace(true));
+    let settled = layout_passes(root);
+    LAYING_OUT.with(|laying_out| laying_out.set(was_laying_out));
+
+    settled
+}
+
+/// Whether views are being laid out by `layout` right now.
+pub(crate) fn is_laying_out() -> bool {
+    LAYING_OUT.with(|laying_out| laying_out.get())
+}
+
+fn layout_passes(root: &View) -> bool {
+    for _ in 0..MAXIMUM_LAYOUT_PASSES {
+        layout_constraints(root);
+
+        if !layout_subviews(root) {
+            return true;
+        }
+    }
+
+    println!("Warning: views still needed laying out after {} layout passes; a view's layout may keep changing its own size.", MAXIMUM_LAYOUT_PASSES);
+    false
+}
+
+/// Calls `Behavior::layout_subviews` for `root` and the views inside it
+/// that need laying out, superviews first. Returns whether any did.
+fn layout_subviews(root: &View) -> bool {
+    let mut laid_out = false;
+    let mut stack = vec![root.clone()];
+
+    while let Some(view) = stack.pop() {
+        let needs_layout = std::mem::replace(&mut view.inner_self.borrow_mut().needs_layout, false);
+
+        if needs_layout {
+            view.behavior().layout_subviews();
+            laid_out = true;
+        }
+
+        // After laying out, in case subviews were added or removed.
+        stack.extend(view.subviews().into_iter().rev());
+    }
+
+    laid_out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::Size;
+    use crate::ui::view::DefaultBehavior;
+    use crate::macros::*;
+    use std::cell::{Cell, RefCell};
+
+    custom_view!(
+        SizedView subclasses DefaultBehavior
+
+        struct SizedViewBehavior {
+            size: Size<u32>
+        }
+
+        impl Behavior {
+            fn intrinsic_content_size(&self) -> Option<Size<u32>> {
+                Some(self.size.clone())
+            }
+        }
+    );
+
+    custom_view!(
+        CenteringView subclasses DefaultBehavior
+
+        struct CenteringViewBehavior {
+            layout_count: Cell<usize>
+        }
+
+        impl Behavior {
+            fn layout_subviews(&self) {
+                self.layout_count.set(self.layout_count.get() + 1);
+
+                let view = self.view_type();
+                let size = view.frame().size;
+
+                for subview in view.subviews() {
+                    let mut frame = subview.frame();
+                    frame.origin.x = (size.width as i32 - frame.size.width as i32) / 2;
+                    frame.origin.y = (size.height as i32 - frame.size.height as i32) / 2;
+                    subview.set_frame(frame);
+                }
+            }
+        }
+    );
+
+    custom_view!(
+        HalvingView subclasses DefaultBehavior
+
+        struct HalvingViewBehavior {
+            width: RefCell<Option<Constraint>>
+        }
+
+        impl Behavior {
+            /// Makes the constrained width half of its own.
+            fn layout_subviews(&self) {
+                let width = self.view_type().frame().size.width;
+
+                if let Some(constraint) = self.width.borrow().as_ref() {
+                    constraint.set_constant(width as f32 / 2.0);
+                }
+            }
+        }
+    );
+
+    custom_view!(
+        GrowingView subclasses DefaultBehavior
+
+        struct GrowingViewBehavior {}
+
+        impl Behavior {
+            fn layout_subviews(&self) {
+                let view = self.view_type();
+                let mut frame = view.frame();
+                frame.size.width += 1;
+                view.set_frame(frame);
+            }
+        }
+    );
+
+    fn root() -> View {
+        View::new(Rectangle::new(50, 50, 400, 300))
+    }
+
+    #[test]
+    fn test_pin_to_edges() {
+        let root = root();
+        let view = View::new(Rectangle::new(0, 0, 10, 10));
+        root.add_subview(view.clone());
+
+        Constraint::activate_all(&[
+            view.leading_anchor().constraint_equal_to(&root.leading_anchor()).with_constant(10.0),
+            view.trailing_anchor().constraint_equal_to(&root.trailing_anchor()).with_constant(-10.0),
+            view.top_anchor().constraint_equal_to(&root.top_anchor()).with_constant(20.0),
+            view.height_anchor().constraint_equal_to(&root.height_anchor()).with_multiplier(0.5)
+        ]);
+        assert!(!view.translates_frame_into_constraints());
+
+        layout_constraints(&root);
+        assert_eq!(view.frame(), Rectangle::new(10, 20, 380, 150));
+        assert_eq!(view.bounds().size, Size::new(380, 150));
+    }
+
+    #[test]
+    fn test_center_and_nested_views() {
+        let root = root();
+        let container = View::new(Rectangle::new(100, 50, 200, 200));
+        let view = View::new(Rectangle::new(0, 0, 0, 0));
+        let sibling = View::new(Rectangle::new(0, 0, 0, 0));
+        root.add_subview(container.clone());
+        container.add_subview(view.clone());
+        root.add_subview(sibling.clone());
+
+        Constraint::activate_all(&[
+            view.center_x_anchor().constraint_equal_to(&container.center_x_anchor()),
+            view.center_y_anchor().constraint_equal_to(&container.center_y_anchor()),
+            view.width_anchor().constraint_equal_to_constant(40.0),
+            view.height_anchor().constraint_equal_to(&view.width_anchor()).with_multiplier(0.5),
+
+            // Views in different superviews share the same coordinates.
+            sibling.leading_anchor().constraint_equal_to(&view.trailing_anchor()),
+            sibling.bottom_anchor().constraint_equal_to(&view.top_anchor()),
+            sibling.width_anchor().constraint_equal_to_constant(10.0),
+            sibling.height_anchor().constraint_equal_to_constant(10.0)
+        ]);
+
+        layout_constraints(&root);
+        assert_eq!(container.frame(), Rectangle::new(100, 50, 200, 200));
+        assert_eq!(view.frame(), Rectangle::new(80, 90, 40, 20));
+        assert_eq!(sibling.frame(), Rectangle::new(220, 130, 10, 10));
+    }
+
+    #[test]
+    fn test_priority_tiers() {
+        let root = root();
+        let view = View::new(Rectangle::new(0, 0, 10, 10));
+        root.add_subview(view.clone());
+
+        Constraint::activate_all(&[
+            view.leading_anchor().constraint_equal_to(&root.leading_anchor()),
+            view.top_anchor().constraint_equal_to(&root.top_anchor()),
+            view.height_anchor().constraint_equal_to_constant(10.0),
+            view.width_anchor().constraint_equal_to_constant(100.0).with_priority(Priority::HIGH)
+        ]);
+
+        // Four low priority constraints don't add up to one high priority one.
+        let low: Vec<Constraint> = (0..4)
+            .map(|_| view.width_anchor().constraint_equal_to_constant(0.0).with_priority(Priority::LOW))
+            .collect();
+        Constraint::activate_all(&low);
+
+        layout_constraints(&root);
+        assert_eq!(view.frame().size, Size::new(100, 10));
+    }
+
+    #[test]
+    fn test_priorities() {
+        let root = root();
+        let view = View::new(Rectangle::new(0, 0, 10, 10));
+        root.add_subview(view.clone());
+
+        let width = view.width_anchor().constraint_equal_to_constant(100.0).with_priority(Priority::LOW);
+        Constraint::activate_all(&[
+            view.leading_anchor().constraint_equal_to(&root.leading_anchor()),
+            view.top_anchor().constraint_equal_to(&root.top_anchor()),
+            view.height_anchor().constraint_equal_to_constant(10.0),
+            view.width_anchor().constraint_less_than_or_equal_to_constant(80.0),
+            width.clone()
+        ]);
+
+        layout_constraints(&root);
+        assert_eq!(view.frame().size, Size::new(80, 10));
+
+        width.set_constant(60.0);
+        layout_constraints(&root);
+        assert_eq!(view.frame().size, Size::new(60, 10));
+
+        width.deactivate();
+        assert!(!width.is_active());
+        assert!(!view.constraints().contains(&width));
+        assert_eq!(view.constraints().len(), 4);
+    }
+
+    #[test]
+    fn test_intrinsic_content_size() {
+        let root = root();
+        let view = SizedView::new_all(Rectangle::new(0, 0, 0, 0), Size::new(120, 30));
+        root.add_subview(view.clone());
+
+        Constraint::activate_all(&[
+            view.leading_anchor().constraint_equal_to(&root.leading_anchor()),
+            view.top_anchor().constraint_equal_to(&root.top_anchor())
+        ]);
+
+        layout_constraints(&root);
+        assert_eq!(view.frame(), Rectangle::new(0, 0, 120, 30));
+
+        // Compression resistance gives way to required constraints.
+        view.width_anchor().constraint_less_than_or_equal_to_constant(100.0).activate();
+        layout_constraints(&root);
+        assert_eq!(view.frame(), Rectangle::new(0, 0, 100, 30));
+    }
+
+    #[test]
+    fn test_conflicting_constraints() {
+        let root = root();
+        let view = View::new(Rectangle::new(0, 0, 10, 10));
+        root.add_subview(view.clone());
+
+        Constraint::activate_all(&[
+            view.leading_anchor().constraint_equal_to(&root.leading_anchor()),
+            view.top_anchor().constraint_equal_to(&root.top_anchor()),
+            view.width_anchor().constraint_equal_to_constant(50.0),
+            view.height_anchor().constraint_equal_to_constant(50.0),
+            view.width_anchor().constraint_equal_to_constant(70.0)
+        ]);
+
+        layout_constraints(&root);
+        assert_eq!(view.frame(), Rectangle::new(0, 0, 50, 50));
+    }
+
+    #[test]
+    fn test_layout_subviews() {
+        let view = CenteringView::new_all(Rectangle::new(0, 0, 100, 100), Cell::new(0));
+        let subview = View::new(Rectangle::new(0, 0, 20, 10));
+        view.add_subview(subview.clone());
+
+        view.layout_if_needed();
+        assert_eq!(subview.frame(), Rectangle::new(40, 45, 20, 10));
+        assert_eq!(view.behavior().layout_count.get(), 1);
+
+        // Batched until the next layout, however often it's resized.
+        view.set_frame(Rectangle::new(0, 0, 200, 100));
+        view.set_frame(Rectangle::new(0, 0, 200, 200));
+        assert_eq!(subview.frame(), Rectangle::new(40, 45, 20, 10));
+
+        view.layout_if_needed();
+        assert_eq!(subview.frame(), Rectangle::new(90, 95, 20, 10));
+        assert_eq!(view.behavior().layout_count.get(), 2);
+
+        // Moving without resizing doesn't need layout.
+        view.set_frame(Rectangle::new(10, 10, 200, 200));
+        assert!(!view.needs_layout());
+
+        view.set_needs_layout();
+        view.layout_if_needed();
+        assert_eq!(view.behavior().layout_count.get(), 3);
+    }
+
+    #[test]
+    fn test_layout_until_settled() {
+        let root = HalvingView::new_all(Rectangle::new(0, 0, 400, 300), RefCell::new(None));
+        let view = CenteringView::new_all(Rectangle::new(0, 0, 10, 10), Cell::new(0));
+        let subview = View::new(Rectangle::new(0, 0, 20, 10));
+        root.add_subview(view.clone());
+        view.add_subview(subview.clone());
+
+        let width = view.width_anchor().constraint_equal_to_constant(10.0);
+        Constraint::activate_all(&[
+            view.leading_anchor().constraint_equal_to(&root.leading_anchor()),
+            view.top_anchor().constraint_equal_to(&root.top_anchor()),
+            view.height_anchor().constraint_equal_to_constant(100.0),
+            width.clone()
+        ]);
+        root.behavior().width.replace(Some(width));
+
+        // Laying out the root changes the constraints, so they're solved
+        // again and the resized view is laid out in the same pass.
+        assert!(layout(&root));
+        assert_eq!(view.frame(), Rectangle::new(0, 0, 200, 100));
+        assert_eq!(subview.frame(), Rectangle::new(90, 45, 20, 10));
+        assert!(!view.needs_layout());
+    }
+
+    #[test]
+    fn test_layout_gives_up() {
+        let view = GrowingView::new_all(Rectangle::new(0, 0, 100, 100));
+
+        // Left waiting for the next layout, rather than looping forever.
+        assert!(!layout(&view));
+        assert_eq!(view.frame().size.width, 100 + MAXIMUM_LAYOUT_PASSES as u32);
+        assert!(view.needs_layout());
+    }
+
+    #[test]
+    fn test_follow_moved_sibling() {
+        let root = root();
+        let sibling = View::new(Rectangle::new(10, 10, 50, 20));
+        let view = View::new(Rectangle::new(0, 0, 0, 0));
+        root.add_subview(sibling.clone());
+        root.add_subview(view.clone());
+
+        Constraint::activate_all(&[
+            view.leading_anchor().constraint_equal_to(&sibling.trailing_anchor()).with_constant(10.0),
+            view.top_anchor().constraint_equal_to(&sibling.top_anchor()),
+            view.width_anchor().constraint_equal_to_constant(30.0),
+            view.height_anchor().constraint_equal_to(&sibling.height_anchor())
+        ]);
+
+        root.layout_if_needed();
+        assert_eq!(view.frame(), Rectangle::new(70, 10, 30, 20));
+
+        // Only moving the frame-based sibling still moves the view with it.
+        sibling.set_frame(Rectangle::new(100, 40, 50, 20));
+        assert!(!is_laying_out());
+        root.layout_if_needed();
+        assert_eq!(view.frame(), Rectangle::new(160, 40, 30, 20));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_incompatible_anchors() {
+        let view = View::new(Rectangle::new(0, 0, 10, 10));
+        view.leading_anchor().constraint_equal_to(&view.top_anchor());
+    }
+}
@@ -0,0 +1,586 @@
+//! An incremental Cassowary solver for systems of linear equalities and
+//! inequalities, each with a strength. Required constraints must hold; the
+//! others are satisfied as closely as possible, stronger ones first.
+//!
+//! This follows the simplex tableau approach of the Kiwi implementation
+//! (https://github.com/nucleic/kiwi). Constraints are only ever added, as the
+//! layout pass builds a new solver each time it runs.
+
+use crate::ui::layout::Relation;
+use std::collections::{BTreeMap, HashMap};
+
+const EPSILON: f64 = 1.0e-8;
+
+/// How many times stronger each tier of strengths is than the one below. See
+/// `tiered_strength`.
+const TIER_FACTOR: f64 = 1000.0;
+
+/// The strength of a constraint that must be satisfied; the tier above all
+/// the others. Any strength below this is optional.
+pub(crate) const REQUIRED: f64 = 1.0e12;
+
+/// A strength in one of the tiers `0` to `3`, weighted by `1.0` to `2.0`
+/// within the tier. As with Kiwi's `strength::create`, each tier is
+/// `TIER_FACTOR` times stronger than the one below, so one constraint
+/// outweighs hundreds of conflicting ones from lower tiers rather than
+/// giving way to a few of them.
+pub(crate) fn tiered_strength(tier: u32, weight: f64) -> f64 {
+    TIER_FACTOR.powi(tier.min(3) as i32) * weight.clamp(1.0, 2.0)
+}
+
+fn near_zero(value: f64) -> bool {
+    value.abs() < EPSILON
+}
+
+/// An unknown for the solver to find the value of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct Variable(usize);
+
+/// A linear combination of variables, plus a constant.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Expression {
+    pub terms: Vec<(Variable, f64)>,
+    pub constant: f64
+}
+
+impl Expression {
+    pub fn constant(constant: f64) -> Expression {
+        Expression { terms: Vec::new(), constant }
+    }
+
+    pub fn variable(variable: Variable) -> Expression {
+        Expression { terms: vec![(variable, 1.0)], constant: 0.0 }
+    }
+}
+
+impl std::ops::Add for Expression {
+    type Output = Expression;
+
+    fn add(mut self, rhs: Expression) -> Expression {
+        self.terms.extend(rhs.terms);
+        self.constant += rhs.constant;
+        self
+    }
+}
+
+impl std::ops::Sub for Expression {
+    type Output = Expression;
+
+    fn sub(self, rhs: Expression) -> Expression {
+        self + rhs * -1.0
+    }
+}
+
+impl std::ops::Add<f64> for Expression {
+    type Output = Expression;
+
+    fn add(mut self, rhs: f64) -> Expression {
+        self.constant += rhs;
+        self
+    }
+}
+
+impl std::ops::Mul<f64> for Expression {
+    type Output = Expression;
+
+    fn mul(mut self, rhs: f64) -> Expression {
+        for term in self.terms.iter_mut() {
+            term.1 *= rhs;
+        }
+        self.constant *= rhs;
+        self
+    }
+}
+
+/// A required constraint conflicts with the required constraints already in
+/// the solver.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Unsatisfiable;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum SymbolKind {
+    /// Stands in for a `Variable`.
+    External,
+
+    /// Turns an inequality into an equality.
+    Slack,
+
+    /// How far an optional constraint is from being satisfied.
+    Error,
+
+    /// Marks a required equality; never leaves the tableau's parameters.
+    Dummy
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Symbol {
+    id: usize,
+    kind: SymbolKind
+}
+
+impl Symbol {
+    fn is_pivotable(&self) -> bool {
+        self.kind == SymbolKind::Slack || self.kind == SymbolKind::Error
+    }
+}
+
+/// A row of the tableau: `basic symbol = constant + sum(coefficient * cell)`.
+///
+/// The cells are ordered so solving is deterministic.
+#[derive(Clone, Debug)]
+struct Row {
+    cells: BTreeMap<Symbol, f64>,
+    constant: f64
+}
+
+impl Row {
+    fn new(constant: f64) -> Row {
+        Row { cells: BTreeMap::new(), constant }
+    }
+
+    fn coefficient_for(&self, symbol: Symbol) -> f64 {
+        self.cells.get(&symbol).copied().unwrap_or(0.0)
+    }
+
+    fn insert_symbol(&mut self, symbol: Symbol, coefficient: f64) {
+        let value = self.cells.entry(symbol).or_insert(0.0);
+        *value += coefficient;
+
+        if near_zero(*value) {
+            self.cells.remove(&symbol);
+        }
+    }
+
+    fn insert_row(&mut self, row: &Row, coefficient: f64) {
+        self.constant += row.constant * coefficient;
+
+        for (&symbol, &cell) in row.cells.iter() {
+            self.insert_symbol(symbol, cell * coefficient);
+        }
+    }
+
+    fn reverse_sign(&mut self) {
+        self.constant = -self.constant;
+
+        for cell in self.cells.values_mut() {
+            *cell = -*cell;
+        }
+    }
+
+    /// Rearrange the row (which equals zero) to give the value of `symbol`.
+    fn solve_for(&mut self, symbol: Symbol) {
+        let coefficient = -1.0 / self.cells.remove(&symbol).expect("symbol to solve for is not in the row");
+        self.constant *= coefficient;
+
+        for cell in self.cells.values_mut() {
+            *cell *= coefficient;
+        }
+    }
+
+    /// Rearrange the row (which equals `basic`) to give the value of `symbol`.
+    fn solve_for_pair(&mut self, basic: Symbol, symbol: Symbol) {
+        self.insert_symbol(basic, -1.0);
+        self.solve_for(symbol);
+    }
+
+    fn substitute(&mut self, symbol: Symbol, row: &Row) {
+        if let Some(coefficient) = self.cells.remove(&symbol) {
+            self.insert_row(row, coefficient);
+        }
+    }
+}
+
+pub(crate) struct Solver {
+    rows: BTreeMap<Symbol, Row>,
+    symbols: HashMap<Variable, Symbol>,
+    objective: Row,
+
+    /// The objective used while finding a feasible solution for a new
+    /// constraint without an obvious subject.
+    artificial: Option<Row>,
+
+    /// Constraints that have been added, to rebuild the tableau from when
+    /// adding one fails part way through.
+    constraints: Vec<(Expression, Relation, f64)>,
+
+    next_variable: usize,
+    next_symbol: usize
+}
+
+impl Solver {
+    pub fn new() -> Solver {
+        Solver {
+            rows: BTreeMap::new(),
+            symbols: HashMap::new(),
+            objective: Row::new(0.0),
+            artificial: None,
+            constraints: Vec::new(),
+            next_variable: 0,
+            next_symbol: 0
+        }
+    }
+
+    pub fn new_variable(&mut self) -> Variable {
+        self.next_variable += 1;
+        Variable(self.next_variable)
+    }
+
+    /// Add the constraint `expression <relation> 0`.
+    ///
+    /// Strengths of `REQUIRED` or above must hold; if that conflicts with the
+    /// constraints already added, the constraint is left out and
+    /// `Unsatisfiable` is returned.
+    pub fn add_constraint(&mut self, expression: Expression, relation: Relation, strength: f64) -> Result<(), Unsatisfiable> {
+        if self.add_to_tableau(&expression, relation, strength) {
+            self.constraints.push((expression, relation, strength));
+            Ok(())
+        } else {
+            self.rebuild();
+            Err(Unsatisfiable)
+        }
+    }
+
+    /// The value of the variable that best satisfies the constraints. `0.0`
+    /// for variables that aren't in any constraint.
+    pub fn value(&self, variable: Variable) -> f64 {
+        self.symbols.get(&variable)
+            .and_then(|symbol| self.rows.get(symbol))
+            .map_or(0.0, |row| row.constant)
+    }
+
+    fn rebuild(&mut self) {
+        let constraints = std::mem::take(&mut self.constraints);
+        let next_variable = self.next_variable;
+
+        *self = Solver::new();
+        self.next_variable = next_variable;
+
+        for (expression, relation, strength) in constraints {
+            let added = self.add_to_tableau(&expression, relation, strength);
+            assert!(added, "previously added constraint became unsatisfiable");
+            self.constraints.push((expression, relation, strength));
+        }
+    }
+
+    fn new_symbol(&mut self, kind: SymbolKind) -> Symbol {
+        self.next_symbol += 1;
+        Symbol { id: self.next_symbol, kind }
+    }
+
+    fn symbol_for(&mut self, variable: Variable) -> Symbol {
+        if let Some(symbol) = self.symbols.get(&variable) {
+            return *symbol;
+        }
+
+        let symbol = self.new_symbol(SymbolKind::External);
+        self.symbols.insert(variable, symbol);
+        symbol
+    }
+
+    /// Returns `false` if the constraint is unsatisfiable, in which case the
+    /// tableau may be left inconsistent.
+    fn add_to_tableau(&mut self, expression: &Expression, relation: Relation, strength: f64) -> bool {
+        let (mut row, marker, other) = self.create_row(expression, relation, strength);
+        let mut subject = choose_subject(&row, marker, other);
+
+        if subject.is_none() && row.cells.keys().all(|symbol| symbol.kind == SymbolKind::Dummy) {
+            if !near_zero(row.constant) {
+                return false;
+            }
+
+            subject = Some(marker);
+        }
+
+        match subject {
+            Some(subject) => {
+                row.solve_for(subject);
+                self.substitute(subject, &row);
+                self.rows.insert(subject, row);
+            },
+            None => {
+                if !self.add_with_artificial_variable(&row) {
+                    return false;
+                }
+            }
+        }
+
+        self.optimize(false);
+        true
+    }
+
+    /// Build the tableau row for a constraint, in terms of the current
+    /// parameters (non-basic symbols). Returns the row along with the
+    /// symbols that mark the constraint.
+    fn create_row(&mut self, expression: &Expression, relation: Relation, strength: f64) -> (Row, Symbol, Option<Symbol>) {
+        let mut row = Row::new(expression.constant);
+
+        for &(variable, coefficient) in expression.terms.iter() {
+            if near_zero(coefficient) {
+                continue;
+            }
+
+            let symbol = self.symbol_for(variable);
+
+            if let Some(basic_row) = self.rows.get(&symbol) {
+                row.insert_row(basic_row, coefficient);
+            } else {
+                row.insert_symbol(symbol, coefficient);
+            }
+        }
+
+        let required = strength >= REQUIRED;
+
+        let (marker, other) = match relation {
+            Relation::LessThanOrEqual | Relation::GreaterThanOrEqual => {
+                let coefficient = if relation == Relation::LessThanOrEqual { 1.0 } else { -1.0 };
+                let slack = self.new_symbol(SymbolKind::Slack);
+                row.insert_symbol(slack, coefficient);
+
+                if required {
+                    (slack, None)
+                } else {
+                    let error = self.new_symbol(SymbolKind::Error);
+                    row.insert_symbol(error, -coefficient);
+                    self.objective.insert_symbol(error, strength);
+                    (slack, Some(error))
+                }
+            },
+            Relation::Equal => {
+                if required {
+                    let dummy = self.new_symbol(SymbolKind::Dummy);
+                    row.insert_symbol(dummy, 1.0);
+                    (dummy, None)
+                } else {
+                    let error_plus = self.new_symbol(SymbolKind::Error);
+                    let error_minus = self.new_symbol(SymbolKind::Error);
+                    row.insert_symbol(error_plus, -1.0);
+                    row.insert_symbol(error_minus, 1.0);
+                    self.objective.insert_symbol(error_plus, strength);
+                    self.objective.insert_symbol(error_minus, strength);
+                    (error_plus, Some(error_minus))
+                }
+            }
+        };
+
+        if row.constant < 0.0 {
+            row.reverse_sign();
+        }
+
+        (row, marker, other)
+    }
+
+    /// Add a row with no obvious subject, by finding a feasible solution for
+    /// it through an artificial variable. Returns `false` if there is none.
+    fn add_with_artificial_variable(&mut self, row: &Row) -> bool {
+        let artificial = self.new_symbol(SymbolKind::Slack);
+        self.rows.insert(artificial, row.clone());
+        self.artificial = Some(row.clone());
+
+        self.optimize(true);
+        let artificial_row = self.artificial.take().expect("artificial objective missing after optimizing");
+        let success = near_zero(artificial_row.constant);
+
+        // If the artificial variable is still basic, pivot it out.
+        if let Some(mut row) = self.rows.remove(&artificial) {
+            if row.cells.is_empty() {
+                return success;
+            }
+
+            let entering = match row.cells.keys().find(|symbol| symbol.is_pivotable()) {
+                Some(entering) => *entering,
+                None => return false
+            };
+
+            row.solve_for_pair(artificial, entering);
+            self.substitute(entering, &row);
+            self.rows.insert(entering, row);
+        }
+
+        for row in self.rows.values_mut() {
+            row.cells.remove(&artificial);
+        }
+        self.objective.cells.remove(&artificial);
+
+        success
+    }
+
+    /// Replace `symbol` with `row` throughout the tableau and objectives.
+    fn substitute(&mut self, symbol: Symbol, row: &Row) {
+        for basic_row in self.rows.values_mut() {
+            basic_row.substitute(symbol, row);
+        }
+
+        self.objective.substitute(symbol, row);
+
+        if let Some(artificial) = self.artificial.as_mut() {
+            artificial.substitute(symbol, row);
+        }
+    }
+
+    /// Pivot until the objective (or the artificial objective) can't be
+    /// reduced any further.
+    fn optimize(&mut self, artificial: bool) {
+        loop {
+            let objective = if artificial {
+                self.artificial.as_ref().expect("optimizing missing artificial objective")
+            } else {
+                &self.objective
+            };
+
+            let entering = objective.cells.iter()
+                .find(|(symbol, coefficient)| symbol.kind != SymbolKind::Dummy && **coefficient < 0.0)
+                .map(|(symbol, _)| *symbol);
+
+            let entering = match entering {
+                Some(entering) => entering,
+                None => return
+            };
+
+            // The objective is unbounded. Not possible for layout, where
+            // every error is bounded below by zero.
+            let leaving = match self.leaving_symbol(entering) {
+                Some(leaving) => leaving,
+                None => return
+            };
+
+            let mut row = self.rows.remove(&leaving).expect("leaving row missing from tableau");
+            row.solve_for_pair(leaving, entering);
+            self.substitute(entering, &row);
+            self.rows.insert(entering, row);
+        }
+    }
+
+    /// The basic symbol whose row most restricts how far `entering` can
+    /// increase.
+    fn leaving_symbol(&self, entering: Symbol) -> Option<Symbol> {
+        let mut ratio = f64::MAX;
+        let mut leaving = None;
+
+        for (&symbol, row) in self.rows.iter() {
+            if symbol.kind == SymbolKind::External {
+                continue;
+            }
+
+            let coefficient = row.coefficient_for(entering);
+
+            if coefficient < 0.0 {
+                let row_ratio = -row.constant / coefficient;
+
+                if row_ratio < ratio {
+                    ratio = row_ratio;
+                    leaving = Some(symbol);
+                }
+            }
+        }
+
+        leaving
+    }
+}
+
+/// The symbol to solve a new row for: an external variable if there is one,
+/// otherwise a marker that can be pivoted with a negative coefficient.
+fn choose_subject(row: &Row, marker: Symbol, other: Option<Symbol>) -> Option<Symbol> {
+    if let Some(symbol) = row.cells.keys().find(|symbol| symbol.kind == SymbolKind::External) {
+        return Some(*symbol);
+    }
+
+    std::iter::once(marker).chain(other)
+        .find(|symbol| symbol.is_pivotable() && row.coefficient_for(*symbol) < 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(value: f64, expected: f64) {
+        assert!((value - expected).abs() < 0.001, "{} is not {}", value, expected);
+    }
+
+    #[test]
+    fn test_required_equalities() {
+        let mut solver = Solver::new();
+        let x = solver.new_variable();
+        let y = solver.new_variable();
+
+        // x = 10, y = x * 2 + 5
+        solver.add_constraint(Expression::variable(x) - Expression::constant(10.0), Relation::Equal, REQUIRED).expect("unsatisfiable");
+        solver.add_constraint(Expression::variable(y) - (Expression::variable(x) * 2.0 + 5.0), Relation::Equal, REQUIRED).expect("unsatisfiable");
+
+        assert_near(solver.value(x), 10.0);
+        assert_near(solver.value(y), 25.0);
+    }
+
+    #[test]
+    fn test_strengths() {
+        let mut solver = Solver::new();
+        let width = solver.new_variable();
+
+        solver.add_constraint(Expression::variable(width) - Expression::constant(100.0), Relation::Equal, 250.0).expect("unsatisfiable");
+        solver.add_constraint(Expression::variable(width) - Expression::constant(50.0), Relation::Equal, 750.0).expect("unsatisfiable");
+        assert_near(solver.value(width), 50.0);
+
+        // Required beats both.
+        solver.add_constraint(Expression::variable(width) - Expression::constant(80.0), Relation::GreaterThanOrEqual, REQUIRED).expect("unsatisfiable");
+        assert_near(solver.value(width), 80.0);
+    }
+
+    #[test]
+    fn test_tiers() {
+        let mut solver = Solver::new();
+        let width = solver.new_variable();
+
+        // One constraint from a higher tier beats several from a lower one.
+        for _ in 0..4 {
+            solver.add_constraint(Expression::variable(width), Relation::Equal, tiered_strength(1, 1.0)).expect("unsatisfiable");
+        }
+
+        solver.add_constraint(Expression::variable(width) - Expression::constant(100.0), Relation::Equal, tiered_strength(3, 1.0)).expect("unsatisfiable");
+        assert_near(solver.value(width), 100.0);
+
+        assert!(tiered_strength(3, 2.0) < REQUIRED);
+        assert!(tiered_strength(2, 2.0) < tiered_strength(3, 1.0));
+    }
+
+    #[test]
+    fn test_inequalities() {
+        let mut solver = Solver::new();
+        let left = solver.new_variable();
+        let right = solver.new_variable();
+
+        // 0 <= left, right <= 100, right - left >= 30; and as wide as
+        // possible, but starting at 20.
+        solver.add_constraint(Expression::variable(left), Relation::GreaterThanOrEqual, REQUIRED).expect("unsatisfiable");
+        solver.add_constraint(Expression::variable(right) - Expression::constant(100.0), Relation::LessThanOrEqual, REQUIRED).expect("unsatisfiable");
+        solver.add_constraint(Expression::variable(right) - Expression::variable(left) - Expression::constant(30.0), Relation::GreaterThanOrEqual, REQUIRED).expect("unsatisfiable");
+        solver.add_constraint(Expression::variable(left) - Expression::constant(20.0), Relation::Equal, 500.0).expect("unsatisfiable");
+        solver.add_constraint(Expression::variable(right) - Expression::constant(1000.0), Relation::Equal, 250.0).expect("unsatisfiable");
+
+        assert_near(solver.value(left), 20.0);
+        assert_near(solver.value(right), 100.0);
+    }
+
+    #[test]
+    fn test_unsatisfiable() {
+        let mut solver = Solver::new();
+        let x = solver.new_variable();
+        let y = solver.new_variable();
+
+        solver.add_constraint(Expression::variable(x) - Expression::constant(10.0), Relation::Equal, REQUIRED).expect("unsatisfiable");
+        solver.add_constraint(Expression::variable(y) - Expression::variable(x), Relation::GreaterThanOrEqual, REQUIRED).expect("unsatisfiable");
+
+        let result = solver.add_constraint(Expression::variable(y) - Expression::constant(5.0), Relation::Equal, REQUIRED);
+        assert_eq!(result, Err(Unsatisfiable));
+
+        // The conflicting constraint is left out, and the rest still hold.
+        solver.add_constraint(Expression::variable(y) - Expression::constant(5.0), Relation::Equal, 1.0).expect("unsatisfiable");
+        assert_near(solver.value(x), 10.0);
+        assert_near(solver.value(y), 10.0);
+    }
+
+    #[test]
+    fn test_unknown_variable() {
+        let mut solver = Solver::new();
+        let x = solver.new_variable();
+        assert_eq!(solver.value(x), 0.0);
+    }
+}
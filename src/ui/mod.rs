@@ -14,6 +14,9 @@ pub mod timer;
 pub mod touch;
 pub use touch::Touch;
 
+pub mod cursor;
+pub use cursor::Cursor;
+
 pub mod view;
 pub use view::View;
 pub use view::WeakView;
@@ -23,17 +26,29 @@ mod view_controller;
 pub use view_controller::ViewControllerBehavior;
 pub use view_controller::ViewController;
 pub use view::ScrollView;
+pub use view::TableView;
+pub use view::TableViewCell;
+pub use view::CollectionView;
+pub use view::CollectionViewCell;
+pub use view::StackView;
 pub use view::TextField;
 #[cfg(target_os = "macos")]
 pub use view::WebView;
 
 pub mod gesture;
 
+pub mod layout;
+pub use layout::{Anchor, AutoresizingMask, Constraint};
+
+pub mod animation;
+pub use animation::{Animation, AnimationCurve, ViewAnimation};
+
 pub mod event;
 
 mod window;
 pub use window::Window;
 pub use window::WindowBehavior;
+pub use window::WindowDelegate;
 
 pub mod application;
 
@@ -11,6 +11,7 @@ pub(crate) fn window_display(window_view: View) {
     }
 
     let window = Window::from_view(window_view.clone());
+    window.layout_if_needed();
 
     // Additional reference for view controller notification.
     let window1 = window_view.clone();
@@ -53,14 +54,19 @@ fn draw_view(view: &View, behavior: &WindowBehavior, context: &Context) {
                 inner_view.layer = Some(layer);
             }
 
-            // Recreate layer if clips_to_bounds and bounds size changed
-            if inner_view.clips_to_bounds {
-                if let Some(ref layer) = inner_view.layer {
-                    if *layer.size() != inner_view.bounds.size {
-                        let size = inner_view.bounds.size.clone();
-                        let layer = Layer::new(context.clone(), size, Box::new(view.clone()));
-                        inner_view.layer = Some(layer);
-                    }
+            // Recreate layer if the size it's drawn at has changed (bounds
+            // size for clips_to_bounds, otherwise frame size). E.g. when the
+            // window is resized.
+            let size = if inner_view.clips_to_bounds {
+                inner_view.bounds.size.clone()
+            } else {
+                inner_view.frame.size.clone()
+            };
+
+            if let Some(ref layer) = inner_view.layer {
+                if *layer.size() != size {
+                    let layer = Layer::new(context.clone(), size, Box::new(view.clone()));
+                    inner_view.layer = Some(layer);
                 }
             }
 
@@ -93,7 +99,7 @@ fn draw_view(view: &View, behavior: &WindowBehavior, context: &Context) {
         // subview can never trigger a re-render when it becomes visible.
         draw_view(subview, behavior, context);
 
-        if subview.is_hidden() {
+        if subview.is_hidden() || subview.alpha() == 0.0 {
             continue;
         }
 
@@ -105,7 +111,7 @@ fn draw_view(view: &View, behavior: &WindowBehavior, context: &Context) {
                 bounds.size.width,
                 bounds.size.height,
             );
-            let sub_frame = subview.frame();
+            let sub_frame = subview.transform().apply_to_rect(&subview.frame());
             if !visible_rect.intersects(&sub_frame) {
                 continue;
             }
@@ -127,13 +133,14 @@ fn draw_view(view: &View, behavior: &WindowBehavior, context: &Context) {
             (frame.size.width, frame.size.height)
         };
 
-        let frame_relative_to_superview_bounds = Rectangle::new(
+        let frame_relative_to_superview_bounds = sub_inner_view.transform.apply_to_rect(&Rectangle::new(
             frame.origin.x - bounds.origin.x,
             frame.origin.y - bounds.origin.y,
             dest_width,
             dest_height,
-        );
+        ));
 
+        subview_layer.set_alpha_factor(sub_inner_view.alpha);
         layer.draw_child_layer(subview_layer, &frame_relative_to_superview_bounds);
     }
 }
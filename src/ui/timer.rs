@@ -77,6 +77,15 @@ impl Timer {
         self.inner.fire_at.get().clone()
     }
 
+    // Moves the next fire to `delay` from now, e.g. to put off a once-off
+    // timer that would otherwise have to be replaced. Does nothing once the
+    // timer is invalid.
+    pub fn reschedule(&self, delay: Duration) {
+        if self.is_valid() {
+            self.inner.fire_at.set(Instant::now() + delay);
+        }
+    }
+
     // Sets the timer as invalidated. Meaning the run loop will recognise this:
     // * To not fire
     // * To be removed from the run loop
@@ -148,6 +157,21 @@ mod tests {
         assert!(!timer.is_valid());
     }
 
+    #[test]
+    fn test_reschedule() {
+        let timer = Timer::new_once_delayed(Duration::from_secs(1), || {});
+        let fire_at = timer.fire_at();
+
+        timer.reschedule(Duration::from_secs(360));
+        assert!(timer.fire_at() > fire_at);
+
+        // An invalid timer stays where it is.
+        timer.invalidate();
+        let fire_at = timer.fire_at();
+        timer.reschedule(Duration::from_secs(720));
+        assert_eq!(timer.fire_at(), fire_at);
+    }
+
     static mut FIRED: bool = false;
 
     #[test]
@@ -12,7 +12,20 @@ struct TouchInner {
     phase: TouchPhase,
     view: Option<View>,
     window: Option<Window>,
-    gesture_recognizers: Vec<Weak<Box<dyn Recognizer>>>
+    gesture_recognizers: Vec<Weak<Box<dyn Recognizer>>>,
+    button: Option<MouseButton>,
+    click_count: u8,
+    pressure: f32,
+
+    /// Set once the view has been sent `touches_began` for this touch. It
+    /// may be delayed by a gesture recognizer; see
+    /// `Recognizer::delays_touches_began`.
+    began_in_view: bool,
+
+    /// Set once the view has been sent `touches_cancelled` for this touch.
+    /// Gesture recognizers carry on receiving the touch, but the view
+    /// doesn't.
+    cancelled_in_view: bool
 }
 
 pub struct Touch {
@@ -29,6 +42,29 @@ pub enum TouchPhase {
     Cancelled
 }
 
+/// The mouse button that produced a `Touch`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    X1,
+    X2
+}
+
+impl MouseButton {
+    pub(crate) fn from_sdl(button: sdl2::mouse::MouseButton) -> Option<MouseButton> {
+        match button {
+            sdl2::mouse::MouseButton::Left => Some(MouseButton::Left),
+            sdl2::mouse::MouseButton::Right => Some(MouseButton::Right),
+            sdl2::mouse::MouseButton::Middle => Some(MouseButton::Middle),
+            sdl2::mouse::MouseButton::X1 => Some(MouseButton::X1),
+            sdl2::mouse::MouseButton::X2 => Some(MouseButton::X2),
+            sdl2::mouse::MouseButton::Unknown => None
+        }
+    }
+}
+
 impl Touch {
     pub fn new(id: usize, position: Point<i32>) -> Touch {
         Touch {
@@ -39,17 +75,84 @@ impl Touch {
                 phase: TouchPhase::Began,
                 view: None,
                 window: None,
-                gesture_recognizers: Vec::new()
+                gesture_recognizers: Vec::new(),
+                button: None,
+                click_count: 1,
+                pressure: 1.0,
+                began_in_view: false,
+                cancelled_in_view: false
             }))
         }
     }
 
+    /// A touch produced by pressing a mouse button.
+    pub(crate) fn new_with_mouse_button(id: usize, position: Point<i32>, button: MouseButton, click_count: u8) -> Touch {
+        let touch = Touch::new(id, position);
+        {
+            let mut inner = touch.inner.borrow_mut();
+            inner.button = Some(button);
+            inner.click_count = click_count;
+        }
+        touch
+    }
+
+    /// A touch produced by a finger on a touchscreen.
+    pub(crate) fn new_with_finger(id: usize, position: Point<i32>, pressure: f32) -> Touch {
+        let touch = Touch::new(id, position);
+        touch.set_pressure(pressure);
+        touch
+    }
+
+    /// The mouse button that produced this touch, or `None` if it didn't come
+    /// from a mouse (e.g. a finger on a touchscreen).
+    pub fn button(&self) -> Option<MouseButton> {
+        self.inner.borrow().button
+    }
+
+    /// The number of clicks in quick succession this touch is part of. E.g.
+    /// `2` for the second click of a double-click.
+    ///
+    /// Always `1` for touches that didn't come from a mouse.
+    pub fn click_count(&self) -> u8 {
+        self.inner.borrow().click_count
+    }
+
+    /// How hard the touch is pressing, from `0.0` to `1.0`.
+    ///
+    /// Always `1.0` for touches from a mouse or from touchscreens that don't
+    /// report pressure.
+    pub fn pressure(&self) -> f32 {
+        self.inner.borrow().pressure
+    }
+
+    pub(crate) fn set_pressure(&self, pressure: f32) {
+        self.inner.borrow_mut().pressure = pressure;
+    }
+
+    pub(crate) fn has_began_in_view(&self) -> bool {
+        self.inner.borrow().began_in_view
+    }
+
+    pub(crate) fn set_began_in_view(&self) {
+        self.inner.borrow_mut().began_in_view = true;
+    }
+
+    pub(crate) fn is_cancelled_in_view(&self) -> bool {
+        self.inner.borrow().cancelled_in_view
+    }
+
+    pub(crate) fn set_cancelled_in_view(&self) {
+        self.inner.borrow_mut().cancelled_in_view = true;
+    }
+
     pub fn position(&self) -> Point<i32> {
         self.inner.borrow().position.clone()
     }
 
+    /// Moves the touch, updating its timestamp to now.
     pub(crate) fn set_position(&self, position: Point<i32>) {
         self.inner.borrow_mut().position = position;
+        self.set_timestamp(Instant::now());
     }
 
     pub(crate) fn set_view(&self, view: View) {
@@ -90,10 +193,15 @@ impl Touch {
         self.inner.borrow_mut().phase = phase;
     }
 
+    /// When the touch began, or last moved.
     pub fn timestamp(&self) -> Instant {
         self.inner.borrow().timestamp
     }
 
+    pub(crate) fn set_timestamp(&self, timestamp: Instant) {
+        self.inner.borrow_mut().timestamp = timestamp;
+    }
+
     pub fn id(&self) -> usize {
         self.inner.borrow().id
     }
@@ -161,6 +269,36 @@ mod tests {
         assert_eq!(touch.view(), Some(view));
     }
 
+    #[test]
+    fn test_button_and_click_count() {
+        let touch = Touch::new(0, Point { x: 5, y: 5 });
+        assert_eq!(touch.button(), None);
+        assert_eq!(touch.click_count(), 1);
+
+        let touch = Touch::new_with_mouse_button(0, Point { x: 5, y: 5 }, MouseButton::Right, 2);
+        assert_eq!(touch.button(), Some(MouseButton::Right));
+        assert_eq!(touch.click_count(), 2);
+    }
+
+    #[test]
+    fn test_pressure() {
+        let touch = Touch::new(0, Point { x: 5, y: 5 });
+        assert_eq!(touch.pressure(), 1.0);
+
+        let touch = Touch::new_with_finger(1, Point { x: 5, y: 5 }, 0.5);
+        assert_eq!(touch.pressure(), 0.5);
+        assert_eq!(touch.button(), None);
+    }
+
+    #[test]
+    fn test_set_position_updates_timestamp() {
+        let touch = Touch::new(0, Point { x: 5, y: 5 });
+        let began = touch.timestamp();
+        touch.set_position(Point { x: 6, y: 5 });
+        assert_eq!(touch.position(), Point { x: 6, y: 5 });
+        assert!(touch.timestamp() >= began);
+    }
+
     #[test]
     fn test_set_phase() {
         let mut touch = Touch::new(0, Point { x: 5, y: 5 });
@@ -1,5 +1,6 @@
-use crate::ui::{View, WeakView, Touch};
+use crate::ui::{View, WeakView, Touch, Cursor};
 use crate::ui::press::Press;
+use crate::graphics::{Point, Size};
 use std::rc::Rc;
 use std::cell::RefCell;
 
@@ -49,6 +50,118 @@ pub trait Behavior {
     fn touches_ended(&self, _touches: &Vec<Touch>) {}
     fn touches_moved(&self, _touches: &Vec<Touch>) {}
 
+    /// Called instead of `touches_ended` when the touches were taken over by
+    /// a gesture recognizer (see `Recognizer::cancels_touches_in_view`), or
+    /// interrupted, e.g. by the window losing focus.
+    ///
+    /// Undo anything started in `touches_began` without acting on it; e.g. a
+    /// button shouldn't fire its action.
+    fn touches_cancelled(&self, _touches: &Vec<Touch>) {}
+
+    /// Called when the right mouse button is pressed over this view. E.g. to
+    /// show a context menu.
+    ///
+    /// Passed on to the next responder (the superview) by default.
+    fn right_mouse_down(&self, touch: &Touch) {
+        if let Some(next) = self.next_responder() {
+            next.borrow().right_mouse_down(touch);
+        }
+    }
+
+    /// Called when the right mouse button is released, on the view that
+    /// received `right_mouse_down`.
+    fn right_mouse_up(&self, touch: &Touch) {
+        if let Some(next) = self.next_responder() {
+            next.borrow().right_mouse_up(touch);
+        }
+    }
+
+    /// Called when a mouse button other than the left or right is pressed over
+    /// this view. See `Touch::button` for which one.
+    fn other_mouse_down(&self, touch: &Touch) {
+        if let Some(next) = self.next_responder() {
+            next.borrow().other_mouse_down(touch);
+        }
+    }
+
+    /// Called when a mouse button other than the left or right is released,
+    /// on the view that received `other_mouse_down`.
+    fn other_mouse_up(&self, touch: &Touch) {
+        if let Some(next) = self.next_responder() {
+            next.borrow().other_mouse_up(touch);
+        }
+    }
+
+    /// Called when the mouse moves over this view (or one of its subviews),
+    /// including while a mouse button is pressed.
+    fn mouse_entered(&self) {}
+
+    /// Called when the mouse is no longer over this view or any of its
+    /// subviews.
+    fn mouse_exited(&self) {}
+
+    /// Called when the mouse moves within this view while no mouse button is
+    /// pressed. Only the deepest view under the mouse (see `View::hit_test`)
+    /// receives this. The position is in this view's coordinate space.
+    ///
+    /// Movement with a button pressed is delivered as touches instead; see
+    /// `touches_moved`.
+    fn mouse_moved(&self, _position: &Point<i32>) {}
+
+    /// The cursor to show while the mouse is over the given point, in this
+    /// view's coordinate space.
+    ///
+    /// Return `None` to use the cursor of the superview. If no view in the
+    /// hierarchy specifies one, the `Cursor::Arrow` is shown.
+    fn cursor_for_point(&self, point: &Point<i32>) -> Option<Cursor> {
+        if let Some(super_behavior) = self.super_behavior() {
+            super_behavior.cursor_for_point(point)
+        } else {
+            None
+        }
+    }
+
+    /// The natural size of the view's content, e.g. the size of a label's
+    /// text or an image. Constraint-based layout keeps a view at this size
+    /// unless its constraints say otherwise; see `layout::Priority::LOW` and
+    /// `layout::Priority::HIGH`.
+    ///
+    /// `None` for views without one, which is the default.
+    fn intrinsic_content_size(&self) -> Option<Size<u32>> {
+        if let Some(super_behavior) = self.super_behavior() {
+            super_behavior.intrinsic_content_size()
+        } else {
+            None
+        }
+    }
+
+    /// The size the view would like to be to fit within `size`, e.g. a label
+    /// wrapping its text to the given width. Used by `View::size_to_fit`.
+    ///
+    /// Returns the intrinsic content size by default, or the current size
+    /// for views without one.
+    fn size_that_fits(&self, _size: &Size<u32>) -> Size<u32> {
+        if let Some(size) = self.intrinsic_content_size() {
+            return size;
+        }
+
+        let view = self.get_view().upgrade().expect("view was deallocated");
+        view.frame().size
+    }
+
+    /// Position the view's subviews, e.g. to fit its new size. Called before
+    /// the window is drawn after the view is resized, its subviews are
+    /// added, removed or hidden, or a subview's intrinsic content size
+    /// changes; or when asked to with `View::set_needs_layout`.
+    ///
+    /// Superviews are laid out before their subviews, and after constraints
+    /// are solved.
+    fn layout_subviews(&self) {
+        if let Some(super_behavior) = self.super_behavior() {
+            super_behavior.layout_subviews();
+        }
+    }
+
     /// Return `true` if the view can resign the first responder.
     ///
     /// Returns `true` by default.
@@ -0,0 +1,257 @@
+use crate::graphics::{EdgeInsets, Rectangle, Size};
+use crate::ui::view::collection_view::CollectionView;
+use crate::ui::view::table_view::IndexPath;
+
+/// Decides where the items of a `CollectionView` go. Use the built in
+/// `GridLayout` or `FlowLayout`, or implement it for a custom layout.
+pub trait CollectionLayout {
+    /// Where every item and header goes, in the coordinates of the scroll
+    /// view's content. Called whenever the items change.
+    ///
+    /// The collection view provides the number of sections and items, and
+    /// (through the data source) any per item sizes.
+    fn layout(&self, collection_view: &CollectionView) -> CollectionLayoutAttributes;
+}
+
+/// Where a `CollectionLayout` puts everything.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollectionLayoutAttributes {
+    /// The size of the area to scroll around.
+    pub content_size: Size<u32>,
+
+    /// The frame of each item, by section.
+    pub item_frames: Vec<Vec<Rectangle<i32, u32>>>,
+
+    /// The frame of each section's header; `None` for no header.
+    pub header_frames: Vec<Option<Rectangle<i32, u32>>>
+}
+
+impl CollectionLayoutAttributes {
+    pub fn empty() -> CollectionLayoutAttributes {
+        CollectionLayoutAttributes {
+            content_size: Size::new(0, 0),
+            item_frames: Vec::new(),
+            header_frames: Vec::new()
+        }
+    }
+
+    pub fn item_frame(&self, index_path: IndexPath) -> Option<Rectangle<i32, u32>> {
+        self.item_frames.get(index_path.section)?.get(index_path.row).cloned()
+    }
+
+    pub fn header_frame(&self, section: usize) -> Option<Rectangle<i32, u32>> {
+        self.header_frames.get(section).cloned().flatten()
+    }
+}
+
+/// Items of the same size in a fixed number of columns, stretched to fill
+/// the width of the collection view. E.g. tiles on a dashboard.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridLayout {
+    pub columns: usize,
+    pub item_height: u32,
+
+    /// Space between the rows and columns.
+    pub spacing: u32,
+
+    /// Space around the items of each section.
+    pub insets: EdgeInsets,
+
+    /// 0 for no headers.
+    pub header_height: u32
+}
+
+impl GridLayout {
+    pub fn new(columns: usize, item_height: u32) -> GridLayout {
+        GridLayout {
+            columns,
+            item_height,
+            spacing: 0,
+            insets: EdgeInsets::zero(),
+            header_height: 0
+        }
+    }
+}
+
+impl CollectionLayout for GridLayout {
+    fn layout(&self, collection_view: &CollectionView) -> CollectionLayoutAttributes {
+        let width = collection_view.frame().size.width as i32;
+        let columns = self.columns.max(1) as i32;
+        let spacing = self.spacing as i32;
+        let item_height = self.item_height as i32;
+        let item_width = ((width - self.insets.horizontal() - spacing * (columns - 1)) / columns).max(0);
+
+        let mut attributes = CollectionLayoutAttributes::empty();
+        let mut y = 0;
+
+        for section in 0..collection_view.number_of_sections() {
+            if self.header_height > 0 {
+                attributes.header_frames.push(Some(Rectangle::new(0, y, width as u32, self.header_height)));
+                y += self.header_height as i32;
+            } else {
+                attributes.header_frames.push(None);
+            }
+
+            y += self.insets.top;
+
+            let count = collection_view.number_of_items(section) as i32;
+            let frames = (0..count).map(|item| {
+                let column = item % columns;
+                let row = item / columns;
+
+                Rectangle::new(
+                    self.insets.left + column * (item_width + spacing),
+                    y + row * (item_height + spacing),
+                    item_width as u32,
+                    self.item_height
+                )
+            }).collect();
+            attributes.item_frames.push(frames);
+
+            let rows = (count + columns - 1) / columns;
+            if rows > 0 {
+                y += rows * item_height + (rows - 1) * spacing;
+            }
+
+            y += self.insets.bottom;
+        }
+
+        attributes.content_size = Size::new(width as u32, y.max(0) as u32);
+        attributes
+    }
+}
+
+/// Items laid out left to right, wrapping onto a new line when they reach
+/// the edge. Items can differ in size, through the data source's
+/// `size_for_item`. E.g. an image gallery.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlowLayout {
+    /// The size of items without one from the data source.
+    pub item_size: Size<u32>,
+
+    /// Space between items on the same line.
+    pub item_spacing: u32,
+
+    /// Space between lines.
+    pub line_spacing: u32,
+
+    /// Space around the items of each section.
+    pub insets: EdgeInsets,
+
+    /// 0 for no headers.
+    pub header_height: u32
+}
+
+impl FlowLayout {
+    pub fn new(item_size: Size<u32>) -> FlowLayout {
+        FlowLayout {
+            item_size,
+            item_spacing: 0,
+            line_spacing: 0,
+            insets: EdgeInsets::zero(),
+            header_height: 0
+        }
+    }
+}
+
+impl CollectionLayout for FlowLayout {
+    fn layout(&self, collection_view: &CollectionView) -> CollectionLayoutAttributes {
+        let width = collection_view.frame().size.width as i32;
+        let right_edge = width - self.insets.right;
+
+        let mut attributes = CollectionLayoutAttributes::empty();
+        let mut y = 0;
+
+        for section in 0..collection_view.number_of_sections() {
+            if self.header_height > 0 {
+                attributes.header_frames.push(Some(Rectangle::new(0, y, width as u32, self.header_height)));
+                y += self.header_height as i32;
+            } else {
+                attributes.header_frames.push(None);
+            }
+
+            let mut x = self.insets.left;
+            let mut line_top = y + self.insets.top;
+            let mut line_height = 0;
+            let mut frames = Vec::new();
+
+            for item in 0..collection_view.number_of_items(section) {
+                let size = collection_view.size_for_item(IndexPath::new(section, item))
+                    .unwrap_or_else(|| self.item_size.clone());
+
+                // Wrap, unless it's the first on the line (and too wide for
+                // any line).
+                if x > self.insets.left && x + size.width as i32 > right_edge {
+                    line_top += line_height + self.line_spacing as i32;
+                    x = self.insets.left;
+                    line_height = 0;
+                }
+
+                frames.push(Rectangle::new(x, line_top, size.width, size.height));
+                x += size.width as i32 + self.item_spacing as i32;
+                line_height = line_height.max(size.height as i32);
+            }
+
+            attributes.item_frames.push(frames);
+            y = line_top + line_height + self.insets.bottom;
+        }
+
+        attributes.content_size = Size::new(width as u32, y.max(0) as u32);
+        attributes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::view::test_data_source::TestDataSource;
+
+    fn collection_view(data_source: TestDataSource) -> CollectionView {
+        let collection_view = CollectionView::new(Rectangle::new(0, 0, 100, 100), GridLayout::new(1, 10));
+        collection_view.set_data_source(data_source);
+        collection_view
+    }
+
+    #[test]
+    fn test_grid_layout() {
+        let collection_view = collection_view(TestDataSource::new(vec![5, 2]));
+        let layout = GridLayout {
+            columns: 3,
+            item_height: 20,
+            spacing: 5,
+            insets: EdgeInsets::uniform(5),
+            header_height: 10
+        };
+
+        let attributes = layout.layout(&collection_view);
+        assert_eq!(attributes.header_frame(0), Some(Rectangle::new(0, 0, 100, 10)));
+        assert_eq!(attributes.item_frame(IndexPath::new(0, 0)), Some(Rectangle::new(5, 15, 26, 20)));
+        assert_eq!(attributes.item_frame(IndexPath::new(0, 4)), Some(Rectangle::new(36, 40, 26, 20)));
+
+        // Two rows of items, then the next section.
+        assert_eq!(attributes.header_frame(1), Some(Rectangle::new(0, 65, 100, 10)));
+        assert_eq!(attributes.item_frame(IndexPath::new(1, 1)), Some(Rectangle::new(36, 80, 26, 20)));
+        assert_eq!(attributes.content_size, Size::new(100, 105));
+    }
+
+    #[test]
+    fn test_flow_layout() {
+        let sizes = vec![Size::new(40, 10), Size::new(40, 20), Size::new(40, 10), Size::new(150, 10)];
+        let collection_view = collection_view(TestDataSource::new(vec![4]).with_sizes(sizes));
+
+        let mut layout = FlowLayout::new(Size::new(10, 10));
+        layout.item_spacing = 10;
+        layout.line_spacing = 5;
+
+        let attributes = layout.layout(&collection_view);
+        assert_eq!(attributes.header_frame(0), None);
+        assert_eq!(attributes.item_frame(IndexPath::new(0, 1)), Some(Rectangle::new(50, 0, 40, 20)));
+
+        // Wraps below the tallest item of the line.
+        assert_eq!(attributes.item_frame(IndexPath::new(0, 2)), Some(Rectangle::new(0, 25, 40, 10)));
+
+        // Too wide for any line, so on one of its own.
+        assert_eq!(attributes.item_frame(IndexPath::new(0, 3)), Some(Rectangle::new(0, 40, 150, 10)));
+        assert_eq!(attributes.content_size, Size::new(100, 50));
+    }
+}
@@ -0,0 +1,872 @@
+use crate::graphics::{Point, Rectangle, Size, Transform};
+use crate::ui::view::{DefaultBehavior, ScrollView, View, WeakView};
+use crate::ui::view::collection_layout::{CollectionLayout, CollectionLayoutAttributes};
+use crate::ui::view::scroll_view::ScrollViewDelegate;
+use crate::ui::view::table_view::IndexPath;
+use crate::ui::Color;
+use crate::ui::animation::{Animation, AnimationCurve, Interpolate};
+use crate::ui::gesture::tap_recognizer::TapRecognizer;
+use crate::macros::*;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use std::time::Duration;
+
+/// How long items take to move when inserted, deleted or moved.
+const UPDATE_ANIMATION_DURATION_MS: u64 = 250;
+
+/// Provides the items of a `CollectionView`. See
+/// `CollectionView::set_data_source`.
+///
+/// Index paths' `row` is the item within the section.
+pub trait CollectionViewDataSource {
+    fn number_of_sections(&self, _collection_view: &CollectionView) -> usize {
+        1
+    }
+
+    fn number_of_items(&self, collection_view: &CollectionView, section: usize) -> usize;
+
+    /// The cell to show for an item that has scrolled into view. Rather than
+    /// creating a new cell each time, reuse one that has scrolled out of view
+    /// with `CollectionView::dequeue_reusable_cell`.
+    fn cell_for_item(&self, collection_view: &CollectionView, index_path: IndexPath) -> CollectionViewCell;
+
+    /// A view shown where the layout puts the section's header.
+    fn view_for_header(&self, _collection_view: &CollectionView, _section: usize) -> Option<View> {
+        None
+    }
+
+    /// The size of the item, for layouts that size items individually, like
+    /// `FlowLayout`. `None` for the layout's usual size.
+    fn size_for_item(&self, _collection_view: &CollectionView, _index_path: IndexPath) -> Option<Size<u32>> {
+        None
+    }
+}
+
+/// Receives notifications about the user tapping items in a
+/// `CollectionView`. See `CollectionView::set_delegate`.
+pub trait CollectionViewDelegate {
+    fn did_select_item(&self, _collection_view: &CollectionView, _index_path: IndexPath) {}
+}
+
+/// A change to the items, already made in the data source. See
+/// `CollectionView::insert_items`.
+enum Update {
+    Insert(Vec<IndexPath>),
+    Delete(Vec<IndexPath>),
+    Move(IndexPath, IndexPath),
+    Reload
+}
+
+impl Update {
+    /// Where an item is after the update; `None` if it was deleted.
+    fn new_index_path(&self, index_path: IndexPath) -> Option<IndexPath> {
+        match self {
+            Update::Insert(inserted) => {
+                let mut rows: Vec<usize> = inserted.iter()
+                    .filter(|inserted| inserted.section == index_path.section)
+                    .map(|inserted| inserted.row)
+                    .collect();
+                rows.sort_unstable();
+
+                let mut row = index_path.row;
+                for inserted in rows {
+                    if inserted <= row {
+                        row += 1;
+                    }
+                }
+
+                Some(IndexPath::new(index_path.section, row))
+            },
+            Update::Delete(deleted) => {
+                if deleted.contains(&index_path) {
+                    return None;
+                }
+
+                let before = deleted.iter()
+                    .filter(|deleted| deleted.section == index_path.section && deleted.row < index_path.row)
+                    .count();
+
+                Some(IndexPath::new(index_path.section, index_path.row - before))
+            },
+            Update::Move(from, to) => {
+                if index_path == *from {
+                    return Some(*to);
+                }
+
+                let mut row = index_path.row;
+                if index_path.section == from.section && row > from.row {
+                    row -= 1;
+                }
+                if index_path.section == to.section && row >= to.row {
+                    row += 1;
+                }
+
+                Some(IndexPath::new(index_path.section, row))
+            },
+            Update::Reload => Some(index_path)
+        }
+    }
+
+    /// Whether the item appeared in the update, rather than moving.
+    fn is_inserted(&self, index_path: IndexPath) -> bool {
+        match self {
+            Update::Insert(inserted) => inserted.contains(&index_path),
+            _ => false
+        }
+    }
+}
+
+/// A cell, with where it's moving from and to.
+type CellMove = (CollectionViewCell, Rectangle<i32, u32>, Rectangle<i32, u32>);
+
+/// Cells moving to where they are after an update, or shrinking away.
+pub(crate) struct UpdateAnimation {
+    progress: Animation<f32>,
+    moving: Vec<CellMove>,
+    appearing: Vec<CollectionViewCell>,
+    disappearing: Vec<CollectionViewCell>
+}
+
+impl UpdateAnimation {
+    /// Whether the cell is moving, appearing or disappearing, so mustn't be
+    /// reused until the animation is over.
+    fn contains(&self, cell: &CollectionViewCell) -> bool {
+        self.moving.iter().any(|(moving, _, _)| moving.id() == cell.id()) ||
+            self.appearing.iter().any(|appearing| appearing.id() == cell.id()) ||
+            self.disappearing.iter().any(|disappearing| disappearing.id() == cell.id())
+    }
+}
+
+/// Shows the items that have scrolled into view.
+struct CollectionViewScrollDelegate {
+    collection_view: WeakView
+}
+
+impl ScrollViewDelegate for CollectionViewScrollDelegate {
+    fn did_scroll(&self, _scroll_view: &ScrollView) {
+        if let Some(view) = self.collection_view.upgrade() {
+            CollectionView::from_view(view).layout_visible_items();
+        }
+    }
+}
+
+custom_view!(
+    CollectionView subclasses DefaultBehavior
+
+    struct CollectionViewBehavior {
+        data_source: RefCell<Option<Rc<dyn CollectionViewDataSource>>>,
+        delegate: RefCell<Option<Rc<dyn CollectionViewDelegate>>>,
+        layout: RefCell<Rc<dyn CollectionLayout>>,
+        item_counts: RefCell<Vec<usize>>,
+        attributes: RefCell<CollectionLayoutAttributes>,
+        visible_cells: RefCell<BTreeMap<IndexPath, CollectionViewCell>>,
+        visible_headers: RefCell<BTreeMap<usize, View>>,
+        reuse_queue: RefCell<HashMap<String, Vec<CollectionViewCell>>>,
+        animation: RefCell<Option<UpdateAnimation>>
+    }
+
+    impl Self {
+        /// A scrolling collection of items, placed by the `layout`, of which
+        /// only those scrolled into view have cells. Give it items with
+        /// `set_data_source`.
+        pub fn new(frame: Rectangle<i32, u32>, layout: impl CollectionLayout + 'static) -> Self {
+            let scroll_view = ScrollView::new(Rectangle::new(0, 0, frame.size.width, frame.size.height));
+
+            let content_view = View::new(Rectangle::new(0, 0, frame.size.width, 0));
+            content_view.set_background_color(Color::clear());
+            scroll_view.set_content_view(content_view);
+
+            let collection_view = Self::new_all(
+                frame,
+                RefCell::new(None),
+                RefCell::new(None),
+                RefCell::new(Rc::new(layout)),
+                RefCell::new(Vec::new()),
+                RefCell::new(CollectionLayoutAttributes::empty()),
+                RefCell::new(BTreeMap::new()),
+                RefCell::new(BTreeMap::new()),
+                RefCell::new(HashMap::new()),
+                RefCell::new(None)
+            );
+
+            scroll_view.set_delegate(CollectionViewScrollDelegate {
+                collection_view: collection_view.view.downgrade()
+            });
+            collection_view.add_subview(scroll_view);
+
+            let tap_gesture = TapRecognizer::new(|gesture_recognizer| {
+                if let Some(view) = gesture_recognizer.view().upgrade() {
+                    CollectionView::from_view(view).did_tap(gesture_recognizer);
+                }
+            });
+            collection_view.add_gesture_recognizer(Box::new(tap_gesture));
+
+            collection_view
+        }
+
+        /// Set the data source providing the items, and load them.
+        pub fn set_data_source(&self, data_source: impl CollectionViewDataSource + 'static) {
+            {
+                let behavior = self.behavior();
+                behavior.data_source.replace(Some(Rc::new(data_source)));
+            }
+
+            self.reload_data();
+        }
+
+        fn data_source(&self) -> Option<Rc<dyn CollectionViewDataSource>> {
+            let behavior = self.behavior();
+            let data_source = behavior.data_source.borrow().clone();
+            data_source
+        }
+
+        /// Set the delegate to be notified when the user taps items.
+        pub fn set_delegate(&self, delegate: impl CollectionViewDelegate + 'static) {
+            let behavior = self.behavior();
+            behavior.delegate.replace(Some(Rc::new(delegate)));
+        }
+
+        fn delegate(&self) -> Option<Rc<dyn CollectionViewDelegate>> {
+            let behavior = self.behavior();
+            let delegate = behavior.delegate.borrow().clone();
+            delegate
+        }
+
+        /// Change how the items are laid out. When `animated`, the items
+        /// move smoothly to where the new layout puts them.
+        pub fn set_layout(&self, layout: impl CollectionLayout + 'static, animated: bool) {
+            {
+                let behavior = self.behavior();
+                behavior.layout.replace(Rc::new(layout));
+            }
+
+            self.update(Update::Reload, animated);
+        }
+
+        /// The scroll view the items are in. The collection view is its
+        /// delegate, so it isn't handed out; see `set_content_offset`.
+        pub(crate) fn scroll_view(&self) -> ScrollView {
+            let view = self.subviews().first().expect("collection view missing scroll view").clone();
+            ScrollView::from_view(view)
+        }
+
+        /// How far the items are scrolled.
+        pub fn content_offset(&self) -> Point<i32> {
+            self.scroll_view().content_offset()
+        }
+
+        /// Scrolls the items to the given offset; see
+        /// `ScrollView::set_content_offset`.
+        pub fn set_content_offset(&self, offset: Point<i32>, animated: bool) {
+            self.scroll_view().set_content_offset(offset, animated);
+        }
+
+        fn content_view(&self) -> View {
+            self.scroll_view().content_view().expect("collection view missing content view")
+        }
+
+        pub fn number_of_sections(&self) -> usize {
+            self.behavior().item_counts.borrow().len()
+        }
+
+        pub fn number_of_items(&self, section: usize) -> usize {
+            self.behavior().item_counts.borrow().get(section).cloned().unwrap_or(0)
+        }
+
+        /// The data source's size for the item, for layouts. See
+        /// `CollectionViewDataSource::size_for_item`.
+        pub fn size_for_item(&self, index_path: IndexPath) -> Option<Size<u32>> {
+            self.data_source().and_then(|data_source| data_source.size_for_item(self, index_path))
+        }
+
+        /// Where the item is, in the coordinates of the scroll view's
+        /// content.
+        pub fn frame_for_item(&self, index_path: IndexPath) -> Option<Rectangle<i32, u32>> {
+            self.behavior().attributes.borrow().item_frame(index_path)
+        }
+
+        /// The item at the given point, in the coordinates of the scroll
+        /// view's content.
+        pub fn index_path_for_item_at(&self, point: &Point<i32>) -> Option<IndexPath> {
+            let behavior = self.behavior();
+            let attributes = behavior.attributes.borrow();
+
+            for (section, frames) in attributes.item_frames.iter().enumerate() {
+                if let Some(item) = frames.iter().position(|frame| frame.contains(point)) {
+                    return Some(IndexPath::new(section, item));
+                }
+            }
+
+            None
+        }
+
+        /// Asks the data source for the items again, e.g. after they've
+        /// changed, without animating.
+        pub fn reload_data(&self) {
+            self.finish_animating();
+
+            let cells: Vec<CollectionViewCell> = {
+                let behavior = self.behavior();
+                let mut visible_cells = behavior.visible_cells.borrow_mut();
+                std::mem::take(&mut *visible_cells).into_values().collect()
+            };
+
+            for cell in cells {
+                self.enqueue_cell(cell);
+            }
+
+            self.remove_headers();
+            self.load_layout();
+            self.layout_visible_items();
+        }
+
+        /// Animates in items that have been added to the data source at the
+        /// given index paths. Items after them move along to make room.
+        pub fn insert_items(&self, index_paths: Vec<IndexPath>) {
+            self.update(Update::Insert(index_paths), true);
+        }
+
+        /// Animates away the items that have been removed from the data
+        /// source from the given index paths. Items after them move back to
+        /// fill the space.
+        pub fn delete_items(&self, index_paths: Vec<IndexPath>) {
+            self.update(Update::Delete(index_paths), true);
+        }
+
+        /// Animates an item that has been moved in the data source to its new
+        /// place.
+        pub fn move_item(&self, from: IndexPath, to: IndexPath) {
+            self.update(Update::Move(from, to), true);
+        }
+
+        /// Asks the data source for the item counts, and the layout where
+        /// everything goes.
+        fn load_layout(&self) {
+            let item_counts: Vec<usize> = match self.data_source() {
+                Some(data_source) => (0..data_source.number_of_sections(self))
+                    .map(|section| data_source.number_of_items(self, section))
+                    .collect(),
+                None => Vec::new()
+            };
+
+            let layout = {
+                let behavior = self.behavior();
+                behavior.item_counts.replace(item_counts);
+                let layout = behavior.layout.borrow().clone();
+                layout
+            };
+
+            let attributes = layout.layout(self);
+            let content_size = attributes.content_size.clone();
+            self.behavior().attributes.replace(attributes);
+
+            self.scroll_view().set_content_size(content_size);
+        }
+
+        /// Applies a change already made in the data source, moving the cells
+        /// of the items to their new places.
+        fn update(&self, update: Update, animated: bool) {
+            self.finish_animating();
+
+            let old_frames: Vec<(IndexPath, CollectionViewCell, Rectangle<i32, u32>)> = {
+                let behavior = self.behavior();
+                let mut visible_cells = behavior.visible_cells.borrow_mut();
+                std::mem::take(&mut *visible_cells).into_iter()
+                    .map(|(index_path, cell)| {
+                        let frame = cell.frame();
+                        (index_path, cell, frame)
+                    })
+                    .collect()
+            };
+
+            // Headers are recreated where the new layout puts them.
+            self.remove_headers();
+            self.load_layout();
+
+            let mut moving = Vec::new();
+            let mut disappearing = Vec::new();
+
+            {
+                let behavior = self.behavior();
+                let attributes = behavior.attributes.borrow();
+                let mut visible_cells = behavior.visible_cells.borrow_mut();
+
+                for (index_path, cell, frame) in old_frames {
+                    let new_frame = update.new_index_path(index_path)
+                        .and_then(|new_index_path| attributes.item_frame(new_index_path).map(|frame| (new_index_path, frame)));
+
+                    match new_frame {
+                        Some((new_index_path, new_frame)) => {
+                            visible_cells.insert(new_index_path, cell.clone());
+                            moving.push((cell, frame, new_frame));
+                        },
+                        None => disappearing.push(cell)
+                    }
+                }
+            }
+
+            if !animated {
+                for (cell, _, to) in moving {
+                    cell.fit_to(to);
+                }
+
+                for cell in disappearing {
+                    self.enqueue_cell(cell);
+                }
+
+                self.layout_visible_items();
+                return;
+            }
+
+            // Eased out, so the cells slow down as they arrive.
+            let weak_view = self.view.downgrade();
+            let weak_completion_view = weak_view.clone();
+            let progress = Animation::new(Duration::from_millis(UPDATE_ANIMATION_DURATION_MS), 0.0, 1.0, AnimationCurve::EaseOut)
+                .with_tick(move |progress| {
+                    if let Some(view) = weak_view.upgrade() {
+                        CollectionView::from_view(view).apply_animation(*progress);
+                    }
+                })
+                .with_completion(move |finished| {
+                    if let Some(view) = weak_completion_view.upgrade().filter(|_| finished) {
+                        CollectionView::from_view(view).finish_animating();
+                    }
+                });
+
+            // Started before showing the items that are now in view, so the
+            // cells that are moving away stay until they're done.
+            {
+                let behavior = self.behavior();
+                behavior.animation.replace(Some(UpdateAnimation {
+                    progress: progress.clone(),
+                    moving,
+                    appearing: Vec::new(),
+                    disappearing
+                }));
+            }
+
+            let visible_before = self.index_paths_for_visible_items();
+            self.layout_visible_items();
+
+            let appearing: Vec<CollectionViewCell> = self.index_paths_for_visible_items().into_iter()
+                .filter(|index_path| !visible_before.contains(index_path) && update.is_inserted(*index_path))
+                .filter_map(|index_path| self.cell_for_item(index_path))
+                .collect();
+
+            for cell in &appearing {
+                cell.set_transform(Transform::scale(0.0, 0.0));
+            }
+
+            if let Some(animation) = self.behavior().animation.borrow_mut().as_mut() {
+                animation.appearing = appearing;
+            }
+
+            progress.start();
+        }
+
+        /// Puts the cells where they are at `progress` through the animation.
+        fn apply_animation(&self, progress: f32) {
+            let behavior = self.behavior();
+            let animation = behavior.animation.borrow();
+            let animation = match animation.as_ref() {
+                Some(animation) => animation,
+                None => return
+            };
+
+            for (cell, from, to) in &animation.moving {
+                cell.fit_to(from.interpolate(to, progress));
+            }
+
+            for cell in &animation.appearing {
+                cell.set_transform(Transform::scale(progress, progress));
+            }
+
+            for cell in &animation.disappearing {
+                cell.set_transform(Transform::scale(1.0 - progress, 1.0 - progress));
+            }
+        }
+
+        /// Jumps to the end of any animation in progress.
+        fn finish_animating(&self) {
+            let animation = self.behavior().animation.take();
+            let animation = match animation {
+                Some(animation) => animation,
+                None => return
+            };
+
+            animation.progress.cancel();
+
+            for (cell, _, to) in animation.moving {
+                cell.fit_to(to);
+            }
+
+            for cell in animation.appearing {
+                cell.set_transform(Transform::identity());
+            }
+
+            for cell in animation.disappearing {
+                cell.set_transform(Transform::identity());
+                self.enqueue_cell(cell);
+            }
+
+            // Cells may have moved out of view.
+            self.layout_visible_items();
+        }
+
+        fn remove_headers(&self) {
+            let headers: Vec<View> = {
+                let behavior = self.behavior();
+                let mut visible_headers = behavior.visible_headers.borrow_mut();
+                std::mem::take(&mut *visible_headers).into_values().collect()
+            };
+
+            for header in headers {
+                header.remove_from_superview();
+            }
+        }
+
+        /// Gives the items (and headers) that have scrolled into view cells,
+        /// reusing those of items that have scrolled out of view.
+        fn layout_visible_items(&self) {
+            let data_source = match self.data_source() {
+                Some(data_source) => data_source,
+                None => return
+            };
+
+            let scroll_view = self.scroll_view();
+            let offset = scroll_view.content_offset();
+            let size = scroll_view.frame().size;
+            let visible_rect = Rectangle::new(offset.x, offset.y, size.width, size.height);
+
+            let (items, headers) = {
+                let behavior = self.behavior();
+                let attributes = behavior.attributes.borrow();
+
+                let items: Vec<(IndexPath, Rectangle<i32, u32>)> = attributes.item_frames.iter().enumerate()
+                    .flat_map(|(section, frames)| {
+                        frames.iter().enumerate().map(move |(item, frame)| (IndexPath::new(section, item), frame.clone()))
+                    })
+                    .filter(|(_, frame)| frame.intersects(&visible_rect))
+                    .collect();
+
+                let headers: Vec<(usize, Rectangle<i32, u32>)> = attributes.header_frames.iter().enumerate()
+                    .filter_map(|(section, frame)| frame.clone().map(|frame| (section, frame)))
+                    .filter(|(_, frame)| frame.intersects(&visible_rect))
+                    .collect();
+
+                (items, headers)
+            };
+
+            // Cells that are animating stay until the animation finishes,
+            // rather than being reused for other items mid-way.
+            let hidden_cells: Vec<CollectionViewCell> = {
+                let behavior = self.behavior();
+                let animation = behavior.animation.borrow();
+                let mut visible_cells = behavior.visible_cells.borrow_mut();
+                let hidden: Vec<IndexPath> = visible_cells.iter()
+                    .filter(|(index_path, _)| !items.iter().any(|(item, _)| item == *index_path))
+                    .filter(|(_, cell)| !animation.as_ref().is_some_and(|animation| animation.contains(cell)))
+                    .map(|(index_path, _)| *index_path)
+                    .collect();
+
+                hidden.iter().filter_map(|index_path| visible_cells.remove(index_path)).collect()
+            };
+
+            for cell in hidden_cells {
+                self.enqueue_cell(cell);
+            }
+
+            let hidden_headers: Vec<View> = {
+                let behavior = self.behavior();
+                let mut visible_headers = behavior.visible_headers.borrow_mut();
+                let hidden: Vec<usize> = visible_headers.keys()
+                    .filter(|section| !headers.iter().any(|(header, _)| header == *section))
+                    .cloned()
+                    .collect();
+
+                hidden.iter().filter_map(|section| visible_headers.remove(section)).collect()
+            };
+
+            for header in hidden_headers {
+                header.remove_from_superview();
+            }
+
+            let content_view = self.content_view();
+
+            for (index_path, frame) in items {
+                if self.cell_for_item(index_path).is_some() {
+                    continue;
+                }
+
+                let cell = data_source.cell_for_item(self, index_path);
+                cell.fit_to(frame);
+                content_view.add_subview(cell.clone());
+
+                let behavior = self.behavior();
+                behavior.visible_cells.borrow_mut().insert(index_path, cell);
+            }
+
+            for (section, frame) in headers {
+                if self.behavior().visible_headers.borrow().contains_key(&section) {
+                    continue;
+                }
+
+                if let Some(header) = data_source.view_for_header(self, section) {
+                    header.set_frame(frame);
+                    content_view.add_subview(header.clone());
+
+                    let behavior = self.behavior();
+                    behavior.visible_headers.borrow_mut().insert(section, header);
+                }
+            }
+        }
+
+        fn enqueue_cell(&self, cell: CollectionViewCell) {
+            cell.remove_from_superview();
+
+            let behavior = self.behavior();
+            let mut reuse_queue = behavior.reuse_queue.borrow_mut();
+            reuse_queue.entry(cell.reuse_identifier()).or_default().push(cell);
+        }
+
+        /// A cell with the given identifier that has scrolled out of view,
+        /// to be reused by the data source's `cell_for_item`. If there isn't
+        /// one, the data source should create a new cell.
+        pub fn dequeue_reusable_cell(&self, reuse_identifier: &str) -> Option<CollectionViewCell> {
+            let behavior = self.behavior();
+            let mut reuse_queue = behavior.reuse_queue.borrow_mut();
+            reuse_queue.get_mut(reuse_identifier).and_then(|cells| cells.pop())
+        }
+
+        /// The cell showing the item, if it's scrolled into view.
+        pub fn cell_for_item(&self, index_path: IndexPath) -> Option<CollectionViewCell> {
+            self.behavior().visible_cells.borrow().get(&index_path).cloned()
+        }
+
+        pub fn index_paths_for_visible_items(&self) -> Vec<IndexPath> {
+            self.behavior().visible_cells.borrow().keys().cloned().collect()
+        }
+
+        /// Scrolls just enough to show the item.
+        pub fn scroll_to_item(&self, index_path: IndexPath, animated: bool) {
+            if let Some(frame) = self.frame_for_item(index_path) {
+                self.scroll_view().scroll_rect_to_visible(frame, animated);
+            }
+        }
+
+        fn did_tap(&self, recognizer: &TapRecognizer) {
+            let point = recognizer.location_in(&self.content_view());
+
+            if let Some(index_path) = self.index_path_for_item_at(&point) {
+                if let Some(delegate) = self.delegate() {
+                    delegate.did_select_item(self, index_path);
+                }
+            }
+        }
+    }
+
+    impl Behavior {
+        /// Keep the scroll view filling the collection view, laying the
+        /// items out again for the new size.
+        fn layout_subviews(&self) {
+            let collection_view = self.view_type();
+            let size = collection_view.frame().size;
+            let scroll_view = collection_view.scroll_view();
+
+            if scroll_view.frame().size == size {
+                return;
+            }
+
+            scroll_view.set_frame(Rectangle::new(0, 0, size.width, size.height));
+
+            collection_view.update(Update::Reload, false);
+        }
+    }
+);
+
+custom_view!(
+    CollectionViewCell subclasses DefaultBehavior
+
+    struct CollectionViewCellBehavior {
+        reuse_identifier: String
+    }
+
+    impl Self {
+        /// An item of a `CollectionView`. Cells with the same
+        /// `reuse_identifier` can be reused for each other's items; see
+        /// `CollectionView::dequeue_reusable_cell`.
+        pub fn new(reuse_identifier: &str) -> Self {
+            let cell = Self::new_all(Rectangle::new(0, 0, 0, 0), reuse_identifier.to_string());
+
+            let content_view = View::new(Rectangle::new(0, 0, 0, 0));
+            content_view.set_background_color(Color::clear());
+            cell.add_subview(content_view);
+
+            cell
+        }
+
+        pub fn reuse_identifier(&self) -> String {
+            self.behavior().reuse_identifier.clone()
+        }
+
+        /// The view to add the cell's content to. It's resized to fill the
+        /// cell.
+        pub fn content_view(&self) -> View {
+            self.subviews().first().expect("collection view cell missing content view").clone()
+        }
+
+        fn fit_to(&self, frame: Rectangle<i32, u32>) {
+            let size = frame.size.clone();
+            self.set_frame(frame);
+            self.content_view().set_frame(Rectangle::new(0, 0, size.width, size.height));
+        }
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::view::collection_layout::GridLayout;
+    use crate::ui::view::test_data_source::TestDataSource;
+
+    /// Four columns of 25x25 items, four rows in view.
+    fn collection_view(count: usize) -> (CollectionView, TestDataSource) {
+        let collection_view = CollectionView::new(Rectangle::new(0, 0, 100, 100), GridLayout::new(4, 25));
+        let data_source = TestDataSource::new(vec![count]);
+        collection_view.set_data_source(data_source.clone());
+        (collection_view, data_source)
+    }
+
+    #[test]
+    fn test_new_index_path() {
+        let insert = Update::Insert(vec![IndexPath::new(0, 1), IndexPath::new(0, 3)]);
+        assert_eq!(insert.new_index_path(IndexPath::new(0, 0)), Some(IndexPath::new(0, 0)));
+        assert_eq!(insert.new_index_path(IndexPath::new(0, 1)), Some(IndexPath::new(0, 2)));
+        assert_eq!(insert.new_index_path(IndexPath::new(0, 2)), Some(IndexPath::new(0, 4)));
+        assert_eq!(insert.new_index_path(IndexPath::new(1, 2)), Some(IndexPath::new(1, 2)));
+
+        let delete = Update::Delete(vec![IndexPath::new(0, 1), IndexPath::new(0, 3)]);
+        assert_eq!(delete.new_index_path(IndexPath::new(0, 1)), None);
+        assert_eq!(delete.new_index_path(IndexPath::new(0, 2)), Some(IndexPath::new(0, 1)));
+        assert_eq!(delete.new_index_path(IndexPath::new(0, 4)), Some(IndexPath::new(0, 2)));
+
+        let move_item = Update::Move(IndexPath::new(0, 1), IndexPath::new(0, 3));
+        assert_eq!(move_item.new_index_path(IndexPath::new(0, 1)), Some(IndexPath::new(0, 3)));
+        assert_eq!(move_item.new_index_path(IndexPath::new(0, 2)), Some(IndexPath::new(0, 1)));
+        assert_eq!(move_item.new_index_path(IndexPath::new(0, 3)), Some(IndexPath::new(0, 2)));
+        assert_eq!(move_item.new_index_path(IndexPath::new(0, 4)), Some(IndexPath::new(0, 4)));
+    }
+
+    #[test]
+    fn test_only_visible_items_have_cells() {
+        let (collection_view, data_source) = collection_view(100);
+        assert_eq!(collection_view.index_paths_for_visible_items().len(), 16);
+        assert_eq!(data_source.created(), 16);
+        assert_eq!(collection_view.scroll_view().content_size(), Size::new(100, 625));
+
+        // Cells of items scrolled out of view are reused.
+        collection_view.set_content_offset(Point::new(0, 200), false);
+        assert_eq!(collection_view.index_paths_for_visible_items().first(), Some(&IndexPath::new(0, 32)));
+        assert_eq!(data_source.created(), 16);
+
+        assert_eq!(collection_view.index_path_for_item_at(&Point::new(30, 210)), Some(IndexPath::new(0, 33)));
+    }
+
+    #[test]
+    fn test_resize() {
+        let (collection_view, _) = collection_view(100);
+
+        collection_view.set_frame(Rectangle::new(0, 0, 100, 200));
+        collection_view.layout_if_needed();
+
+        assert_eq!(collection_view.scroll_view().frame(), Rectangle::new(0, 0, 100, 200));
+        assert_eq!(collection_view.index_paths_for_visible_items().len(), 32);
+    }
+
+    #[test]
+    fn test_delete_items() {
+        let (collection_view, data_source) = collection_view(8);
+        let second = collection_view.cell_for_item(IndexPath::new(0, 2)).unwrap();
+
+        data_source.set_count(0, 7);
+        collection_view.delete_items(vec![IndexPath::new(0, 1)]);
+
+        // The cell after the deleted one moves back into its place.
+        assert_eq!(collection_view.cell_for_item(IndexPath::new(0, 1)).unwrap().id(), second.id());
+        assert!(collection_view.behavior().animation.borrow().is_some());
+
+        collection_view.finish_animating();
+        assert_eq!(second.frame(), Rectangle::new(25, 0, 25, 25));
+        assert_eq!(collection_view.index_paths_for_visible_items().len(), 7);
+        assert_eq!(collection_view.content_view().subviews().len(), 7);
+    }
+
+    #[test]
+    fn test_insert_items() {
+        let (collection_view, data_source) = collection_view(4);
+        let first = collection_view.cell_for_item(IndexPath::new(0, 0)).unwrap();
+
+        data_source.set_count(0, 5);
+        collection_view.insert_items(vec![IndexPath::new(0, 0)]);
+
+        assert_eq!(collection_view.cell_for_item(IndexPath::new(0, 1)).unwrap().id(), first.id());
+
+        let inserted = collection_view.cell_for_item(IndexPath::new(0, 0)).unwrap();
+        assert_eq!(inserted.transform(), Transform::scale(0.0, 0.0));
+
+        collection_view.finish_animating();
+        assert_eq!(inserted.transform(), Transform::identity());
+        assert_eq!(first.frame(), Rectangle::new(25, 0, 25, 25));
+    }
+
+    #[test]
+    fn test_scroll_while_updating() {
+        let (collection_view, data_source) = collection_view(100);
+
+        data_source.set_count(0, 99);
+        collection_view.delete_items(vec![IndexPath::new(0, 0)]);
+        collection_view.set_content_offset(Point::new(0, 200), false);
+
+        // The moving cells aren't reused for the items scrolled into view.
+        {
+            let behavior = collection_view.behavior();
+            let animation = behavior.animation.borrow();
+            let animation = animation.as_ref().expect("collection view isn't animating");
+            let reuse_queue = behavior.reuse_queue.borrow();
+            assert!(reuse_queue.values().flatten().all(|cell| !animation.contains(cell)));
+
+            let mut ids: Vec<_> = behavior.visible_cells.borrow().values().map(|cell| cell.id()).collect();
+            let count = ids.len();
+            ids.sort();
+            ids.dedup();
+            assert_eq!(ids.len(), count);
+        }
+
+        collection_view.finish_animating();
+        assert_eq!(collection_view.index_paths_for_visible_items().first(), Some(&IndexPath::new(0, 32)));
+        assert_eq!(collection_view.index_paths_for_visible_items().len(), 16);
+        assert_eq!(collection_view.content_view().subviews().len(), 16);
+    }
+
+    #[test]
+    fn test_move_item() {
+        let (collection_view, _) = collection_view(4);
+        let first = collection_view.cell_for_item(IndexPath::new(0, 0)).unwrap();
+
+        collection_view.move_item(IndexPath::new(0, 0), IndexPath::new(0, 3));
+        collection_view.finish_animating();
+
+        assert_eq!(collection_view.cell_for_item(IndexPath::new(0, 3)).unwrap().id(), first.id());
+        assert_eq!(first.frame(), Rectangle::new(75, 0, 25, 25));
+    }
+
+    #[test]
+    fn test_set_layout() {
+        let (collection_view, _) = collection_view(4);
+        let last = collection_view.cell_for_item(IndexPath::new(0, 3)).unwrap();
+
+        collection_view.set_layout(GridLayout::new(2, 50), false);
+        assert_eq!(last.frame(), Rectangle::new(50, 50, 50, 50));
+    }
+}
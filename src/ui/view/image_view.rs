@@ -1,4 +1,4 @@
-use crate::graphics::{Image, Rectangle, Point};
+use crate::graphics::{Image, Rectangle, Point, Size};
 use crate::ui::view::DefaultBehavior;
 use std::cell::RefCell;
 use crate::macros::*;
@@ -19,6 +19,10 @@ custom_view!(
     }
 
     impl Behavior {
+        fn intrinsic_content_size(&self) -> Option<Size<u32>> {
+            Some(self.image.borrow().size().clone())
+        }
+
         fn draw(&self) {
             let view = self.view.upgrade().expect("view was deallocated").clone();
             let inner_self = view.inner_self.borrow();
@@ -10,6 +10,9 @@ use std::ops::Range;
 use std::rc::Rc;
 use crate::text::Text;
 
+/// Wide enough that measuring text for its intrinsic size doesn't wrap it.
+const UNWRAPPED_WIDTH: u32 = 100_000;
+
 custom_view!(
     Label subclasses DefaultBehavior
 
@@ -69,12 +72,14 @@ custom_view!(
             }
             (*behavior.attributed_text).replace(attributed_text);
             behavior.set_needs_display();
+            self.invalidate_intrinsic_content_size();
         }
 
         pub fn set_attributed_text(&self, attributed_text: AttributedString) {
             let behavior = self.behavior();
             (*behavior.attributed_text).replace(attributed_text);
             behavior.set_needs_display();
+            self.invalidate_intrinsic_content_size();
         }
 
         pub fn insert_text_at_index(&self, index: usize, text_to_insert: &str) {
@@ -86,6 +91,7 @@ custom_view!(
             }
 
             behavior.set_needs_display();
+            self.invalidate_intrinsic_content_size();
         }
 
         pub fn replace_text_in_range(&self, range: Range<usize>, text_to_replace: &str) {
@@ -97,6 +103,7 @@ custom_view!(
             }
 
             behavior.set_needs_display();
+            self.invalidate_intrinsic_content_size();
         }
 
         pub fn set_text_color(&self, text_color: Color) {
@@ -135,6 +142,7 @@ custom_view!(
             }
 
             behavior.set_needs_display();
+            self.invalidate_intrinsic_content_size();
         }
 
         pub fn font(&self) -> Font {
@@ -199,6 +207,19 @@ custom_view!(
     }
 
     impl Behavior {
+        /// The size of the text on a single line (other than explicit line
+        /// breaks).
+        fn intrinsic_content_size(&self) -> Option<Size<u32>> {
+            Some(self.text_size(UNWRAPPED_WIDTH))
+        }
+
+        /// The size of the text wrapped to the given width, or on a single
+        /// line for a width of `0`.
+        fn size_that_fits(&self, size: &Size<u32>) -> Size<u32> {
+            let width = if size.width == 0 { UNWRAPPED_WIDTH } else { size.width };
+            self.text_size(width)
+        }
+
         fn set_needs_display(&self) {
             self.super_behavior().expect("label missing super_behavior").set_needs_display();
             let label = Label::from_view(self.view.upgrade().expect("label view was deallocated"));
@@ -268,6 +289,13 @@ custom_view!(
 );
 
 impl LabelBehavior {
+    fn text_size(&self, width: u32) -> Size<u32> {
+        let attributed_string = self.attributed_text.borrow();
+        let frame = Rectangle::new(0, 0, width, 0);
+        let whole_text = rendering::WholeText::from(&attributed_string, frame, 1.0);
+        whole_text.calculate_character_render_positions().text_size()
+    }
+
     pub fn rendering(&self) -> Ref<'_, rendering::Result> {
         let label = Label::from_view(self.view.upgrade().expect("label view was deallocated"));
         let rendering_result = self.rendering_result.borrow();
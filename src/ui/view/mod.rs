@@ -6,7 +6,13 @@ pub mod weak_view;
 pub mod image_view;
 pub mod label;
 pub mod scroll_view;
+pub mod table_view;
+pub mod collection_layout;
+pub mod collection_view;
+pub mod stack_view;
 pub mod text_field;
+#[cfg(test)]
+mod test_data_source;
 #[cfg(target_os = "macos")]
 pub mod web_view;
 
@@ -18,6 +24,9 @@ pub(crate) use view_inner::ViewInner;
 pub use image_view::ImageView;
 pub use label::Label;
 pub use scroll_view::ScrollView;
+pub use table_view::{TableView, TableViewCell};
+pub use collection_view::{CollectionView, CollectionViewCell};
+pub use stack_view::StackView;
 pub use text_field::TextField;
 #[cfg(target_os = "macos")]
 pub use web_view::WebView;
@@ -1,18 +1,182 @@
-use crate::graphics::Rectangle;
+use crate::graphics::{EdgeInsets, Rectangle, Transform};
 use crate::graphics::Size;
 use crate::ui::view::View;
-use crate::ui::Color;
+use crate::ui::{Color, Touch, WeakView};
 use crate::ui::view::DefaultBehavior;
 use crate::macros::*;
 use crate::ui::gesture::pan_recognizer::PanRecognizer;
+use crate::ui::gesture::pinch_recognizer::PinchRecognizer;
+use crate::ui::gesture::tap_recognizer::TapRecognizer;
+use crate::ui::gesture::recognizer::{Arbitration, Recognizer, RecognizerDelegate, RecognizerState};
+use crate::ui::timer::Timer;
+use crate::ui::animation::{frame_timer, Animation, AnimationCurve, Interpolate, Spring};
+use crate::ui::run_loop::RunLoop;
 use crate::graphics::Point;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// The default `ScrollView::deceleration_rate`; scrolling carries on for a
+/// while after a flick.
+pub const DECELERATION_RATE_NORMAL: f32 = 0.998;
+
+/// A `ScrollView::deceleration_rate` that stops scrolling quickly after a
+/// flick.
+pub const DECELERATION_RATE_FAST: f32 = 0.99;
+
+/// How long scroll bars stay after scrolling stops, before fading out.
+const SCROLL_BAR_HIDE_DELAY_MS: u64 = 1000;
+
+/// How long scroll bars take to fade out.
+const SCROLL_BAR_FADE_DURATION_MS: u64 = 250;
+
+/// How long `set_content_offset` takes to scroll when animated.
+const SCROLL_ANIMATION_DURATION_MS: u64 = 300;
+
+/// Below this velocity (in points per second) the content has stopped.
+const MINIMUM_VELOCITY: f32 = 5.0;
+
+/// How much of a drag past the edge of the content moves it. The rest is
+/// taken up by the rubber band.
+const RUBBER_BAND_RESISTANCE: f32 = 0.5;
+
+/// How strongly content overscrolled past an edge is pulled back to it. The
+/// spring is critically damped, so it settles without wobbling.
+const BOUNCE_STIFFNESS: f32 = 150.0;
+
+/// With paging, a flick faster than this (in points per second) goes on to
+/// the next page, rather than back to the nearest.
+const PAGING_VELOCITY_THRESHOLD: f32 = 300.0;
+
+/// Receives notifications about scrolling in a `ScrollView`. See
+/// `ScrollView::set_delegate`.
+pub trait ScrollViewDelegate {
+    /// Called whenever the content offset changes, whether by the user or
+    /// programmatically.
+    fn did_scroll(&self, _scroll_view: &ScrollView) {}
+
+    /// Called when the user starts dragging (or scrolling) the content.
+    fn will_begin_dragging(&self, _scroll_view: &ScrollView) {}
+
+    /// Called when the user stops dragging the content. `will_decelerate` is
+    /// `true` if the content carries on moving, in which case
+    /// `did_end_decelerating` follows once it has stopped.
+    fn did_end_dragging(&self, _scroll_view: &ScrollView, _will_decelerate: bool) {}
+
+    /// Called when the content comes to a stop after being flicked, or after
+    /// springing back from past an edge.
+    fn did_end_decelerating(&self, _scroll_view: &ScrollView) {}
+
+    /// The view to scale when zooming; usually the content view. The scroll
+    /// view only zooms if this returns a view, and its minimum and maximum
+    /// zoom scales differ.
+    fn view_for_zooming(&self, _scroll_view: &ScrollView) -> Option<View> {
+        None
+    }
+
+    /// Called whenever the zoom scale changes.
+    fn did_zoom(&self, _scroll_view: &ScrollView) {}
+}
+
+/// Content moving by itself after being flicked, or springing back after
+/// being pulled past an edge.
+pub(crate) struct Deceleration {
+    timer: Timer,
+    offset: (f32, f32),
+    velocity: (f32, f32),
+    last_step_at: Instant
+}
+
+/// Content moving to a new offset (and zoom scale), from
+/// `ScrollView::set_content_offset`, `ScrollView::set_zoom_scale` or snapping
+/// to a page.
+pub(crate) struct ScrollAnimation {
+    progress: Animation<f32>,
+    from: Point<i32>,
+    to: Point<i32>,
+    from_scale: f32,
+    to_scale: f32,
+
+    /// Whether the delegate is told `did_end_decelerating` once it's done;
+    /// i.e. it carries on from a drag.
+    ends_deceleration: bool
+}
+
+/// Where the content offset snaps to once a drag ends. See
+/// `ScrollView::set_paging_enabled`.
+pub(crate) struct Paging {
+    enabled: bool,
+    horizontal_snap_points: Vec<i32>,
+    vertical_snap_points: Vec<i32>
+}
+
+/// See `ScrollView::set_zoom_scale`.
+pub(crate) struct Zooming {
+    scale: f32,
+    minimum_scale: f32,
+    maximum_scale: f32,
+
+    /// The zoom scale when the pinch began.
+    pinch_start_scale: f32,
+
+    /// The point under the fingers when the pinch began, in the unzoomed
+    /// content's coordinates. It's kept under them as they move.
+    pinch_anchor: Option<(f32, f32)>
+}
+
+/// Only lets the scroll view's pan begin while scrolling is enabled, and not
+/// while a scroll bar is being dragged.
+struct ScrollViewPanDelegate {
+    scroll_view: WeakView
+}
+
+impl RecognizerDelegate for ScrollViewPanDelegate {
+    fn should_begin(&self, _recognizer: &dyn Recognizer) -> bool {
+        match self.scroll_view.upgrade() {
+            Some(view) => {
+                let scroll_view = ScrollView::from_view(view);
+                scroll_view.is_scroll_enabled() && !scroll_view.is_dragging_scroll_bar()
+            },
+            None => false
+        }
+    }
+}
+
+/// Only lets the scroll view's pinch and double-tap begin while it can zoom,
+/// and lets the pinch pan the content too.
+struct ScrollViewZoomDelegate {
+    scroll_view: WeakView,
+    pan: Rc<Arbitration>
+}
+
+impl RecognizerDelegate for ScrollViewZoomDelegate {
+    fn should_begin(&self, _recognizer: &dyn Recognizer) -> bool {
+        match self.scroll_view.upgrade() {
+            Some(view) => ScrollView::from_view(view).is_zoom_enabled(),
+            None => false
+        }
+    }
+
+    fn should_recognize_simultaneously(&self, _recognizer: &dyn Recognizer, other: &dyn Recognizer) -> bool {
+        Rc::ptr_eq(&other.arbitration(), &self.pan)
+    }
+}
 
 custom_view!(
     ScrollView subclasses DefaultBehavior
 
     struct ScrollViewBehavior {
-
+        deceleration_rate: Cell<f32>,
+        bounces: Cell<bool>,
+        scroll_enabled: Cell<bool>,
+        content_inset: Cell<EdgeInsets>,
+        directional_lock_enabled: Cell<bool>,
+        locked_direction: Cell<Option<ScrollBarDirection>>,
+        deceleration: RefCell<Option<Deceleration>>,
+        animation: RefCell<Option<ScrollAnimation>>,
+        paging: RefCell<Paging>,
+        zooming: RefCell<Zooming>,
+        delegate: RefCell<Option<Rc<dyn ScrollViewDelegate>>>
     }
 
     impl Self {
@@ -24,7 +188,30 @@ custom_view!(
             content_view.set_background_color(Color::clear());
             content_view.set_clips_to_bounds(true);
 
-            let scroll_view = Self::new_all(frame);
+            let scroll_view = Self::new_all(
+                frame,
+                Cell::new(DECELERATION_RATE_NORMAL),
+                Cell::new(true),
+                Cell::new(true),
+                Cell::new(EdgeInsets::zero()),
+                Cell::new(false),
+                Cell::new(None),
+                RefCell::new(None),
+                RefCell::new(None),
+                RefCell::new(Paging {
+                    enabled: false,
+                    horizontal_snap_points: Vec::new(),
+                    vertical_snap_points: Vec::new()
+                }),
+                RefCell::new(Zooming {
+                    scale: 1.0,
+                    minimum_scale: 1.0,
+                    maximum_scale: 1.0,
+                    pinch_start_scale: 1.0,
+                    pinch_anchor: None
+                }),
+                RefCell::new(None)
+            );
             scroll_view.set_background_color(Color::clear());
             scroll_view.add_subview(content_view);
             scroll_view.add_subview(vertical_scroll_bar.clone());
@@ -34,64 +221,788 @@ custom_view!(
             horizontal_scroll_bar.fit_to_superview();
 
             let pan_gesture = PanRecognizer::new(|gesture_recognizer| {
-                if gesture_recognizer.view().is_none() {
-                    return;
+                if let Some(view) = gesture_recognizer.view().upgrade() {
+                    ScrollView::from_view(view).pan_did_change(gesture_recognizer);
                 }
+            });
 
-                let view = gesture_recognizer.view().upgrade().expect("gesture recognizer view was deallocated");
-                let scroll_view = ScrollView::from_view(view.clone());
-
-                let translation = gesture_recognizer.translation_in(&view);
+            pan_gesture.set_delegate(Rc::new(ScrollViewPanDelegate {
+                scroll_view: scroll_view.view.downgrade()
+            }));
 
-                let translation = Point::new(
-                    -translation.x,
-                    -translation.y
-                );
+            let pinch_gesture = PinchRecognizer::new(|gesture_recognizer| {
+                if let Some(view) = gesture_recognizer.view().upgrade() {
+                    ScrollView::from_view(view).pinch_did_change(gesture_recognizer);
+                }
+            });
 
-                scroll_view.set_content_offset(
-                    scroll_view.content_offset() + translation
-                );
+            pinch_gesture.set_delegate(Rc::new(ScrollViewZoomDelegate {
+                scroll_view: scroll_view.view.downgrade(),
+                pan: pan_gesture.arbitration()
+            }));
 
-                gesture_recognizer.set_translation(Point::new(0, 0), &view);
+            let double_tap_gesture = TapRecognizer::new(|gesture_recognizer| {
+                if let Some(view) = gesture_recognizer.view().upgrade() {
+                    ScrollView::from_view(view).did_double_tap(gesture_recognizer);
+                }
             });
+            double_tap_gesture.set_number_of_taps_required(2);
+
+            double_tap_gesture.set_delegate(Rc::new(ScrollViewZoomDelegate {
+                scroll_view: scroll_view.view.downgrade(),
+                pan: pan_gesture.arbitration()
+            }));
 
             scroll_view.add_gesture_recognizer(Box::new(pan_gesture));
+            scroll_view.add_gesture_recognizer(Box::new(pinch_gesture));
+            scroll_view.add_gesture_recognizer(Box::new(double_tap_gesture));
 
             scroll_view
         }
 
+        /// Set the delegate to be notified about scrolling, e.g. to load more
+        /// content as the end is scrolled to.
+        pub fn set_delegate(&self, delegate: impl ScrollViewDelegate + 'static) {
+            let behavior = self.behavior();
+            behavior.delegate.replace(Some(Rc::new(delegate)));
+        }
+
+        pub(crate) fn delegate(&self) -> Option<Rc<dyn ScrollViewDelegate>> {
+            let behavior = self.behavior();
+            let delegate = behavior.delegate.borrow().clone();
+            delegate
+        }
+
+        /// The point of the content view shown at the top left of the scroll
+        /// view.
         pub fn content_offset(&self) -> Point<i32> {
             self.inner_content_view().bounds().origin
         }
 
-        fn content_size(&self) -> Size<u32> {
+        /// Scrolls the content to the given offset, kept within the edges of
+        /// the content (and its insets). When `animated`, it scrolls there
+        /// smoothly rather than jumping.
+        pub fn set_content_offset(&self, offset: Point<i32>, animated: bool) {
+            self.stop_decelerating();
+            self.stop_animating();
+
+            let offset = self.clamped_content_offset(offset);
+
+            if animated {
+                self.animate_content_offset(offset, self.zoom_scale(), false);
+            } else {
+                self.move_content_to(offset);
+            }
+        }
+
+        /// The size of the content view (as zoomed); i.e. the area that can
+        /// be scrolled around.
+        pub fn content_size(&self) -> Size<u32> {
             if let Some(content_view) = self.content_view() {
-                content_view.frame().size
+                content_view.transform().apply_to_rect(&content_view.frame()).size
             } else {
                 Size::new(0, 0)
             }
         }
 
-        fn set_content_offset(&self, offset: Point<i32>) {
-            let mut content_width = self.content_size().width;
-            let scrollview_width = self.frame().size.width;
+        /// Resize the content view, e.g. as content is added to it. Unlike
+        /// replacing the content view, the content offset stays where it is
+        /// (as far as the new size allows).
+        pub fn set_content_size(&self, size: Size<u32>) {
+            let content_view = match self.content_view() {
+                Some(content_view) => content_view,
+                None => return
+            };
+
+            let frame = content_view.frame();
+            content_view.set_frame(Rectangle::new(frame.origin.x, frame.origin.y, size.width, size.height));
+
+            let offset = self.content_offset();
+            let viewport_size = self.frame().size;
+            let inner_content_view = self.inner_content_view();
+            inner_content_view.set_frame(Rectangle::new(0, 0, size.width, size.height));
+            inner_content_view.set_bounds(Rectangle::new(offset.x, offset.y, viewport_size.width, viewport_size.height));
+
+            self.vertical_scroll_bar().update_scroll_handle();
+            self.horizontal_scroll_bar().update_scroll_handle();
+
+            // Content still moving settles within the new edges by itself.
+            let clamped = self.clamped_content_offset(offset.clone());
+            if clamped != offset && !self.is_decelerating() {
+                self.move_content_to(clamped);
+            }
+        }
+
+        /// Extra space that can be scrolled to around the content. E.g. so
+        /// the top of the content can be scrolled out from under a header.
+        pub fn content_inset(&self) -> EdgeInsets {
+            self.behavior().content_inset.get()
+        }
+
+        pub fn set_content_inset(&self, inset: EdgeInsets) {
+            let was_at_minimum = self.content_offset() == self.minimum_content_offset();
+            self.behavior().content_inset.set(inset);
+
+            // Content scrolled to the start stays at the start, rather than
+            // under the new inset.
+            if was_at_minimum {
+                self.move_content_to(self.minimum_content_offset());
+            } else {
+                self.move_content_to(self.clamped_content_offset(self.content_offset()));
+            }
+        }
+
+        /// Scrolls just enough to show the given rectangle (in the content
+        /// view's coordinate system). Does nothing if it is already visible.
+        pub fn scroll_rect_to_visible(&self, rect: Rectangle<i32, u32>, animated: bool) {
+            let offset = self.content_offset();
+            let size = self.frame().size;
+            let inset = self.content_inset();
+
+            let visible_left = offset.x + inset.left;
+            let visible_right = offset.x + size.width as i32 - inset.right;
+            let visible_top = offset.y + inset.top;
+            let visible_bottom = offset.y + size.height as i32 - inset.bottom;
+
+            let x = if rect.left() < visible_left {
+                rect.left() - inset.left
+            } else if rect.right() > visible_right {
+                rect.right() - size.width as i32 + inset.right
+            } else {
+                offset.x
+            };
 
-            let mut content_height = self.content_size().height;
-            let scrollview_height = self.frame().size.height;
+            let y = if rect.top() < visible_top {
+                rect.top() - inset.top
+            } else if rect.bottom() > visible_bottom {
+                rect.bottom() - size.height as i32 + inset.bottom
+            } else {
+                offset.y
+            };
 
-            if content_width < scrollview_width {
-                content_width = scrollview_width;
+            if Point::new(x, y) != offset {
+                self.set_content_offset(Point::new(x, y), animated);
             }
+        }
+
+        /// Whether the user can scroll the content. Programmatic scrolling
+        /// still works when disabled. `true` by default.
+        pub fn is_scroll_enabled(&self) -> bool {
+            self.behavior().scroll_enabled.get()
+        }
 
-            if content_height < scrollview_height {
-                content_height = scrollview_height;
+        pub fn set_scroll_enabled(&self, enabled: bool) {
+            self.behavior().scroll_enabled.set(enabled);
+
+            if !enabled {
+                self.stop_decelerating();
             }
+        }
+
+        /// Whether a drag that starts mostly horizontally or vertically only
+        /// scrolls along that axis until it ends. `false` by default.
+        pub fn is_directional_lock_enabled(&self) -> bool {
+            self.behavior().directional_lock_enabled.get()
+        }
+
+        pub fn set_directional_lock_enabled(&self, enabled: bool) {
+            self.behavior().directional_lock_enabled.set(enabled);
+        }
+
+        /// How quickly scrolling slows down after a flick: the fraction of
+        /// the velocity kept each millisecond. See `DECELERATION_RATE_NORMAL`
+        /// and `DECELERATION_RATE_FAST`.
+        pub fn deceleration_rate(&self) -> f32 {
+            self.behavior().deceleration_rate.get()
+        }
+
+        pub fn set_deceleration_rate(&self, rate: f32) {
+            self.behavior().deceleration_rate.set(rate.clamp(0.0, 1.0));
+        }
+
+        /// Whether the content can be dragged or flicked past its edges, and
+        /// springs back when let go. `true` by default.
+        pub fn bounces(&self) -> bool {
+            self.behavior().bounces.get()
+        }
+
+        pub fn set_bounces(&self, bounces: bool) {
+            self.behavior().bounces.set(bounces);
+        }
+
+        /// Whether the content is moving by itself, after being flicked or
+        /// pulled past an edge.
+        pub fn is_decelerating(&self) -> bool {
+            self.behavior().deceleration.borrow().is_some()
+        }
+
+        /// Whether the content snaps to whole pages (multiples of the scroll
+        /// view's size) once a drag ends, rather than decelerating. `false`
+        /// by default.
+        pub fn is_paging_enabled(&self) -> bool {
+            self.behavior().paging.borrow().enabled
+        }
+
+        pub fn set_paging_enabled(&self, enabled: bool) {
+            self.behavior().paging.borrow_mut().enabled = enabled;
+        }
+
+        pub fn snap_points(&self, direction: ScrollBarDirection) -> Vec<i32> {
+            let behavior = self.behavior();
+            let paging = behavior.paging.borrow();
+            match direction {
+                ScrollBarDirection::Horizontal => paging.horizontal_snap_points.clone(),
+                ScrollBarDirection::Vertical => paging.vertical_snap_points.clone()
+            }
+        }
+
+        /// Content offsets along one axis for the content to snap to once a
+        /// drag ends, instead of pages; e.g. the start of each row. This works
+        /// whether or not paging is enabled. Empty (the default) to not snap
+        /// along the axis.
+        pub fn set_snap_points(&self, direction: ScrollBarDirection, offsets: Vec<i32>) {
+            let behavior = self.behavior();
+            let mut paging = behavior.paging.borrow_mut();
+            match direction {
+                ScrollBarDirection::Horizontal => paging.horizontal_snap_points = offsets,
+                ScrollBarDirection::Vertical => paging.vertical_snap_points = offsets
+            }
+        }
+
+        /// How much the view returned by the delegate's `view_for_zooming` is
+        /// scaled up. `1.0` by default.
+        pub fn zoom_scale(&self) -> f32 {
+            self.behavior().zooming.borrow().scale
+        }
+
+        /// Zooms to the given scale (within the minimum and maximum), keeping
+        /// the middle of the scroll view where it is. Does nothing without a
+        /// view for zooming.
+        pub fn set_zoom_scale(&self, scale: f32, animated: bool) {
+            self.stop_decelerating();
+            self.stop_animating();
+
+            let size = self.frame().size;
+            let center = Point::new(size.width as i32 / 2, size.height as i32 / 2);
+
+            if animated {
+                self.animate_zoom(scale, &center, &center);
+            } else {
+                let anchor = self.unzoomed_point_at(&center);
+                self.zoom_around(scale, anchor, &center);
+            }
+        }
+
+        pub fn minimum_zoom_scale(&self) -> f32 {
+            self.behavior().zooming.borrow().minimum_scale
+        }
+
+        /// The furthest the content can be zoomed out. `1.0` by default.
+        pub fn set_minimum_zoom_scale(&self, scale: f32) {
+            self.behavior().zooming.borrow_mut().minimum_scale = scale;
+            self.set_zoom_scale(self.zoom_scale(), false);
+        }
+
+        pub fn maximum_zoom_scale(&self) -> f32 {
+            self.behavior().zooming.borrow().maximum_scale
+        }
+
+        /// The furthest the content can be zoomed in. `1.0` by default, so
+        /// it must be raised for the user to be able to zoom.
+        pub fn set_maximum_zoom_scale(&self, scale: f32) {
+            self.behavior().zooming.borrow_mut().maximum_scale = scale;
+            self.set_zoom_scale(self.zoom_scale(), false);
+        }
+
+        fn view_for_zooming(&self) -> Option<View> {
+            self.delegate().and_then(|delegate| delegate.view_for_zooming(self))
+        }
+
+        fn is_zoom_enabled(&self) -> bool {
+            self.maximum_zoom_scale() > self.minimum_zoom_scale() && self.view_for_zooming().is_some()
+        }
+
+        fn clamped_zoom_scale(&self, scale: f32) -> f32 {
+            scale.max(self.minimum_zoom_scale()).min(self.maximum_zoom_scale())
+        }
+
+        /// The point of the unzoomed content shown at the given point of the
+        /// scroll view.
+        fn unzoomed_point_at(&self, point: &Point<i32>) -> (f32, f32) {
+            let offset = self.content_offset();
+            let scale = self.zoom_scale();
+
+            (
+                (offset.x + point.x) as f32 / scale,
+                (offset.y + point.y) as f32 / scale
+            )
+        }
+
+        /// Zooms to `scale`, scrolling so the `anchor` (in the unzoomed
+        /// content) is shown at `location` in the scroll view, as far as the
+        /// edges allow.
+        fn zoom_around(&self, scale: f32, anchor: (f32, f32), location: &Point<i32>) {
+            let view = match self.view_for_zooming() {
+                Some(view) => view,
+                None => return
+            };
+
+            self.apply_zoom_scale(&view, self.clamped_zoom_scale(scale));
+
+            let scale = self.zoom_scale();
+            let offset = Point::new(
+                (anchor.0 * scale).round() as i32 - location.x,
+                (anchor.1 * scale).round() as i32 - location.y
+            );
+
+            self.move_content_to(self.clamped_content_offset(offset));
+        }
+
+        /// Scales the view, leaving its top left where it is so the content
+        /// offset still starts from the top left of the content.
+        fn apply_zoom_scale(&self, view: &View, scale: f32) {
+            self.behavior().zooming.borrow_mut().scale = scale;
+
+            let size = view.frame().size;
+            view.set_transform(
+                Transform::scale(scale, scale).translated(
+                    (scale - 1.0) * size.width as f32 / 2.0,
+                    (scale - 1.0) * size.height as f32 / 2.0
+                )
+            );
+
+            self.vertical_scroll_bar().update_scroll_handle();
+            self.horizontal_scroll_bar().update_scroll_handle();
+
+            if let Some(delegate) = self.delegate() {
+                delegate.did_zoom(self);
+            }
+        }
+
+        /// Zooms smoothly to `scale`, ending up with the point at `from` (in
+        /// the scroll view) at `to`.
+        fn animate_zoom(&self, scale: f32, from: &Point<i32>, to: &Point<i32>) {
+            let view = match self.view_for_zooming() {
+                Some(view) => view,
+                None => return
+            };
+
+            let scale = self.clamped_zoom_scale(scale);
+            let (x, y) = self.unzoomed_point_at(from);
+
+            // Where the content can be scrolled to at the new scale.
+            let unzoomed_size = view.frame().size;
+            let content_size = Size::new(
+                (unzoomed_size.width as f32 * scale).round() as u32,
+                (unzoomed_size.height as f32 * scale).round() as u32
+            );
+            let min = self.minimum_content_offset();
+            let max = self.maximum_content_offset_for(&content_size);
+
+            let offset = Point::new(
+                ((x * scale).round() as i32 - to.x).max(min.x).min(max.x),
+                ((y * scale).round() as i32 - to.y).max(min.y).min(max.y)
+            );
+
+            self.animate_content_offset(offset, scale, false);
+        }
+
+        fn pinch_did_change(&self, recognizer: &PinchRecognizer) {
+            let location = recognizer.location_in(self);
+
+            match recognizer.state() {
+                RecognizerState::Began => {
+                    self.stop_decelerating();
+                    self.stop_animating();
+
+                    let anchor = self.unzoomed_point_at(&location);
+                    let behavior = self.behavior();
+                    let mut zooming = behavior.zooming.borrow_mut();
+                    zooming.pinch_start_scale = zooming.scale;
+                    zooming.pinch_anchor = Some(anchor);
+                },
+                RecognizerState::Changed => {
+                    let (start_scale, anchor) = {
+                        let behavior = self.behavior();
+                        let zooming = behavior.zooming.borrow();
+                        (zooming.pinch_start_scale, zooming.pinch_anchor)
+                    };
+
+                    if let Some(anchor) = anchor {
+                        self.zoom_around(start_scale * recognizer.scale(), anchor, &location);
+                    }
+                },
+                RecognizerState::Ended | RecognizerState::Cancelled => {
+                    self.behavior().zooming.borrow_mut().pinch_anchor = None;
+                },
+                _ => {}
+            }
+        }
+
+        /// Zooms in on the tapped point, or back out if already zoomed in.
+        fn did_double_tap(&self, recognizer: &TapRecognizer) {
+            self.stop_decelerating();
+            self.stop_animating();
+
+            let location = recognizer.location_in(self);
+            let size = self.frame().size;
+            let center = Point::new(size.width as i32 / 2, size.height as i32 / 2);
+
+            if self.zoom_scale() > self.minimum_zoom_scale() {
+                self.animate_zoom(self.minimum_zoom_scale(), &location, &location);
+            } else {
+                self.animate_zoom(self.maximum_zoom_scale(), &location, &center);
+            }
+        }
 
-            let max_x = content_width - scrollview_width;
-            let max_y = content_height - scrollview_height;
+        fn pan_did_change(&self, recognizer: &PanRecognizer) {
+            let translation = recognizer.translation_in(self);
+            recognizer.set_translation(Point::new(0, 0), self);
 
-            let x = offset.x.max(0).min(max_x as i32);
-            let y = offset.y.max(0).min(max_y as i32);
+            // The content moves the opposite way to the finger.
+            let translation = Point::new(-translation.x, -translation.y);
+
+            match recognizer.state() {
+                RecognizerState::Began => {
+                    // Catch the content if it's still moving.
+                    self.stop_decelerating();
+                    self.stop_animating();
+                    self.lock_direction(&translation);
+
+                    if let Some(delegate) = self.delegate() {
+                        delegate.will_begin_dragging(self);
+                    }
+
+                    self.drag_content_by(translation);
+                },
+                RecognizerState::Changed => {
+                    self.drag_content_by(translation);
+                },
+                RecognizerState::Ended => {
+                    self.drag_content_by(translation);
+
+                    let velocity = recognizer.velocity_in(self);
+                    self.end_dragging(Point::new(-velocity.x, -velocity.y));
+                },
+                RecognizerState::Cancelled => {
+                    self.end_dragging(Point::new(0.0, 0.0));
+                },
+                _ => {}
+            }
+        }
+
+        /// Locks a drag that is starting (with the given translation) to
+        /// the axis it's mostly along, if `directional_lock_enabled`.
+        fn lock_direction(&self, translation: &Point<i32>) {
+            let direction = if !self.is_directional_lock_enabled() {
+                None
+            } else if translation.x.abs() > translation.y.abs() * 2 {
+                Some(ScrollBarDirection::Horizontal)
+            } else if translation.y.abs() > translation.x.abs() * 2 {
+                Some(ScrollBarDirection::Vertical)
+            } else {
+                None
+            };
+
+            self.behavior().locked_direction.set(direction);
+        }
+
+        fn end_dragging(&self, velocity: Point<f32>) {
+            let velocity = match self.behavior().locked_direction.get() {
+                Some(ScrollBarDirection::Horizontal) => Point::new(velocity.x, 0.0),
+                Some(ScrollBarDirection::Vertical) => Point::new(0.0, velocity.y),
+                None => velocity
+            };
+
+            self.behavior().locked_direction.set(None);
+
+            let will_decelerate = match self.snap_offset(&velocity) {
+                Some(offset) => {
+                    self.animate_content_offset(offset, self.zoom_scale(), true);
+                    true
+                },
+                None => self.start_decelerating(velocity)
+            };
+
+            if let Some(delegate) = self.delegate() {
+                delegate.did_end_dragging(self, will_decelerate);
+            }
+        }
+
+        /// Where the content should snap to after a drag that ended with the
+        /// given velocity, if it snaps along either axis and isn't there
+        /// already.
+        fn snap_offset(&self, velocity: &Point<f32>) -> Option<Point<i32>> {
+            let horizontal = self.snap_points_along(ScrollBarDirection::Horizontal);
+            let vertical = self.snap_points_along(ScrollBarDirection::Vertical);
+
+            if horizontal.is_empty() && vertical.is_empty() {
+                return None;
+            }
+
+            let offset = self.content_offset();
+            let clamped = self.clamped_content_offset(offset.clone());
+
+            let snapped = Point::new(
+                snap(clamped.x, velocity.x, &horizontal),
+                snap(clamped.y, velocity.y, &vertical)
+            );
+
+            if snapped == offset {
+                None
+            } else {
+                Some(snapped)
+            }
+        }
+
+        /// The custom snap points along the axis, or else page boundaries if
+        /// paging is enabled.
+        fn snap_points_along(&self, direction: ScrollBarDirection) -> Vec<i32> {
+            let points = self.snap_points(direction);
+            if !points.is_empty() || !self.is_paging_enabled() {
+                return points;
+            }
+
+            let min = self.minimum_content_offset();
+            let max = self.maximum_content_offset();
+            let size = self.frame().size;
+
+            let (min, max, page) = match direction {
+                ScrollBarDirection::Horizontal => (min.x, max.x, size.width as i32),
+                ScrollBarDirection::Vertical => (min.y, max.y, size.height as i32)
+            };
+
+            if page == 0 {
+                return vec![min];
+            }
+
+            // The last page may be short, so the end is a snap point too.
+            let mut points: Vec<i32> = (min..max).step_by(page as usize).collect();
+            points.push(max);
+            points
+        }
+
+        fn clamped_content_offset(&self, offset: Point<i32>) -> Point<i32> {
+            let min = self.minimum_content_offset();
+            let max = self.maximum_content_offset();
+
+            Point::new(
+                offset.x.max(min.x).min(max.x),
+                offset.y.max(min.y).min(max.y)
+            )
+        }
+
+        /// The content offset with the content scrolled to its top left,
+        /// including the inset.
+        fn minimum_content_offset(&self) -> Point<i32> {
+            let inset = self.content_inset();
+            Point::new(-inset.left, -inset.top)
+        }
+
+        /// The furthest the content can be scrolled without going past its
+        /// edges (and inset).
+        fn maximum_content_offset(&self) -> Point<i32> {
+            self.maximum_content_offset_for(&self.content_size())
+        }
+
+        fn maximum_content_offset_for(&self, content_size: &Size<u32>) -> Point<i32> {
+            let min = self.minimum_content_offset();
+            let inset = self.content_inset();
+            let scrollview_size = self.frame().size;
+
+            Point::new(
+                (content_size.width as i32 + inset.right - scrollview_size.width as i32).max(min.x),
+                (content_size.height as i32 + inset.bottom - scrollview_size.height as i32).max(min.y)
+            )
+        }
+
+        /// Moves the content by the given amount, keeping it within its
+        /// edges, except (if `bounces`) past them with resistance.
+        fn drag_content_by(&self, translation: Point<i32>) {
+            let translation = match self.behavior().locked_direction.get() {
+                Some(ScrollBarDirection::Horizontal) => Point::new(translation.x, 0),
+                Some(ScrollBarDirection::Vertical) => Point::new(0, translation.y),
+                None => translation
+            };
+
+            let offset = self.content_offset();
+
+            if !self.bounces() {
+                self.move_content_to(self.clamped_content_offset(offset + translation));
+                return;
+            }
+
+            let min = self.minimum_content_offset();
+            let max = self.maximum_content_offset();
+
+            // Only along the axes there is content to scroll.
+            let x = if max.x > min.x { rubber_band(offset.x, translation.x, min.x, max.x) } else { min.x };
+            let y = if max.y > min.y { rubber_band(offset.y, translation.y, min.y, max.y) } else { min.y };
+
+            self.move_content_to(Point::new(x, y));
+        }
+
+        /// Carries on moving the content at the given velocity (in points per
+        /// second), slowing down by `deceleration_rate`. Content past an edge
+        /// springs back to it.
+        ///
+        /// Returns `false` if the content is already still.
+        fn start_decelerating(&self, velocity: Point<f32>) -> bool {
+            self.stop_decelerating();
+
+            let offset = self.content_offset();
+            let in_bounds = self.clamped_content_offset(offset.clone()) == offset;
+
+            if in_bounds && velocity.x.abs() < MINIMUM_VELOCITY && velocity.y.abs() < MINIMUM_VELOCITY {
+                return false;
+            }
+
+            let weak_view = self.view.downgrade();
+            let timer = frame_timer(move || {
+                if let Some(view) = weak_view.upgrade() {
+                    ScrollView::from_view(view).step_deceleration();
+                }
+            });
+
+            let behavior = self.behavior();
+            behavior.deceleration.replace(Some(Deceleration {
+                timer,
+                offset: (offset.x as f32, offset.y as f32),
+                velocity: (velocity.x, velocity.y),
+                last_step_at: Instant::now()
+            }));
+
+            true
+        }
+
+        fn stop_decelerating(&self) {
+            let behavior = self.behavior();
+            if let Some(deceleration) = behavior.deceleration.take() {
+                deceleration.timer.invalidate();
+            }
+        }
+
+        fn step_deceleration(&self) {
+            let rate = self.deceleration_rate();
+            let bounces = self.bounces();
+            let min = self.minimum_content_offset();
+            let max = self.maximum_content_offset();
+
+            let (offset, finished) = {
+                let behavior = self.behavior();
+                let mut deceleration = behavior.deceleration.borrow_mut();
+                let deceleration = match deceleration.as_mut() {
+                    Some(deceleration) => deceleration,
+                    None => return
+                };
+
+                let now = Instant::now();
+                let elapsed_ms = now.duration_since(deceleration.last_step_at).as_secs_f32() * 1000.0;
+                deceleration.last_step_at = now;
+
+                let (x, velocity_x) = decelerate(deceleration.offset.0, deceleration.velocity.0, min.x as f32, max.x as f32, rate, bounces, elapsed_ms);
+                let (y, velocity_y) = decelerate(deceleration.offset.1, deceleration.velocity.1, min.y as f32, max.y as f32, rate, bounces, elapsed_ms);
+                deceleration.offset = (x, y);
+                deceleration.velocity = (velocity_x, velocity_y);
+
+                let finished = velocity_x == 0.0 && velocity_y == 0.0 &&
+                    x >= min.x as f32 && x <= max.x as f32 && y >= min.y as f32 && y <= max.y as f32;
+
+                (Point::new(x.round() as i32, y.round() as i32), finished)
+            };
+
+            self.move_content_to(offset);
+
+            if finished {
+                self.stop_decelerating();
+
+                if let Some(delegate) = self.delegate() {
+                    delegate.did_end_decelerating(self);
+                }
+            }
+        }
+
+        /// Moves the content smoothly to the given offset, zooming to `scale`
+        /// on the way.
+        fn animate_content_offset(&self, offset: Point<i32>, scale: f32, ends_deceleration: bool) {
+            self.stop_animating();
+
+            // Eased out, so it slows down as it arrives.
+            let weak_view = self.view.downgrade();
+            let weak_completion_view = weak_view.clone();
+            let progress = Animation::new(Duration::from_millis(SCROLL_ANIMATION_DURATION_MS), 0.0, 1.0, AnimationCurve::EaseOut)
+                .with_tick(move |progress| {
+                    if let Some(view) = weak_view.upgrade() {
+                        ScrollView::from_view(view).step_animation(*progress);
+                    }
+                })
+                .with_completion(move |finished| {
+                    if let Some(view) = weak_completion_view.upgrade().filter(|_| finished) {
+                        ScrollView::from_view(view).finish_animating();
+                    }
+                });
+
+            {
+                let behavior = self.behavior();
+                behavior.animation.replace(Some(ScrollAnimation {
+                    progress: progress.clone(),
+                    from: self.content_offset(),
+                    to: offset,
+                    from_scale: self.zoom_scale(),
+                    to_scale: scale,
+                    ends_deceleration
+                }));
+            }
+
+            progress.start();
+        }
+
+        fn stop_animating(&self) {
+            let animation = self.behavior().animation.take();
+            if let Some(animation) = animation {
+                animation.progress.cancel();
+            }
+        }
+
+        /// Moves the content `progress` of the way to where it's animating
+        /// to.
+        fn step_animation(&self, progress: f32) {
+            let (offset, scale) = {
+                let behavior = self.behavior();
+                let animation = behavior.animation.borrow();
+                let animation = match animation.as_ref() {
+                    Some(animation) => animation,
+                    None => return
+                };
+
+                (animation.from.interpolate(&animation.to, progress), animation.from_scale.interpolate(&animation.to_scale, progress))
+            };
+
+            if scale != self.zoom_scale() {
+                if let Some(view) = self.view_for_zooming() {
+                    self.apply_zoom_scale(&view, scale);
+                }
+            }
+
+            self.move_content_to(offset);
+        }
+
+        fn finish_animating(&self) {
+            let animation = self.behavior().animation.take();
+
+            if animation.is_some_and(|animation| animation.ends_deceleration) {
+                if let Some(delegate) = self.delegate() {
+                    delegate.did_end_decelerating(self);
+                }
+            }
+        }
+
+        /// Moves the content, without keeping it within its edges.
+        fn move_content_to(&self, offset: Point<i32>) {
+            let min = self.minimum_content_offset();
+            let max = self.maximum_content_offset();
+            let x = offset.x;
+            let y = offset.y;
 
             self.inner_content_view().set_bounds(
                 Rectangle::new(
@@ -102,11 +1013,16 @@ custom_view!(
                 )
             );
 
-            let vertical_percent = y as f32 / max_y as f32 * 100.0;
-            let horizontal_percent = x as f32 / max_x as f32 * 100.0;
+            let vertical_percent = ((y - min.y) as f32 / (max.y - min.y) as f32 * 100.0).clamp(0.0, 100.0);
+            let horizontal_percent = ((x - min.x) as f32 / (max.x - min.x) as f32 * 100.0).clamp(0.0, 100.0);
 
             self.vertical_scroll_bar().set_percent(vertical_percent as u8);
             self.horizontal_scroll_bar().set_percent(horizontal_percent as u8);
+            self.flash_scroll_bars();
+
+            if let Some(delegate) = self.delegate() {
+                delegate.did_scroll(self);
+            }
         }
 
         fn inner_content_view(&self) -> View {
@@ -145,6 +1061,80 @@ custom_view!(
             ScrollBarView::from_view(view)
         }
 
+        fn scroll_bar(&self, direction: ScrollBarDirection) -> ScrollBarView {
+            match direction {
+                ScrollBarDirection::Vertical => self.vertical_scroll_bar(),
+                ScrollBarDirection::Horizontal => self.horizontal_scroll_bar()
+            }
+        }
+
+        pub fn scroll_bar_style(&self, direction: ScrollBarDirection) -> ScrollBarStyle {
+            self.scroll_bar(direction).style()
+        }
+
+        /// Change how the scroll bar along the given axis looks.
+        pub fn set_scroll_bar_style(&self, direction: ScrollBarDirection, style: ScrollBarStyle) {
+            self.scroll_bar(direction).set_style(style);
+        }
+
+        pub fn shows_scroll_bar(&self, direction: ScrollBarDirection) -> bool {
+            self.scroll_bar(direction).is_shown()
+        }
+
+        /// Show or hide the scroll bar along the given axis. Scroll bars are
+        /// only ever shown when there is content to scroll along that axis.
+        pub fn set_shows_scroll_bar(&self, direction: ScrollBarDirection, shows: bool) {
+            self.scroll_bar(direction).set_shown(shows);
+        }
+
+        /// Whether the scroll bars fade out after scrolling stops, rather
+        /// than staying. `true` by default.
+        pub fn auto_hides_scroll_bars(&self) -> bool {
+            self.vertical_scroll_bar().auto_hides()
+        }
+
+        pub fn set_auto_hides_scroll_bars(&self, auto_hides: bool) {
+            self.vertical_scroll_bar().set_auto_hides(auto_hides);
+            self.horizontal_scroll_bar().set_auto_hides(auto_hides);
+        }
+
+        /// Briefly shows the scroll bars. E.g. to hint that there is more
+        /// content when the scroll view first appears.
+        pub fn flash_scroll_bars(&self) {
+            self.vertical_scroll_bar().flash();
+            self.horizontal_scroll_bar().flash();
+        }
+
+        fn is_dragging_scroll_bar(&self) -> bool {
+            self.vertical_scroll_bar().is_dragging() || self.horizontal_scroll_bar().is_dragging()
+        }
+
+        /// Scrolls along one axis by whole viewports; backwards for negative
+        /// `pages`.
+        fn scroll_by_page(&self, direction: ScrollBarDirection, pages: i32) {
+            let offset = self.content_offset();
+            let size = self.frame().size;
+
+            let offset = match direction {
+                ScrollBarDirection::Vertical => Point::new(offset.x, offset.y + pages * size.height as i32),
+                ScrollBarDirection::Horizontal => Point::new(offset.x + pages * size.width as i32, offset.y)
+            };
+
+            self.set_content_offset(offset, true);
+        }
+
+        /// Scrolls along one axis, leaving the other where it is.
+        fn scroll_to(&self, direction: ScrollBarDirection, offset: i32) {
+            let current = self.content_offset();
+
+            let offset = match direction {
+                ScrollBarDirection::Vertical => Point::new(current.x, offset),
+                ScrollBarDirection::Horizontal => Point::new(offset, current.y)
+            };
+
+            self.set_content_offset(offset, false);
+        }
+
         fn update_content_size(&self, size: Size<u32>) {
             let inner_content_view = self.inner_content_view();
             inner_content_view.set_frame(Rectangle::new(0, 0, size.width, size.height));
@@ -162,7 +1152,107 @@ custom_view!(
             self.horizontal_scroll_bar().update_scroll_handle();
         }
     }
-);
+
+    impl Behavior {
+        /// Keep the content's viewport and the scroll bars fitting the
+        /// scroll view as it's resized.
+        fn layout_subviews(&self) {
+            let scroll_view = self.view_type();
+            let size = scroll_view.bounds().size;
+            let offset = scroll_view.content_offset();
+            scroll_view.inner_content_view().set_bounds(Rectangle::new(offset.x, offset.y, size.width, size.height));
+
+            for scroll_bar in [scroll_view.vertical_scroll_bar(), scroll_view.horizontal_scroll_bar()].iter() {
+                scroll_bar.fit_to_superview();
+                scroll_bar.update_scroll_handle();
+            }
+
+            let clamped = scroll_view.clamped_content_offset(offset.clone());
+            if clamped != offset && !scroll_view.is_decelerating() {
+                scroll_view.move_content_to(clamped);
+            }
+        }
+    }
+);
+
+/// The new content offset along one axis after dragging it by `delta`, where
+/// the part of the drag past either edge (`min` and `max`) only moves it by
+/// `RUBBER_BAND_RESISTANCE`.
+fn rubber_band(offset: i32, delta: i32, min: i32, max: i32) -> i32 {
+    let resist = |offset: f32| -> f32 {
+        let edge = offset.max(min as f32).min(max as f32);
+        edge + (offset - edge) * RUBBER_BAND_RESISTANCE
+    };
+
+    // Where the drag would be without resistance.
+    let offset = offset as f32;
+    let edge = offset.max(min as f32).min(max as f32);
+    let dragged = edge + (offset - edge) / RUBBER_BAND_RESISTANCE + delta as f32;
+
+    resist(dragged).round() as i32
+}
+
+/// The snap point to settle on along one axis, for content at `offset` that
+/// was let go at `velocity`. A fast enough flick carries on to the next point
+/// in its direction; otherwise the nearest is picked.
+fn snap(offset: i32, velocity: f32, points: &[i32]) -> i32 {
+    let before = points.iter().filter(|point| **point <= offset).max();
+    let after = points.iter().filter(|point| **point >= offset).min();
+
+    match (before, after) {
+        (Some(&before), Some(&after)) => {
+            if velocity > PAGING_VELOCITY_THRESHOLD {
+                after
+            } else if velocity < -PAGING_VELOCITY_THRESHOLD || offset - before <= after - offset {
+                before
+            } else {
+                after
+            }
+        },
+        (Some(&before), None) => before,
+        (None, Some(&after)) => after,
+        (None, None) => offset
+    }
+}
+
+/// Moves content along one axis for `elapsed_ms`, returning the new offset
+/// and velocity (in points per second). Within the edges (`min` and `max`) it
+/// slows down by `rate` each millisecond; past them it springs back, or stops
+/// at the edge if the content doesn't bounce.
+fn decelerate(offset: f32, velocity: f32, min: f32, max: f32, rate: f32, bounces: bool, elapsed_ms: f32) -> (f32, f32) {
+    let elapsed = elapsed_ms / 1000.0;
+    let edge = offset.max(min).min(max);
+
+    if offset != edge {
+        if !bounces {
+            return (edge, 0.0);
+        }
+
+        let displacement = offset - edge;
+        let (new_displacement, velocity) = Spring::critically_damped(1.0, BOUNCE_STIFFNESS).step(displacement, velocity, elapsed);
+        let new_offset = edge + new_displacement;
+
+        // Back at (or over) the edge, or close enough to it.
+        let settled = (new_offset - edge).abs() < 0.5 && velocity.abs() < MINIMUM_VELOCITY;
+        if (new_offset - edge) * displacement <= 0.0 || settled {
+            return (edge, 0.0);
+        }
+
+        return (new_offset, velocity);
+    }
+
+    let velocity = velocity * rate.powf(elapsed_ms);
+    if velocity.abs() < MINIMUM_VELOCITY {
+        return (offset, 0.0);
+    }
+
+    let new_offset = offset + velocity * elapsed;
+    if !bounces && (new_offset < min || new_offset > max) {
+        return (new_offset.max(min).min(max), 0.0);
+    }
+
+    (new_offset, velocity)
+}
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum ScrollBarDirection {
@@ -170,23 +1260,85 @@ pub enum ScrollBarDirection {
     Horizontal
 }
 
+/// How a `ScrollView`'s scroll bar looks. See
+/// `ScrollView::set_scroll_bar_style`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScrollBarStyle {
+    /// The colour of the handle.
+    pub color: Color,
+
+    /// How wide the vertical scroll bar is, or how tall the horizontal one
+    /// is.
+    pub thickness: u32,
+
+    /// The thickness while the mouse is over the scroll bar, making it
+    /// easier to grab.
+    pub hover_thickness: u32,
+
+    /// Space between the scroll bar and the edges of the scroll view.
+    pub insets: EdgeInsets
+}
+
+impl Default for ScrollBarStyle {
+    fn default() -> ScrollBarStyle {
+        ScrollBarStyle {
+            color: Color::new(127, 127, 127, 127),
+            thickness: 10,
+            hover_thickness: 14,
+            insets: EdgeInsets::zero()
+        }
+    }
+}
+
 custom_view!(
     ScrollBarView subclasses DefaultBehavior
 
     struct ScrollBarViewBehavior {
         direction: ScrollBarDirection,
-        percent: Cell<u8>
+        percent: Cell<u8>,
+        style: RefCell<ScrollBarStyle>,
+        shown: Cell<bool>,
+        auto_hides: Cell<bool>,
+        hovered: Cell<bool>,
+        opacity: Cell<f32>,
+
+        // Starts fading the scroll bar out once it hasn't been flashed for a
+        // while.
+        hide_timer: RefCell<Option<Timer>>,
+
+        fade: RefCell<Option<Animation<f32>>>,
+
+        // Where along the scroll bar the handle was grabbed, and the content
+        // offset along the same axis at the time.
+        drag_origin: Cell<Option<(i32, i32)>>
     }
 
     impl Self {
         fn new(direction: ScrollBarDirection) -> Self {
             let handle = View::new(Rectangle::new(0, 0, 10, 10));
-            handle.set_background_color(Color::new(127, 127, 127, 127));
 
-            let scroll_bar_view = Self::new_all(Rectangle::new(0, 0, 10, 10), direction, Cell::new(0));
+            // Touches on the handle go to the scroll bar, to drag it.
+            handle.set_user_interaction_enabled(false);
+
+            let scroll_bar_view = Self::new_all(
+                Rectangle::new(0, 0, 10, 10),
+                direction,
+                Cell::new(0),
+                RefCell::new(ScrollBarStyle::default()),
+                Cell::new(true),
+                Cell::new(true),
+                Cell::new(false),
+                Cell::new(0.0),
+                RefCell::new(None),
+                RefCell::new(None),
+                Cell::new(None)
+            );
             scroll_bar_view.set_background_color(Color::clear());
             scroll_bar_view.add_subview(handle);
 
+            // Hidden until there's scrolling.
+            scroll_bar_view.set_opacity(0.0);
+
             scroll_bar_view
         }
 
@@ -210,26 +1362,195 @@ custom_view!(
             behavior.direction
         }
 
+        fn style(&self) -> ScrollBarStyle {
+            self.behavior().style.borrow().clone()
+        }
+
+        fn set_style(&self, style: ScrollBarStyle) {
+            self.behavior().style.replace(style);
+            self.fit_to_superview();
+            self.update_scroll_handle();
+            self.set_opacity(self.opacity());
+        }
+
+        fn is_shown(&self) -> bool {
+            self.behavior().shown.get()
+        }
+
+        fn set_shown(&self, shown: bool) {
+            self.behavior().shown.set(shown);
+            self.update_scroll_handle();
+        }
+
+        fn auto_hides(&self) -> bool {
+            self.behavior().auto_hides.get()
+        }
+
+        fn set_auto_hides(&self, auto_hides: bool) {
+            self.behavior().auto_hides.set(auto_hides);
+            self.flash();
+        }
+
+        fn is_dragging(&self) -> bool {
+            self.behavior().drag_origin.get().is_some()
+        }
+
+        /// The thickness right now, which is wider while hovered.
+        fn thickness(&self) -> u32 {
+            let behavior = self.behavior();
+            let style = behavior.style.borrow();
+
+            if behavior.hovered.get() {
+                style.hover_thickness
+            } else {
+                style.thickness
+            }
+        }
+
+        fn opacity(&self) -> f32 {
+            self.behavior().opacity.get()
+        }
+
+        fn set_opacity(&self, opacity: f32) {
+            self.behavior().opacity.set(opacity);
+
+            let mut color = self.style().color;
+            color.alpha = (color.alpha as f32 * opacity).round() as u8;
+            self.handle().set_background_color(color);
+
+            self.update_user_interaction();
+        }
+
+        /// Only a visible scroll bar can be grabbed. Otherwise touches go to
+        /// the content underneath.
+        fn update_user_interaction(&self) {
+            let visible = self.opacity() > 0.0 && !self.handle().is_hidden();
+            self.set_user_interaction_enabled(visible);
+        }
+
+        /// Shows the scroll bar, then fades it out after a moment (if it
+        /// `auto_hides`).
+        fn flash(&self) {
+            let fade = self.behavior().fade.take();
+            if let Some(fade) = fade {
+                fade.cancel();
+            }
+
+            self.set_opacity(1.0);
+
+            if !self.auto_hides() {
+                if let Some(timer) = self.behavior().hide_timer.take() {
+                    timer.invalidate();
+                }
+                return;
+            }
+
+            // Flashed again before hiding, e.g. on every step of a scroll;
+            // put off hiding rather than starting over.
+            if let Some(timer) = self.behavior().hide_timer.borrow().as_ref() {
+                if timer.is_valid() {
+                    timer.reschedule(Duration::from_millis(SCROLL_BAR_HIDE_DELAY_MS));
+                    return;
+                }
+            }
+
+            let weak_view = self.view.downgrade();
+            let timer = Timer::new_once_delayed(Duration::from_millis(SCROLL_BAR_HIDE_DELAY_MS), move || {
+                if let Some(view) = weak_view.upgrade() {
+                    ScrollBarView::from_view(view).start_fading();
+                }
+            });
+
+            self.behavior().hide_timer.replace(Some(timer.clone()));
+
+            let run_loop = RunLoop::borrow();
+            run_loop.add_timer(timer);
+        }
+
+        fn start_fading(&self) {
+            self.behavior().hide_timer.replace(None);
+
+            // Stays while in use. Flashed again once the mouse leaves or the
+            // drag ends.
+            if self.behavior().hovered.get() || self.is_dragging() {
+                return;
+            }
+
+            let weak_view = self.view.downgrade();
+            let fade = Animation::new(Duration::from_millis(SCROLL_BAR_FADE_DURATION_MS), self.opacity(), 0.0, AnimationCurve::Linear)
+                .with_tick(move |opacity| {
+                    if let Some(view) = weak_view.upgrade() {
+                        ScrollBarView::from_view(view).set_opacity(*opacity);
+                    }
+                });
+
+            fade.start();
+            self.behavior().fade.replace(Some(fade));
+        }
+
+        fn scroll_view(&self) -> ScrollView {
+            let superview = self.superview().upgrade().expect("scroll view missing superview");
+            ScrollView::from_view(superview)
+        }
+
+        /// The position of the touch along the scroll bar.
+        fn position_of(&self, touch: &Touch) -> i32 {
+            let window = touch.window().expect("touch missing window");
+            let position = window.convert_point_to(&touch.position(), self);
+
+            match self.direction() {
+                ScrollBarDirection::Vertical => position.y,
+                ScrollBarDirection::Horizontal => position.x
+            }
+        }
+
+        /// The content offset along this scroll bar's axis.
+        fn content_offset(&self) -> i32 {
+            let offset = self.scroll_view().content_offset();
+
+            match self.direction() {
+                ScrollBarDirection::Vertical => offset.y,
+                ScrollBarDirection::Horizontal => offset.x
+            }
+        }
+
+        /// The length of the scroll bar, and the length of the viewport and
+        /// how far it can scroll along the same axis.
+        fn lengths(&self) -> (i32, i32, i32) {
+            let scroll_view = self.scroll_view();
+            let min = scroll_view.minimum_content_offset();
+            let max = scroll_view.maximum_content_offset();
+            let size = self.frame().size;
+            let viewport = scroll_view.frame().size;
+
+            match self.direction() {
+                ScrollBarDirection::Vertical => (size.height as i32, viewport.height as i32, max.y - min.y),
+                ScrollBarDirection::Horizontal => (size.width as i32, viewport.width as i32, max.x - min.x)
+            }
+        }
+
         fn fit_to_superview(&self) {
             let superview = self.superview().upgrade().expect("scroll view missing superview");
             let superview_size = superview.frame().size;
+            let insets = self.style().insets;
+            let thickness = self.thickness();
             let frame: Rectangle<i32, u32>;
 
             match self.direction() {
                 ScrollBarDirection::Vertical => {
                     frame = Rectangle::new(
-                        superview_size.width as i32 - 10,
-                        0,
-                        10,
-                        superview_size.height
+                        superview_size.width as i32 - thickness as i32 - insets.right,
+                        insets.top,
+                        thickness,
+                        (superview_size.height as i32 - insets.vertical()).max(0) as u32
                     );
                 },
                 ScrollBarDirection::Horizontal => {
                     frame = Rectangle::new(
-                        0,
-                        superview_size.height as i32 - 10,
-                        superview_size.width,
-                        10
+                        insets.left,
+                        superview_size.height as i32 - thickness as i32 - insets.bottom,
+                        (superview_size.width as i32 - insets.horizontal()).max(0) as u32,
+                        thickness
                     );
                 }
             }
@@ -238,79 +1559,113 @@ custom_view!(
         }
 
         fn update_scroll_handle(&self) {
-            let superview = self.superview().upgrade().expect("scroll view missing superview");
-            let scrollview = ScrollView::from_view(superview);
-            let inner_content_view = scrollview.inner_content_view();
             let handle = self.handle();
+            let (length, viewport, range) = self.lengths();
+
+            if !self.is_shown() || range <= 0 || length <= 0 {
+                handle.set_hidden(true);
+                self.update_user_interaction();
+                return;
+            }
 
-            handle.set_hidden(true);
+            // The handle is as long, compared to the scroll bar, as the
+            // viewport is compared to everything it can scroll over.
+            let handle_length = (viewport as f32 / (viewport + range) as f32 * length as f32) as i32;
+            let travel = length - handle_length;
+            let position = (self.percent() as f32 / 100.0 * travel as f32) as i32;
+            let thickness = self.thickness();
+
+            let frame = match self.direction() {
+                ScrollBarDirection::Vertical => Rectangle::new(0, position, thickness, handle_length as u32),
+                ScrollBarDirection::Horizontal => Rectangle::new(position, 0, handle_length as u32, thickness)
+            };
+
+            handle.set_frame(frame);
+            handle.set_hidden(false);
+            self.update_user_interaction();
+        }
 
-            let content_view_size = inner_content_view.frame().size;
-            let scrollview_size = scrollview.frame().size;
+        /// The offset (along this scroll bar's axis) the content moves by
+        /// when the handle is moved by the given distance.
+        fn offset_for_handle_distance(&self, distance: i32) -> i32 {
+            let (length, viewport, range) = self.lengths();
+            let handle_length = (viewport as f32 / (viewport + range) as f32 * length as f32) as i32;
+            let travel = length - handle_length;
 
-            match self.direction() {
-                ScrollBarDirection::Vertical => {
-                    let height_of_vertical_handle: u32;
-
-                    if content_view_size.height == 0 {
-                        height_of_vertical_handle = 0;
-                    } else {
-                        height_of_vertical_handle = (
-                            (scrollview_size.height as f32 / content_view_size.height as f32 ) *
-                            scrollview_size.height as f32
-                        ) as u32;
-                    }
+            if travel <= 0 {
+                return 0;
+            }
 
-                    let handle_size = Size {
-                        width: 10,
-                        height: height_of_vertical_handle
-                    };
+            (distance as f32 * range as f32 / travel as f32).round() as i32
+        }
+    }
 
-                    if scrollview_size.height < handle_size.height {
-                        handle.set_hidden(true);
-                    } else {
-                        let percent_of_scrollview = self.percent() as f32 / 100.0;
-                        let origin_y = (percent_of_scrollview * (scrollview_size.height - handle_size.height) as f32) as i32;
-                        let origin_x = handle.frame().origin.x;
+    impl Behavior {
+        fn touches_began(&self, touches: &Vec<Touch>) {
+            let touch = match touches.first() {
+                Some(touch) => touch,
+                None => return
+            };
+
+            let scroll_bar = self.view_type();
+            let position = scroll_bar.position_of(touch);
+            let handle = scroll_bar.handle().frame();
+
+            let (start, end) = match scroll_bar.direction() {
+                ScrollBarDirection::Vertical => (handle.top(), handle.bottom()),
+                ScrollBarDirection::Horizontal => (handle.left(), handle.right())
+            };
+
+            if position < start {
+                scroll_bar.scroll_view().scroll_by_page(scroll_bar.direction(), -1);
+            } else if position > end {
+                scroll_bar.scroll_view().scroll_by_page(scroll_bar.direction(), 1);
+            } else {
+                self.drag_origin.set(Some((position, scroll_bar.content_offset())));
+            }
 
-                        handle.set_frame(Rectangle {
-                            origin: Point::new(origin_x, origin_y),
-                            size: handle_size
-                        });
+            scroll_bar.flash();
+        }
 
-                        handle.set_hidden(scrollview_size.height == height_of_vertical_handle);
-                    }
-                },
-                ScrollBarDirection::Horizontal => {
-                    let width_of_horizontal_handle: u32;
-
-                    if content_view_size.width == 0 {
-                        width_of_horizontal_handle = 0;
-                    } else {
-                        width_of_horizontal_handle = (
-                            (scrollview_size.width as f32 / content_view_size.width as f32 ) *
-                            scrollview_size.width as f32
-                        ) as u32;
-                    }
+        fn touches_moved(&self, touches: &Vec<Touch>) {
+            let (touch, (origin, origin_offset)) = match (touches.first(), self.drag_origin.get()) {
+                (Some(touch), Some(drag_origin)) => (touch, drag_origin),
+                _ => return
+            };
 
+            let scroll_bar = self.view_type();
+            let distance = scroll_bar.position_of(touch) - origin;
+            let offset = origin_offset + scroll_bar.offset_for_handle_distance(distance);
 
-                    let handle_size = Size {
-                        width: width_of_horizontal_handle,
-                        height: 10
-                    };
+            scroll_bar.scroll_view().scroll_to(scroll_bar.direction(), offset);
+        }
+
+        fn touches_ended(&self, _touches: &Vec<Touch>) {
+            self.drag_origin.set(None);
+            self.view_type().flash();
+        }
 
-                    let percent_of_scrollview = self.percent() as f32 / 100.0;
-                    let origin_x = (percent_of_scrollview * (scrollview_size.width - handle_size.width) as f32) as i32;
-                    let origin_y = handle.frame().origin.y;
+        fn touches_cancelled(&self, _touches: &Vec<Touch>) {
+            self.drag_origin.set(None);
+            self.view_type().flash();
+        }
 
-                    handle.set_frame(Rectangle {
-                        origin: Point::new(origin_x, origin_y),
-                        size: handle_size
-                    });
+        fn mouse_entered(&self) {
+            self.hovered.set(true);
 
-                    handle.set_hidden(scrollview_size.width == width_of_horizontal_handle);
-                }
-            }
+            let scroll_bar = self.view_type();
+            scroll_bar.fit_to_superview();
+            scroll_bar.update_scroll_handle();
+            scroll_bar.flash();
+        }
+
+        fn mouse_exited(&self) {
+            self.hovered.set(false);
+
+            let scroll_bar = self.view_type();
+            scroll_bar.fit_to_superview();
+            scroll_bar.update_scroll_handle();
+            scroll_bar.flash();
         }
     }
 );
@@ -356,7 +1711,7 @@ mod tests {
         {
             let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
             assert_eq!(scroll_view.content_offset(), Point::new(0, 0));
-            scroll_view.set_content_offset(Point::new(10, 10));
+            scroll_view.set_content_offset(Point::new(10, 10), false);
             assert_eq!(scroll_view.content_offset(), Point::new(0, 0));
         }
 
@@ -368,7 +1723,7 @@ mod tests {
             scroll_view.set_content_view(content_view);
 
             assert_eq!(scroll_view.content_offset(), Point::new(0, 0));
-            scroll_view.set_content_offset(Point::new(10, 10));
+            scroll_view.set_content_offset(Point::new(10, 10), false);
             assert_eq!(scroll_view.content_offset(), Point::new(0, 0));
         }
 
@@ -379,8 +1734,400 @@ mod tests {
             scroll_view.set_content_view(content_view);
 
             assert_eq!(scroll_view.content_offset(), Point::new(0, 0));
-            scroll_view.set_content_offset(Point::new(10, 10));
+            scroll_view.set_content_offset(Point::new(10, 10), false);
             assert_eq!(scroll_view.content_offset(), Point::new(10, 10));
         }
     }
+
+    #[test]
+    fn test_drag_past_edge() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        scroll_view.set_content_view(View::new(Rectangle::new(0, 0, 100, 200)));
+
+        scroll_view.drag_content_by(Point::new(0, -20));
+        assert_eq!(scroll_view.content_offset(), Point::new(0, -10));
+
+        // There's nothing to scroll horizontally, so no bounce either.
+        scroll_view.drag_content_by(Point::new(-20, 30));
+        assert_eq!(scroll_view.content_offset(), Point::new(0, 10));
+
+        scroll_view.set_bounces(false);
+        scroll_view.drag_content_by(Point::new(0, 200));
+        assert_eq!(scroll_view.content_offset(), Point::new(0, 100));
+    }
+
+    #[test]
+    fn test_deceleration_rate() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        assert_eq!(scroll_view.deceleration_rate(), DECELERATION_RATE_NORMAL);
+        scroll_view.set_deceleration_rate(DECELERATION_RATE_FAST);
+        assert_eq!(scroll_view.deceleration_rate(), DECELERATION_RATE_FAST);
+    }
+
+    #[test]
+    fn test_start_decelerating() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        scroll_view.set_content_view(View::new(Rectangle::new(0, 0, 100, 200)));
+
+        assert!(!scroll_view.start_decelerating(Point::new(0.0, 0.0)));
+        assert!(!scroll_view.is_decelerating());
+
+        assert!(scroll_view.start_decelerating(Point::new(0.0, 1000.0)));
+        assert!(scroll_view.is_decelerating());
+
+        scroll_view.stop_decelerating();
+        assert!(!scroll_view.is_decelerating());
+
+        // Springs back from past the edge.
+        scroll_view.drag_content_by(Point::new(0, -20));
+        assert!(scroll_view.start_decelerating(Point::new(0.0, 0.0)));
+        assert!(scroll_view.is_decelerating());
+        scroll_view.stop_decelerating();
+    }
+
+    #[test]
+    fn test_rubber_band() {
+        assert_eq!(rubber_band(50, 10, 0, 100), 60);
+        assert_eq!(rubber_band(0, -10, 0, 100), -5);
+        assert_eq!(rubber_band(-5, -10, 0, 100), -10);
+        assert_eq!(rubber_band(-10, 20, 0, 100), 0);
+        assert_eq!(rubber_band(95, 15, 0, 100), 105);
+    }
+
+    #[test]
+    fn test_decelerate() {
+        let (offset, velocity) = decelerate(50.0, 1000.0, 0.0, 1000.0, DECELERATION_RATE_NORMAL, true, 16.0);
+        assert!(offset > 50.0);
+        assert!(velocity < 1000.0 && velocity > 900.0);
+
+        // A faster rate slows down more.
+        let (_, fast_velocity) = decelerate(50.0, 1000.0, 0.0, 1000.0, DECELERATION_RATE_FAST, true, 16.0);
+        assert!(fast_velocity < velocity);
+
+        assert_eq!(decelerate(50.0, 1.0, 0.0, 1000.0, DECELERATION_RATE_NORMAL, true, 16.0), (50.0, 0.0));
+        assert_eq!(decelerate(995.0, 1000.0, 0.0, 1000.0, DECELERATION_RATE_NORMAL, false, 16.0), (1000.0, 0.0));
+    }
+
+    #[test]
+    fn test_decelerate_springs_back() {
+        let mut offset = -50.0;
+        let mut velocity = 0.0;
+
+        for _ in 0..200 {
+            let (new_offset, new_velocity) = decelerate(offset, velocity, 0.0, 1000.0, DECELERATION_RATE_NORMAL, true, 16.0);
+            assert!(new_offset <= 0.0);
+            offset = new_offset;
+            velocity = new_velocity;
+        }
+
+        assert_eq!((offset, velocity), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_set_content_size() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        scroll_view.set_content_view(View::new(Rectangle::new(0, 0, 100, 400)));
+        scroll_view.set_content_offset(Point::new(0, 200), false);
+
+        scroll_view.set_content_size(Size::new(100, 800));
+        assert_eq!(scroll_view.content_size(), Size::new(100, 800));
+        assert_eq!(scroll_view.content_offset(), Point::new(0, 200));
+
+        scroll_view.set_content_size(Size::new(100, 250));
+        assert_eq!(scroll_view.content_offset(), Point::new(0, 150));
+    }
+
+    #[test]
+    fn test_content_inset() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        scroll_view.set_content_view(View::new(Rectangle::new(0, 0, 100, 200)));
+
+        // Stays scrolled to the start.
+        scroll_view.set_content_inset(EdgeInsets::new(20, 0, 10, 0));
+        assert_eq!(scroll_view.content_inset(), EdgeInsets::new(20, 0, 10, 0));
+        assert_eq!(scroll_view.content_offset(), Point::new(0, -20));
+
+        scroll_view.set_content_offset(Point::new(0, 500), false);
+        assert_eq!(scroll_view.content_offset(), Point::new(0, 110));
+    }
+
+    #[test]
+    fn test_animated_content_offset() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        scroll_view.set_content_view(View::new(Rectangle::new(0, 0, 100, 200)));
+
+        scroll_view.set_content_offset(Point::new(0, 50), true);
+        assert_eq!(scroll_view.content_offset(), Point::new(0, 0));
+        assert!(scroll_view.behavior().animation.borrow().is_some());
+
+        // Setting it again replaces the animation.
+        scroll_view.set_content_offset(Point::new(0, 20), false);
+        assert_eq!(scroll_view.content_offset(), Point::new(0, 20));
+        assert!(scroll_view.behavior().animation.borrow().is_none());
+    }
+
+    #[test]
+    fn test_scroll_rect_to_visible() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        scroll_view.set_content_view(View::new(Rectangle::new(0, 0, 100, 400)));
+
+        scroll_view.scroll_rect_to_visible(Rectangle::new(0, 250, 100, 50), false);
+        assert_eq!(scroll_view.content_offset(), Point::new(0, 200));
+
+        // Already visible.
+        scroll_view.scroll_rect_to_visible(Rectangle::new(0, 220, 10, 10), false);
+        assert_eq!(scroll_view.content_offset(), Point::new(0, 200));
+
+        scroll_view.scroll_rect_to_visible(Rectangle::new(0, 10, 10, 10), false);
+        assert_eq!(scroll_view.content_offset(), Point::new(0, 10));
+    }
+
+    #[test]
+    fn test_scroll_enabled() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        let delegate = ScrollViewPanDelegate { scroll_view: scroll_view.view.downgrade() };
+        let recognizer = PanRecognizer::new(|_pan_recognizer| {});
+
+        assert!(scroll_view.is_scroll_enabled());
+        assert!(delegate.should_begin(&recognizer));
+
+        scroll_view.set_scroll_enabled(false);
+        assert!(!delegate.should_begin(&recognizer));
+    }
+
+    #[test]
+    fn test_directional_lock() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        scroll_view.set_content_view(View::new(Rectangle::new(0, 0, 200, 200)));
+        scroll_view.set_directional_lock_enabled(true);
+
+        scroll_view.lock_direction(&Point::new(20, 2));
+        scroll_view.drag_content_by(Point::new(20, 30));
+        assert_eq!(scroll_view.content_offset(), Point::new(20, 0));
+
+        // Diagonal drags aren't locked.
+        scroll_view.lock_direction(&Point::new(20, 15));
+        scroll_view.drag_content_by(Point::new(10, 30));
+        assert_eq!(scroll_view.content_offset(), Point::new(30, 30));
+    }
+
+    struct ScrollCounter {
+        count: Rc<Cell<usize>>
+    }
+
+    impl ScrollViewDelegate for ScrollCounter {
+        fn did_scroll(&self, _scroll_view: &ScrollView) {
+            self.count.set(self.count.get() + 1);
+        }
+    }
+
+    #[test]
+    fn test_delegate_did_scroll() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        scroll_view.set_content_view(View::new(Rectangle::new(0, 0, 100, 200)));
+
+        let count = Rc::new(Cell::new(0));
+        scroll_view.set_delegate(ScrollCounter { count: count.clone() });
+
+        scroll_view.set_content_offset(Point::new(0, 10), false);
+        scroll_view.drag_content_by(Point::new(0, 10));
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn test_snap() {
+        let points = vec![0, 100, 200];
+        assert_eq!(snap(30, 0.0, &points), 0);
+        assert_eq!(snap(60, 0.0, &points), 100);
+        assert_eq!(snap(30, 500.0, &points), 100);
+        assert_eq!(snap(160, -500.0, &points), 100);
+        assert_eq!(snap(250, 500.0, &points), 200);
+        assert_eq!(snap(50, 0.0, &[]), 50);
+    }
+
+    #[test]
+    fn test_paging() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        scroll_view.set_content_view(View::new(Rectangle::new(0, 0, 250, 100)));
+        scroll_view.set_paging_enabled(true);
+
+        assert_eq!(scroll_view.snap_points_along(ScrollBarDirection::Horizontal), vec![0, 100, 150]);
+        assert_eq!(scroll_view.snap_points_along(ScrollBarDirection::Vertical), vec![0]);
+
+        scroll_view.set_content_offset(Point::new(40, 0), false);
+        scroll_view.end_dragging(Point::new(0.0, 0.0));
+        assert_eq!(scroll_view.behavior().animation.borrow().as_ref().map(|animation| animation.to.clone()), Some(Point::new(0, 0)));
+
+        scroll_view.set_content_offset(Point::new(40, 0), false);
+        scroll_view.end_dragging(Point::new(500.0, 0.0));
+        assert_eq!(scroll_view.behavior().animation.borrow().as_ref().map(|animation| animation.to.clone()), Some(Point::new(100, 0)));
+        assert!(!scroll_view.is_decelerating());
+    }
+
+    #[test]
+    fn test_snap_points() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        scroll_view.set_content_view(View::new(Rectangle::new(0, 0, 100, 300)));
+        scroll_view.set_snap_points(ScrollBarDirection::Vertical, vec![0, 50, 120]);
+
+        scroll_view.set_content_offset(Point::new(0, 60), false);
+        assert_eq!(scroll_view.snap_offset(&Point::new(0.0, 0.0)), Some(Point::new(0, 50)));
+
+        // Already at a snap point.
+        scroll_view.set_content_offset(Point::new(0, 120), false);
+        assert_eq!(scroll_view.snap_offset(&Point::new(0.0, 0.0)), None);
+    }
+
+    struct ZoomContent {
+        view: View
+    }
+
+    impl ScrollViewDelegate for ZoomContent {
+        fn view_for_zooming(&self, _scroll_view: &ScrollView) -> Option<View> {
+            Some(self.view.clone())
+        }
+    }
+
+    #[test]
+    fn test_zoom_scale() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        let content_view = View::new(Rectangle::new(0, 0, 100, 100));
+        scroll_view.set_content_view(content_view.clone());
+
+        // Can't zoom without a view for zooming, or room to zoom.
+        scroll_view.set_zoom_scale(2.0, false);
+        assert_eq!(scroll_view.zoom_scale(), 1.0);
+        assert!(!scroll_view.is_zoom_enabled());
+
+        scroll_view.set_delegate(ZoomContent { view: content_view.clone() });
+        scroll_view.set_maximum_zoom_scale(4.0);
+        assert!(scroll_view.is_zoom_enabled());
+
+        scroll_view.set_zoom_scale(2.0, false);
+        assert_eq!(scroll_view.zoom_scale(), 2.0);
+        assert_eq!(scroll_view.content_size(), Size::new(200, 200));
+        assert_eq!(content_view.transform().apply_to_rect(&content_view.frame()), Rectangle::new(0, 0, 200, 200));
+
+        // Zoomed around the middle.
+        assert_eq!(scroll_view.content_offset(), Point::new(50, 50));
+
+        scroll_view.set_zoom_scale(10.0, false);
+        assert_eq!(scroll_view.zoom_scale(), 4.0);
+
+        scroll_view.set_zoom_scale(0.5, false);
+        assert_eq!(scroll_view.zoom_scale(), 1.0);
+        assert_eq!(scroll_view.content_offset(), Point::new(0, 0));
+    }
+
+    #[test]
+    fn test_zoom_around() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        let content_view = View::new(Rectangle::new(0, 0, 200, 200));
+        scroll_view.set_content_view(content_view.clone());
+        scroll_view.set_delegate(ZoomContent { view: content_view });
+        scroll_view.set_maximum_zoom_scale(4.0);
+        scroll_view.set_content_offset(Point::new(20, 20), false);
+
+        // The content under the point stays under it.
+        let location = Point::new(30, 40);
+        let anchor = scroll_view.unzoomed_point_at(&location);
+        assert_eq!(anchor, (50.0, 60.0));
+
+        scroll_view.zoom_around(2.0, anchor, &location);
+        assert_eq!(scroll_view.content_offset(), Point::new(70, 80));
+        assert_eq!(scroll_view.unzoomed_point_at(&location), anchor);
+    }
+
+    #[test]
+    fn test_scroll_bar_style() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        assert_eq!(scroll_view.scroll_bar_style(ScrollBarDirection::Vertical), ScrollBarStyle::default());
+
+        let style = ScrollBarStyle {
+            color: Color::red(),
+            thickness: 6,
+            hover_thickness: 8,
+            insets: EdgeInsets::new(2, 0, 2, 3)
+        };
+        scroll_view.set_scroll_bar_style(ScrollBarDirection::Vertical, style.clone());
+
+        assert_eq!(scroll_view.scroll_bar_style(ScrollBarDirection::Vertical), style);
+        assert_eq!(
+            scroll_view.vertical_scroll_bar().frame(),
+            Rectangle::new(91, 2, 6, 96)
+        );
+    }
+
+    #[test]
+    fn test_scroll_bar_handle() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        scroll_view.set_content_view(View::new(Rectangle::new(0, 0, 100, 200)));
+
+        let handle = scroll_view.vertical_scroll_bar().handle();
+        assert!(!handle.is_hidden());
+        assert_eq!(handle.frame(), Rectangle::new(0, 0, 10, 50));
+
+        // Nothing to scroll horizontally.
+        assert!(scroll_view.horizontal_scroll_bar().handle().is_hidden());
+
+        scroll_view.set_content_offset(Point::new(0, 100), false);
+        assert_eq!(handle.frame(), Rectangle::new(0, 50, 10, 50));
+
+        scroll_view.set_shows_scroll_bar(ScrollBarDirection::Vertical, false);
+        assert!(!scroll_view.shows_scroll_bar(ScrollBarDirection::Vertical));
+        assert!(handle.is_hidden());
+    }
+
+    #[test]
+    fn test_drag_scroll_bar() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        scroll_view.set_content_view(View::new(Rectangle::new(0, 0, 100, 200)));
+
+        // The handle moves over half the scroll bar, for all 100 points of
+        // scrolling.
+        let scroll_bar = scroll_view.vertical_scroll_bar();
+        assert_eq!(scroll_bar.offset_for_handle_distance(10), 20);
+
+        scroll_view.scroll_to(ScrollBarDirection::Vertical, 20);
+        assert_eq!(scroll_view.content_offset(), Point::new(0, 20));
+
+        // The content can't be panned while the scroll bar is dragged.
+        let delegate = ScrollViewPanDelegate { scroll_view: scroll_view.view.downgrade() };
+        let recognizer = PanRecognizer::new(|_pan_recognizer| {});
+        scroll_bar.behavior().drag_origin.set(Some((0, 0)));
+        assert!(!delegate.should_begin(&recognizer));
+    }
+
+    #[test]
+    fn test_scroll_bars_fade() {
+        let scroll_view = ScrollView::new(Rectangle::new(0, 0, 100, 100));
+        scroll_view.set_content_view(View::new(Rectangle::new(0, 0, 100, 200)));
+        let scroll_bar = scroll_view.vertical_scroll_bar();
+
+        assert!(scroll_view.auto_hides_scroll_bars());
+        assert_eq!(scroll_bar.opacity(), 0.0);
+
+        scroll_view.flash_scroll_bars();
+        assert_eq!(scroll_bar.opacity(), 1.0);
+        let hide_timer = scroll_bar.behavior().hide_timer.borrow().clone().expect("scroll bar isn't waiting to hide");
+        let hides_at = hide_timer.fire_at();
+
+        // Scrolling more puts off hiding, rather than replacing the timer.
+        std::thread::sleep(Duration::from_millis(1));
+        scroll_view.set_content_offset(Point::new(0, 10), false);
+        assert!(hide_timer.is_valid());
+        assert!(hide_timer.fire_at() > hides_at);
+
+        scroll_bar.start_fading();
+        let fade = scroll_bar.behavior().fade.borrow().clone().expect("scroll bar isn't fading");
+        fade.update(Instant::now() + Duration::from_millis(SCROLL_BAR_FADE_DURATION_MS / 2));
+        assert!(scroll_bar.opacity() < 1.0);
+
+        // Stays once auto hiding is turned off.
+        scroll_view.set_auto_hides_scroll_bars(false);
+        assert_eq!(scroll_bar.opacity(), 1.0);
+        assert!(scroll_bar.behavior().fade.borrow().is_none());
+        assert!(!fade.is_running());
+        assert!(scroll_bar.behavior().hide_timer.borrow().is_none());
+    }
 }
@@ -0,0 +1,420 @@
+use crate::graphics::{EdgeInsets, Rectangle, Size};
+use crate::ui::Color;
+use crate::ui::View;
+use crate::ui::view::DefaultBehavior;
+use crate::ui::layout::Axis;
+use std::cell::{Cell, RefCell};
+use crate::macros::*;
+
+/// Where a `StackView` puts its arranged subviews across its axis. E.g. for
+/// a horizontal stack, `Leading` lines them up along the top.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StackAlignment {
+    /// Stretched to the full width (or height) of the stack.
+    Fill,
+    Leading,
+    Center,
+    Trailing
+}
+
+/// How a `StackView` sizes its arranged subviews along its axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StackDistribution {
+    /// Each keeps its size, apart from one that's stretched (or squeezed) to
+    /// fill the stack: the first without an intrinsic content size, e.g. a
+    /// plain `View` as a spacer, or otherwise the last.
+    Fill,
+
+    /// All the same size.
+    FillEqually,
+
+    /// Stretched (or squeezed) in proportion to their sizes.
+    FillProportionally
+}
+
+/// An arranged subview, with the size it had when it was added. Views
+/// without an intrinsic content size are stacked at that size, rather than
+/// their frame, which the stack view changes.
+#[derive(Clone, Debug)]
+pub(crate) struct ArrangedSubview {
+    view: View,
+    size: Size<u32>
+}
+
+custom_view!(
+    StackView subclasses DefaultBehavior
+
+    struct StackViewBehavior {
+        axis: Cell<Axis>,
+        spacing: Cell<u32>,
+        alignment: Cell<StackAlignment>,
+        distribution: Cell<StackDistribution>,
+        padding: Cell<EdgeInsets>,
+        arranged_subviews: RefCell<Vec<ArrangedSubview>>,
+
+        // As of the last time the stack was arranged.
+        last_intrinsic_content_size: RefCell<Option<Size<u32>>>
+    }
+
+    impl Self {
+        /// A stack view lining up its arranged subviews one after another
+        /// along the axis; left to right, or top to bottom.
+        ///
+        /// Each arranged subview is sized by its intrinsic content size, or
+        /// if it doesn't have one, by its frame when it was added. Hidden
+        /// views are skipped.
+        pub fn new(frame: Rectangle<i32, u32>, axis: Axis) -> StackView {
+            let stack_view = StackView::new_all(
+                frame,
+                Cell::new(axis),
+                Cell::new(0),
+                Cell::new(StackAlignment::Fill),
+                Cell::new(StackDistribution::Fill),
+                Cell::new(EdgeInsets::zero()),
+                RefCell::new(Vec::new()),
+                RefCell::new(None)
+            );
+            stack_view.set_background_color(Color::clear());
+            stack_view
+        }
+
+        /// Add a view to the end of the stack, adding it as a subview if it
+        /// isn't already.
+        pub fn add_arranged_subview(&self, view: impl Into<View>) {
+            let count = self.behavior().arranged_subviews.borrow().len();
+            self.insert_arranged_subview(view, count);
+        }
+
+        pub fn insert_arranged_subview(&self, view: impl Into<View>, index: usize) {
+            let view: View = view.into();
+
+            {
+                let behavior = self.behavior();
+                let mut arranged_subviews = behavior.arranged_subviews.borrow_mut();
+                arranged_subviews.retain(|arranged| arranged.view != view);
+                let index = index.min(arranged_subviews.len());
+                let size = view.frame().size;
+                arranged_subviews.insert(index, ArrangedSubview { view: view.clone(), size });
+            }
+
+            if !self.subviews().contains(&view) {
+                self.add_subview(view);
+            }
+
+            self.set_needs_layout();
+        }
+
+        /// Remove a view from the stack, and from the stack view.
+        pub fn remove_arranged_subview(&self, view: &View) {
+            self.behavior().arranged_subviews.borrow_mut().retain(|arranged| arranged.view != *view);
+
+            if self.subviews().contains(view) {
+                view.remove_from_superview();
+            }
+
+            self.set_needs_layout();
+        }
+
+        pub fn arranged_subviews(&self) -> Vec<View> {
+            let behavior = self.behavior();
+            let arranged_subviews = behavior.arranged_subviews.borrow();
+            arranged_subviews.iter().map(|arranged| arranged.view.clone()).collect()
+        }
+
+        pub fn axis(&self) -> Axis {
+            self.behavior().axis.get()
+        }
+
+        pub fn set_axis(&self, axis: Axis) {
+            self.behavior().axis.set(axis);
+            self.set_needs_layout();
+        }
+
+        /// The space between arranged subviews.
+        pub fn spacing(&self) -> u32 {
+            self.behavior().spacing.get()
+        }
+
+        pub fn set_spacing(&self, spacing: u32) {
+            self.behavior().spacing.set(spacing);
+            self.set_needs_layout();
+        }
+
+        pub fn alignment(&self) -> StackAlignment {
+            self.behavior().alignment.get()
+        }
+
+        pub fn set_alignment(&self, alignment: StackAlignment) {
+            self.behavior().alignment.set(alignment);
+            self.set_needs_layout();
+        }
+
+        pub fn distribution(&self) -> StackDistribution {
+            self.behavior().distribution.get()
+        }
+
+        pub fn set_distribution(&self, distribution: StackDistribution) {
+            self.behavior().distribution.set(distribution);
+            self.set_needs_layout();
+        }
+
+        /// The space between the edges of the stack view and the arranged
+        /// subviews.
+        pub fn padding(&self) -> EdgeInsets {
+            self.behavior().padding.get()
+        }
+
+        pub fn set_padding(&self, padding: EdgeInsets) {
+            self.behavior().padding.set(padding);
+            self.set_needs_layout();
+        }
+
+        /// The arranged subviews that are laid out; those still in the stack
+        /// view and not hidden.
+        fn visible_arranged_subviews(&self) -> Vec<ArrangedSubview> {
+            let subviews = self.subviews();
+            let behavior = self.behavior();
+            let arranged_subviews = behavior.arranged_subviews.borrow();
+            arranged_subviews.iter()
+                .filter(|arranged| !arranged.view.is_hidden() && subviews.contains(&arranged.view))
+                .cloned()
+                .collect()
+        }
+
+        /// The size of each visible arranged subview, along the axis and
+        /// across it.
+        fn natural_sizes(&self, arranged_subviews: &[ArrangedSubview]) -> Vec<(i32, i32)> {
+            let axis = self.axis();
+
+            arranged_subviews.iter().map(|arranged| {
+                let size = arranged.view.intrinsic_content_size().unwrap_or_else(|| arranged.size.clone());
+                let (along, across) = match axis {
+                    Axis::Horizontal => (size.width, size.height),
+                    Axis::Vertical => (size.height, size.width)
+                };
+                (along as i32, across as i32)
+            }).collect()
+        }
+
+        /// Position the arranged subviews within the stack view's current
+        /// size.
+        fn arrange(&self) {
+            let views = self.visible_arranged_subviews();
+            let sizes = self.natural_sizes(&views);
+            let axis = self.axis();
+            let padding = self.padding();
+            let size = self.frame().size;
+
+            let (length, breadth, start, cross_start) = match axis {
+                Axis::Horizontal => (size.width as i32 - padding.horizontal(), size.height as i32 - padding.vertical(), padding.left, padding.top),
+                Axis::Vertical => (size.height as i32 - padding.vertical(), size.width as i32 - padding.horizontal(), padding.top, padding.left)
+            };
+
+            let natural_lengths: Vec<i32> = sizes.iter().map(|(along, _)| *along).collect();
+            let flexible = views.iter().position(|arranged| arranged.view.intrinsic_content_size().is_none());
+            let spacing = self.spacing() as i32 * (views.len() as i32 - 1).max(0);
+            let lengths = distribute(&natural_lengths, (length - spacing).max(0), self.distribution(), flexible);
+
+            let mut position = start;
+
+            for ((arranged, (_, natural_breadth)), view_length) in views.iter().zip(sizes).zip(lengths) {
+                let view = &arranged.view;
+                let (offset, view_breadth) = match self.alignment() {
+                    StackAlignment::Fill => (0, breadth),
+                    StackAlignment::Leading => (0, natural_breadth),
+                    StackAlignment::Center => ((breadth - natural_breadth) / 2, natural_breadth),
+                    StackAlignment::Trailing => (breadth - natural_breadth, natural_breadth)
+                };
+
+                let view_breadth = view_breadth.max(0) as u32;
+                let frame = match axis {
+                    Axis::Horizontal => Rectangle::new(position, cross_start + offset, view_length as u32, view_breadth),
+                    Axis::Vertical => Rectangle::new(cross_start + offset, position, view_breadth, view_length as u32)
+                };
+
+                if view.frame() != frame {
+                    view.set_frame(frame);
+                }
+
+                position += view_length + self.spacing() as i32;
+            }
+
+            // Let the superview know if the stack's own intrinsic size has
+            // changed, e.g. for nested stacks.
+            let intrinsic_content_size = self.intrinsic_content_size();
+            let previous = self.behavior().last_intrinsic_content_size.replace(intrinsic_content_size.clone());
+
+            if previous != intrinsic_content_size {
+                self.invalidate_intrinsic_content_size();
+            }
+        }
+    }
+
+    impl Behavior {
+        /// Just big enough for the arranged subviews, the spacing and the
+        /// padding.
+        fn intrinsic_content_size(&self) -> Option<Size<u32>> {
+            let stack_view = self.view_type();
+            let views = stack_view.visible_arranged_subviews();
+            let sizes = stack_view.natural_sizes(&views);
+            let padding = self.padding.get();
+
+            let spacing = self.spacing.get() as i32 * (views.len() as i32 - 1).max(0);
+            let length = sizes.iter().map(|(along, _)| along).sum::<i32>() + spacing;
+            let breadth = sizes.iter().map(|(_, across)| *across).max().unwrap_or(0);
+
+            let (width, height) = match self.axis.get() {
+                Axis::Horizontal => (length + padding.horizontal(), breadth + padding.vertical()),
+                Axis::Vertical => (breadth + padding.horizontal(), length + padding.vertical())
+            };
+
+            Some(Size::new(width.max(0) as u32, height.max(0) as u32))
+        }
+
+        fn layout_subviews(&self) {
+            self.view_type().arrange();
+        }
+    }
+);
+
+/// Sizes along the axis for views with the given natural sizes, to fill
+/// `available`. `flexible` is the view to stretch for `Fill`.
+fn distribute(natural: &[i32], available: i32, distribution: StackDistribution, flexible: Option<usize>) -> Vec<i32> {
+    let count = natural.len() as i32;
+
+    if count == 0 {
+        return Vec::new();
+    }
+
+    match distribution {
+        StackDistribution::Fill => {
+            let mut lengths = natural.to_vec();
+            let flexible = flexible.unwrap_or(natural.len() - 1);
+            let others: i32 = natural.iter().enumerate()
+                .filter(|(index, _)| *index != flexible)
+                .map(|(_, length)| length)
+                .sum();
+            lengths[flexible] = (available - others).max(0);
+            lengths
+        },
+        StackDistribution::FillEqually => {
+            // Spread the remainder over the first few.
+            let remainder = available % count;
+            (0..count).map(|index| available / count + if index < remainder { 1 } else { 0 }).collect()
+        },
+        StackDistribution::FillProportionally => {
+            let total: i32 = natural.iter().sum();
+
+            if total <= 0 {
+                return distribute(natural, available, StackDistribution::FillEqually, flexible);
+            }
+
+            // Round the edges, so the lengths add up exactly.
+            let mut edge = 0;
+            let mut previous = 0;
+
+            natural.iter().map(|length| {
+                edge += length;
+                let rounded = (edge as f32 * available as f32 / total as f32).round() as i32;
+                let length = rounded - previous;
+                previous = rounded;
+                length
+            }).collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stack_view(axis: Axis) -> (StackView, View, View, View) {
+        let stack_view = StackView::new(Rectangle::new(0, 0, 200, 100), axis);
+        let first = View::new(Rectangle::new(0, 0, 20, 10));
+        let second = View::new(Rectangle::new(0, 0, 40, 20));
+        let third = View::new(Rectangle::new(0, 0, 60, 30));
+        stack_view.add_arranged_subview(first.clone());
+        stack_view.add_arranged_subview(second.clone());
+        stack_view.add_arranged_subview(third.clone());
+        (stack_view, first, second, third)
+    }
+
+    #[test]
+    fn test_distribute() {
+        assert_eq!(distribute(&[10, 20, 30], 100, StackDistribution::Fill, None), vec![10, 20, 70]);
+        assert_eq!(distribute(&[10, 20, 30], 100, StackDistribution::Fill, Some(0)), vec![50, 20, 30]);
+        assert_eq!(distribute(&[10, 20, 30], 40, StackDistribution::Fill, None), vec![10, 20, 10]);
+        assert_eq!(distribute(&[10, 20, 30], 100, StackDistribution::FillEqually, None), vec![34, 33, 33]);
+        assert_eq!(distribute(&[10, 20, 30], 120, StackDistribution::FillProportionally, None), vec![20, 40, 60]);
+        assert_eq!(distribute(&[], 100, StackDistribution::Fill, None), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn test_horizontal_fill() {
+        let (stack_view, first, second, third) = stack_view(Axis::Horizontal);
+        stack_view.set_spacing(10);
+        stack_view.layout_if_needed();
+
+        // Plain views have no intrinsic size, so the first is stretched.
+        assert_eq!(first.frame(), Rectangle::new(0, 0, 80, 100));
+        assert_eq!(second.frame(), Rectangle::new(90, 0, 40, 100));
+        assert_eq!(third.frame(), Rectangle::new(140, 0, 60, 100));
+    }
+
+    #[test]
+    fn test_vertical_alignment_and_padding() {
+        let (stack_view, first, second, third) = stack_view(Axis::Vertical);
+        stack_view.set_padding(EdgeInsets::new(5, 10, 5, 10));
+        stack_view.set_distribution(StackDistribution::FillEqually);
+        stack_view.set_alignment(StackAlignment::Center);
+        stack_view.layout_if_needed();
+
+        assert_eq!(first.frame(), Rectangle::new(90, 5, 20, 30));
+        assert_eq!(second.frame(), Rectangle::new(80, 35, 40, 30));
+        assert_eq!(third.frame(), Rectangle::new(70, 65, 60, 30));
+
+        stack_view.set_alignment(StackAlignment::Trailing);
+        stack_view.layout_if_needed();
+        assert_eq!(first.frame(), Rectangle::new(170, 5, 20, 30));
+    }
+
+    #[test]
+    fn test_hidden_and_removed_views() {
+        let (stack_view, first, second, third) = stack_view(Axis::Horizontal);
+        stack_view.set_distribution(StackDistribution::FillProportionally);
+
+        second.set_hidden(true);
+        stack_view.layout_if_needed();
+        assert_eq!(first.frame(), Rectangle::new(0, 0, 50, 100));
+        assert_eq!(third.frame(), Rectangle::new(50, 0, 150, 100));
+
+        stack_view.remove_arranged_subview(&first);
+        stack_view.layout_if_needed();
+        assert_eq!(stack_view.arranged_subviews(), vec![second.clone(), third.clone()]);
+        assert!(!stack_view.subviews().contains(&first));
+        assert_eq!(third.frame(), Rectangle::new(0, 0, 200, 100));
+    }
+
+    #[test]
+    fn test_intrinsic_content_size() {
+        let (stack_view, _, _, _) = stack_view(Axis::Vertical);
+        stack_view.set_spacing(5);
+        stack_view.set_padding(EdgeInsets::uniform(2));
+
+        assert_eq!(stack_view.intrinsic_content_size(), Some(Size::new(64, 74)));
+        assert_eq!(stack_view.size_that_fits(&Size::new(0, 0)), Size::new(64, 74));
+    }
+
+    #[test]
+    fn test_relayout_when_resized() {
+        let (stack_view, _, _, third) = stack_view(Axis::Horizontal);
+        stack_view.layout_if_needed();
+        assert!(!stack_view.needs_layout());
+
+        stack_view.set_frame(Rectangle::new(0, 0, 300, 50));
+        assert!(stack_view.needs_layout());
+
+        stack_view.layout_if_needed();
+        assert_eq!(third.frame(), Rectangle::new(240, 0, 60, 50));
+    }
+}
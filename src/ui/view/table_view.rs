@@ -0,0 +1,977 @@
+use crate::graphics::{Point, Rectangle, Size};
+use crate::ui::view::{DefaultBehavior, ScrollView, View, WeakView};
+use crate::ui::view::scroll_view::ScrollViewDelegate;
+use crate::ui::Color;
+use crate::ui::gesture::tap_recognizer::TapRecognizer;
+use crate::ui::key::KeyCode;
+use crate::ui::press::Press;
+use crate::macros::*;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::rc::Rc;
+
+/// The default `TableView::row_height`.
+const DEFAULT_ROW_HEIGHT: u32 = 44;
+
+/// The position of a row in a `TableView`: its section, and the row within
+/// that section. Also the position of an item in a `CollectionView`, where
+/// `row` is the item within the section.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IndexPath {
+    pub section: usize,
+    pub row: usize
+}
+
+impl IndexPath {
+    pub fn new(section: usize, row: usize) -> IndexPath {
+        IndexPath {
+            section,
+            row
+        }
+    }
+}
+
+/// Provides the rows of a `TableView`. See `TableView::set_data_source`.
+pub trait TableViewDataSource {
+    fn number_of_sections(&self, _table_view: &TableView) -> usize {
+        1
+    }
+
+    fn number_of_rows(&self, table_view: &TableView, section: usize) -> usize;
+
+    /// The cell to show for a row that has scrolled into view. Rather than
+    /// creating a new cell each time, reuse one that has scrolled out of view
+    /// with `TableView::dequeue_reusable_cell`.
+    fn cell_for_row(&self, table_view: &TableView, index_path: IndexPath) -> TableViewCell;
+
+    /// Defaults to `TableView::row_height`.
+    fn height_for_row(&self, table_view: &TableView, _index_path: IndexPath) -> u32 {
+        table_view.row_height()
+    }
+
+    /// A view shown above the rows of the section, sized to the header
+    /// height.
+    fn view_for_header(&self, _table_view: &TableView, _section: usize) -> Option<View> {
+        None
+    }
+
+    /// Defaults to `TableView::section_header_height`.
+    fn height_for_header(&self, table_view: &TableView, _section: usize) -> u32 {
+        table_view.section_header_height()
+    }
+}
+
+/// Receives notifications about the user selecting rows in a `TableView`.
+/// See `TableView::set_delegate`.
+pub trait TableViewDelegate {
+    fn did_select_row(&self, _table_view: &TableView, _index_path: IndexPath) {}
+    fn did_deselect_row(&self, _table_view: &TableView, _index_path: IndexPath) {}
+}
+
+/// Where a section and its rows are in the table, in the coordinates of the
+/// scroll view's content.
+pub(crate) struct SectionLayout {
+    top: i32,
+    header_height: u32,
+
+    /// The top of each row, followed by the bottom of the last.
+    row_tops: Vec<i32>
+}
+
+impl SectionLayout {
+    fn bottom(&self) -> i32 {
+        *self.row_tops.last().expect("section layout missing its bottom")
+    }
+
+    fn number_of_rows(&self) -> usize {
+        self.row_tops.len() - 1
+    }
+}
+
+/// Rows (or sections' headers) with where they are.
+type Frames<T> = Vec<(T, Rectangle<i32, u32>)>;
+
+/// Shows the rows that have scrolled into view.
+struct TableViewScrollDelegate {
+    table_view: WeakView
+}
+
+impl ScrollViewDelegate for TableViewScrollDelegate {
+    fn did_scroll(&self, _scroll_view: &ScrollView) {
+        if let Some(view) = self.table_view.upgrade() {
+            TableView::from_view(view).layout_visible_rows();
+        }
+    }
+}
+
+custom_view!(
+    TableView subclasses DefaultBehavior
+
+    struct TableViewBehavior {
+        data_source: RefCell<Option<Rc<dyn TableViewDataSource>>>,
+        delegate: RefCell<Option<Rc<dyn TableViewDelegate>>>,
+        row_height: Cell<u32>,
+        section_header_height: Cell<u32>,
+        allows_multiple_selection: Cell<bool>,
+        sections: RefCell<Vec<SectionLayout>>,
+        visible_cells: RefCell<BTreeMap<IndexPath, TableViewCell>>,
+        visible_headers: RefCell<BTreeMap<usize, View>>,
+        reuse_queue: RefCell<HashMap<String, Vec<TableViewCell>>>,
+        selected_rows: RefCell<BTreeSet<IndexPath>>
+    }
+
+    impl Self {
+        /// A table of rows stacked vertically, of which only those scrolled
+        /// into view have cells. Give it rows with `set_data_source`.
+        pub fn new(frame: Rectangle<i32, u32>) -> Self {
+            let scroll_view = ScrollView::new(Rectangle::new(0, 0, frame.size.width, frame.size.height));
+
+            let content_view = View::new(Rectangle::new(0, 0, frame.size.width, 0));
+            content_view.set_background_color(Color::clear());
+            scroll_view.set_content_view(content_view);
+
+            let table_view = Self::new_all(
+                frame,
+                RefCell::new(None),
+                RefCell::new(None),
+                Cell::new(DEFAULT_ROW_HEIGHT),
+                Cell::new(0),
+                Cell::new(false),
+                RefCell::new(Vec::new()),
+                RefCell::new(BTreeMap::new()),
+                RefCell::new(BTreeMap::new()),
+                RefCell::new(HashMap::new()),
+                RefCell::new(BTreeSet::new())
+            );
+
+            scroll_view.set_delegate(TableViewScrollDelegate {
+                table_view: table_view.view.downgrade()
+            });
+            table_view.add_subview(scroll_view);
+
+            let tap_gesture = TapRecognizer::new(|gesture_recognizer| {
+                if let Some(view) = gesture_recognizer.view().upgrade() {
+                    TableView::from_view(view).did_tap(gesture_recognizer);
+                }
+            });
+            table_view.add_gesture_recognizer(Box::new(tap_gesture));
+
+            table_view
+        }
+
+        /// Set the data source providing the rows, and load them.
+        pub fn set_data_source(&self, data_source: impl TableViewDataSource + 'static) {
+            {
+                let behavior = self.behavior();
+                behavior.data_source.replace(Some(Rc::new(data_source)));
+            }
+
+            self.reload_data();
+        }
+
+        fn data_source(&self) -> Option<Rc<dyn TableViewDataSource>> {
+            let behavior = self.behavior();
+            let data_source = behavior.data_source.borrow().clone();
+            data_source
+        }
+
+        /// Set the delegate to be notified when the user selects rows.
+        pub fn set_delegate(&self, delegate: impl TableViewDelegate + 'static) {
+            let behavior = self.behavior();
+            behavior.delegate.replace(Some(Rc::new(delegate)));
+        }
+
+        fn delegate(&self) -> Option<Rc<dyn TableViewDelegate>> {
+            let behavior = self.behavior();
+            let delegate = behavior.delegate.borrow().clone();
+            delegate
+        }
+
+        /// The scroll view the rows are in. The table view is its delegate,
+        /// so it isn't handed out; see `set_content_offset`.
+        pub(crate) fn scroll_view(&self) -> ScrollView {
+            let view = self.subviews().first().expect("table view missing scroll view").clone();
+            ScrollView::from_view(view)
+        }
+
+        /// How far the rows are scrolled.
+        pub fn content_offset(&self) -> Point<i32> {
+            self.scroll_view().content_offset()
+        }
+
+        /// Scrolls the rows to the given offset; see
+        /// `ScrollView::set_content_offset`.
+        pub fn set_content_offset(&self, offset: Point<i32>, animated: bool) {
+            self.scroll_view().set_content_offset(offset, animated);
+        }
+
+        fn content_view(&self) -> View {
+            self.scroll_view().content_view().expect("table view missing content view")
+        }
+
+        /// The height of rows whose data source doesn't implement
+        /// `height_for_row`. 44 by default.
+        pub fn row_height(&self) -> u32 {
+            self.behavior().row_height.get()
+        }
+
+        pub fn set_row_height(&self, height: u32) {
+            self.behavior().row_height.set(height);
+            self.reload_data();
+        }
+
+        /// The height of section headers whose data source doesn't implement
+        /// `height_for_header`. 0 (no headers) by default.
+        pub fn section_header_height(&self) -> u32 {
+            self.behavior().section_header_height.get()
+        }
+
+        pub fn set_section_header_height(&self, height: u32) {
+            self.behavior().section_header_height.set(height);
+            self.reload_data();
+        }
+
+        /// Asks the data source for the rows again, e.g. after they've
+        /// changed. Rows that no longer exist are deselected.
+        pub fn reload_data(&self) {
+            // The visible cells and headers may now be for different rows.
+            let cells: Vec<TableViewCell> = {
+                let behavior = self.behavior();
+                let mut visible_cells = behavior.visible_cells.borrow_mut();
+                std::mem::take(&mut *visible_cells).into_values().collect()
+            };
+
+            for cell in cells {
+                self.enqueue_cell(cell);
+            }
+
+            let headers: Vec<View> = {
+                let behavior = self.behavior();
+                let mut visible_headers = behavior.visible_headers.borrow_mut();
+                std::mem::take(&mut *visible_headers).into_values().collect()
+            };
+
+            for header in headers {
+                header.remove_from_superview();
+            }
+
+            let sections = self.layout_sections();
+            let height = sections.last().map(|section| section.bottom()).unwrap_or(0);
+
+            {
+                let behavior = self.behavior();
+                behavior.sections.replace(sections);
+            }
+
+            let selected_rows = self.behavior().selected_rows.take();
+            let selected_rows = selected_rows.into_iter().filter(|index_path| self.contains_row(index_path)).collect();
+            self.behavior().selected_rows.replace(selected_rows);
+
+            self.scroll_view().set_content_size(Size::new(self.frame().size.width, height as u32));
+            self.layout_visible_rows();
+        }
+
+        fn layout_sections(&self) -> Vec<SectionLayout> {
+            let data_source = match self.data_source() {
+                Some(data_source) => data_source,
+                None => return Vec::new()
+            };
+
+            let mut sections = Vec::new();
+            let mut top = 0;
+
+            for section in 0..data_source.number_of_sections(self) {
+                let header_height = data_source.height_for_header(self, section);
+                let mut row_tops = vec![top + header_height as i32];
+
+                for row in 0..data_source.number_of_rows(self, section) {
+                    let row_top = *row_tops.last().expect("row tops is never empty");
+                    row_tops.push(row_top + data_source.height_for_row(self, IndexPath::new(section, row)) as i32);
+                }
+
+                let layout = SectionLayout {
+                    top,
+                    header_height,
+                    row_tops
+                };
+
+                top = layout.bottom();
+                sections.push(layout);
+            }
+
+            sections
+        }
+
+        pub fn number_of_sections(&self) -> usize {
+            self.behavior().sections.borrow().len()
+        }
+
+        pub fn number_of_rows(&self, section: usize) -> usize {
+            let behavior = self.behavior();
+            let sections = behavior.sections.borrow();
+            sections.get(section).map(|section| section.number_of_rows()).unwrap_or(0)
+        }
+
+        fn contains_row(&self, index_path: &IndexPath) -> bool {
+            index_path.row < self.number_of_rows(index_path.section)
+        }
+
+        /// Where the row is, in the coordinates of the scroll view's content.
+        pub fn rect_for_row(&self, index_path: IndexPath) -> Option<Rectangle<i32, u32>> {
+            let width = self.frame().size.width;
+            let behavior = self.behavior();
+            let sections = behavior.sections.borrow();
+            let section = sections.get(index_path.section)?;
+
+            if index_path.row >= section.number_of_rows() {
+                return None;
+            }
+
+            let top = section.row_tops[index_path.row];
+            let bottom = section.row_tops[index_path.row + 1];
+
+            Some(Rectangle::new(0, top, width, (bottom - top) as u32))
+        }
+
+        /// Where the section's header is, in the coordinates of the scroll
+        /// view's content.
+        pub fn rect_for_header(&self, section: usize) -> Option<Rectangle<i32, u32>> {
+            let width = self.frame().size.width;
+            let behavior = self.behavior();
+            let sections = behavior.sections.borrow();
+            let section = sections.get(section)?;
+
+            Some(Rectangle::new(0, section.top, width, section.header_height))
+        }
+
+        /// The row at the given point, in the coordinates of the scroll view's
+        /// content. `None` for headers, and below the last row.
+        pub fn index_path_for_row_at(&self, point: &Point<i32>) -> Option<IndexPath> {
+            let behavior = self.behavior();
+            let sections = behavior.sections.borrow();
+
+            let section = sections.iter().position(|section| point.y >= section.top && point.y < section.bottom())?;
+            let rows_above = sections[section].row_tops.partition_point(|top| *top <= point.y);
+
+            // Above the first row is the header.
+            if rows_above == 0 {
+                return None;
+            }
+
+            Some(IndexPath::new(section, rows_above - 1))
+        }
+
+        /// The rows, and the headers, at least partly between `top` and
+        /// `bottom`, with where they are.
+        fn rows_between(&self, top: i32, bottom: i32) -> (Frames<IndexPath>, Frames<usize>) {
+            let width = self.frame().size.width;
+            let behavior = self.behavior();
+            let sections = behavior.sections.borrow();
+
+            let mut rows = Vec::new();
+            let mut headers = Vec::new();
+
+            for (index, section) in sections.iter().enumerate() {
+                if section.top >= bottom {
+                    break;
+                }
+
+                if section.bottom() <= top {
+                    continue;
+                }
+
+                if section.header_height > 0 && section.top + section.header_height as i32 > top {
+                    headers.push((index, Rectangle::new(0, section.top, width, section.header_height)));
+                }
+
+                let first = section.row_tops.partition_point(|row_top| *row_top <= top).saturating_sub(1);
+
+                for row in first..section.number_of_rows() {
+                    let row_top = section.row_tops[row];
+                    let row_bottom = section.row_tops[row + 1];
+
+                    if row_top >= bottom {
+                        break;
+                    }
+
+                    if row_bottom > top {
+                        rows.push((IndexPath::new(index, row), Rectangle::new(0, row_top, width, (row_bottom - row_top) as u32)));
+                    }
+                }
+            }
+
+            (rows, headers)
+        }
+
+        /// Gives the rows (and headers) that have scrolled into view cells,
+        /// reusing those of rows that have scrolled out of view.
+        fn layout_visible_rows(&self) {
+            let data_source = match self.data_source() {
+                Some(data_source) => data_source,
+                None => return
+            };
+
+            let scroll_view = self.scroll_view();
+            let top = scroll_view.content_offset().y;
+            let bottom = top + scroll_view.frame().size.height as i32;
+            let (rows, headers) = self.rows_between(top, bottom);
+
+            let hidden_cells: Vec<TableViewCell> = {
+                let behavior = self.behavior();
+                let mut visible_cells = behavior.visible_cells.borrow_mut();
+                let hidden: Vec<IndexPath> = visible_cells.keys()
+                    .filter(|index_path| !rows.iter().any(|(row, _)| row == *index_path))
+                    .cloned()
+                    .collect();
+
+                hidden.iter().filter_map(|index_path| visible_cells.remove(index_path)).collect()
+            };
+
+            for cell in hidden_cells {
+                self.enqueue_cell(cell);
+            }
+
+            let hidden_headers: Vec<View> = {
+                let behavior = self.behavior();
+                let mut visible_headers = behavior.visible_headers.borrow_mut();
+                let hidden: Vec<usize> = visible_headers.keys()
+                    .filter(|section| !headers.iter().any(|(header, _)| header == *section))
+                    .cloned()
+                    .collect();
+
+                hidden.iter().filter_map(|section| visible_headers.remove(section)).collect()
+            };
+
+            for header in hidden_headers {
+                header.remove_from_superview();
+            }
+
+            let content_view = self.content_view();
+
+            for (index_path, frame) in rows {
+                if self.cell_for_row(index_path).is_some() {
+                    continue;
+                }
+
+                let cell = data_source.cell_for_row(self, index_path);
+                cell.fit_to(frame);
+                cell.set_selected(self.is_row_selected(index_path));
+                content_view.add_subview(cell.clone());
+
+                let behavior = self.behavior();
+                behavior.visible_cells.borrow_mut().insert(index_path, cell);
+            }
+
+            // Headers go above the rows.
+            for (section, frame) in headers {
+                if self.behavior().visible_headers.borrow().contains_key(&section) {
+                    continue;
+                }
+
+                if let Some(header) = data_source.view_for_header(self, section) {
+                    header.set_frame(frame);
+                    content_view.add_subview(header.clone());
+
+                    let behavior = self.behavior();
+                    behavior.visible_headers.borrow_mut().insert(section, header);
+                }
+            }
+        }
+
+        fn enqueue_cell(&self, cell: TableViewCell) {
+            cell.remove_from_superview();
+            cell.set_selected(false);
+
+            let behavior = self.behavior();
+            let mut reuse_queue = behavior.reuse_queue.borrow_mut();
+            reuse_queue.entry(cell.reuse_identifier()).or_default().push(cell);
+        }
+
+        /// A cell with the given identifier that has scrolled out of view,
+        /// to be reused by the data source's `cell_for_row`. If there isn't
+        /// one, the data source should create a new cell.
+        pub fn dequeue_reusable_cell(&self, reuse_identifier: &str) -> Option<TableViewCell> {
+            let behavior = self.behavior();
+            let mut reuse_queue = behavior.reuse_queue.borrow_mut();
+            reuse_queue.get_mut(reuse_identifier).and_then(|cells| cells.pop())
+        }
+
+        /// The cell showing the row, if it's scrolled into view.
+        pub fn cell_for_row(&self, index_path: IndexPath) -> Option<TableViewCell> {
+            self.behavior().visible_cells.borrow().get(&index_path).cloned()
+        }
+
+        pub fn index_paths_for_visible_rows(&self) -> Vec<IndexPath> {
+            self.behavior().visible_cells.borrow().keys().cloned().collect()
+        }
+
+        /// Scrolls just enough to show the row.
+        pub fn scroll_to_row(&self, index_path: IndexPath, animated: bool) {
+            if let Some(rect) = self.rect_for_row(index_path) {
+                self.scroll_view().scroll_rect_to_visible(rect, animated);
+            }
+        }
+
+        /// Whether tapping rows adds them to the selection, rather than
+        /// replacing it. `false` by default.
+        pub fn allows_multiple_selection(&self) -> bool {
+            self.behavior().allows_multiple_selection.get()
+        }
+
+        pub fn set_allows_multiple_selection(&self, allows: bool) {
+            self.behavior().allows_multiple_selection.set(allows);
+
+            if !allows {
+                for index_path in self.selected_rows().into_iter().skip(1) {
+                    self.deselect_row(index_path);
+                }
+            }
+        }
+
+        /// The selected rows, in order.
+        pub fn selected_rows(&self) -> Vec<IndexPath> {
+            self.behavior().selected_rows.borrow().iter().cloned().collect()
+        }
+
+        /// The first selected row.
+        pub fn selected_row(&self) -> Option<IndexPath> {
+            self.behavior().selected_rows.borrow().iter().next().cloned()
+        }
+
+        pub fn is_row_selected(&self, index_path: IndexPath) -> bool {
+            self.behavior().selected_rows.borrow().contains(&index_path)
+        }
+
+        /// Selects (and highlights) the row, deselecting any others unless
+        /// multiple selection is allowed. The delegate is only notified of
+        /// selections made by the user.
+        pub fn select_row(&self, index_path: IndexPath) {
+            if !self.contains_row(&index_path) {
+                return;
+            }
+
+            if !self.allows_multiple_selection() {
+                for other in self.selected_rows() {
+                    if other != index_path {
+                        self.deselect_row(other);
+                    }
+                }
+            }
+
+            self.behavior().selected_rows.borrow_mut().insert(index_path);
+
+            if let Some(cell) = self.cell_for_row(index_path) {
+                cell.set_selected(true);
+            }
+        }
+
+        pub fn deselect_row(&self, index_path: IndexPath) {
+            self.behavior().selected_rows.borrow_mut().remove(&index_path);
+
+            if let Some(cell) = self.cell_for_row(index_path) {
+                cell.set_selected(false);
+            }
+        }
+
+        /// Selects the row as the user, telling the delegate about the rows
+        /// selected and deselected.
+        fn user_select_row(&self, index_path: IndexPath) {
+            let delegate = self.delegate();
+
+            if !self.allows_multiple_selection() {
+                for other in self.selected_rows() {
+                    if other != index_path {
+                        self.deselect_row(other);
+
+                        if let Some(delegate) = &delegate {
+                            delegate.did_deselect_row(self, other);
+                        }
+                    }
+                }
+            }
+
+            if !self.is_row_selected(index_path) {
+                self.select_row(index_path);
+
+                if let Some(delegate) = &delegate {
+                    delegate.did_select_row(self, index_path);
+                }
+            }
+        }
+
+        fn user_deselect_row(&self, index_path: IndexPath) {
+            self.deselect_row(index_path);
+
+            if let Some(delegate) = self.delegate() {
+                delegate.did_deselect_row(self, index_path);
+            }
+        }
+
+        fn did_tap(&self, recognizer: &TapRecognizer) {
+            self.become_first_responder();
+
+            let point = recognizer.location_in(&self.content_view());
+            let index_path = match self.index_path_for_row_at(&point) {
+                Some(index_path) => index_path,
+                None => return
+            };
+
+            // With multiple selection, tapping a selected row deselects it.
+            if self.allows_multiple_selection() && self.is_row_selected(index_path) {
+                self.user_deselect_row(index_path);
+            } else {
+                self.user_select_row(index_path);
+            }
+        }
+
+        /// Selects only the row after (or before) the selection, as with the
+        /// arrow keys, scrolling to show it.
+        fn select_adjacent_row(&self, forwards: bool) {
+            let current = {
+                let behavior = self.behavior();
+                let selected_rows = behavior.selected_rows.borrow();
+                if forwards {
+                    selected_rows.iter().next_back().cloned()
+                } else {
+                    selected_rows.iter().next().cloned()
+                }
+            };
+
+            let adjacent = match current {
+                Some(index_path) if forwards => self.index_path_after(index_path),
+                Some(index_path) => self.index_path_before(index_path),
+                None if forwards => self.index_path_after_section_end(0),
+                None => self.index_path_before_section_start(self.number_of_sections())
+            };
+
+            let adjacent = match adjacent {
+                Some(adjacent) => adjacent,
+                None => return
+            };
+
+            for other in self.selected_rows() {
+                if other != adjacent {
+                    self.user_deselect_row(other);
+                }
+            }
+
+            self.user_select_row(adjacent);
+            self.scroll_to_row(adjacent, false);
+        }
+
+        fn index_path_after(&self, index_path: IndexPath) -> Option<IndexPath> {
+            if index_path.row + 1 < self.number_of_rows(index_path.section) {
+                Some(IndexPath::new(index_path.section, index_path.row + 1))
+            } else {
+                self.index_path_after_section_end(index_path.section + 1)
+            }
+        }
+
+        fn index_path_before(&self, index_path: IndexPath) -> Option<IndexPath> {
+            if index_path.row > 0 {
+                Some(IndexPath::new(index_path.section, index_path.row - 1))
+            } else {
+                self.index_path_before_section_start(index_path.section)
+            }
+        }
+
+        /// The first row of the first section with rows, from `section` on.
+        fn index_path_after_section_end(&self, section: usize) -> Option<IndexPath> {
+            (section..self.number_of_sections())
+                .find(|section| self.number_of_rows(*section) > 0)
+                .map(|section| IndexPath::new(section, 0))
+        }
+
+        /// The last row of the last section with rows, before `section`.
+        fn index_path_before_section_start(&self, section: usize) -> Option<IndexPath> {
+            (0..section).rev()
+                .find(|section| self.number_of_rows(*section) > 0)
+                .map(|section| IndexPath::new(section, self.number_of_rows(section) - 1))
+        }
+    }
+
+    impl Behavior {
+        /// Keep the scroll view filling the table view, and the rows as wide
+        /// as it.
+        fn layout_subviews(&self) {
+            let table_view = self.view_type();
+            let size = table_view.frame().size;
+            let scroll_view = table_view.scroll_view();
+
+            if scroll_view.frame().size == size {
+                return;
+            }
+
+            scroll_view.set_frame(Rectangle::new(0, 0, size.width, size.height));
+
+            let height = scroll_view.content_size().height;
+            scroll_view.set_content_size(Size::new(size.width, height));
+
+            let cells: Vec<(IndexPath, TableViewCell)> = self.visible_cells.borrow().iter()
+                .map(|(index_path, cell)| (*index_path, cell.clone()))
+                .collect();
+
+            for (index_path, cell) in cells {
+                if let Some(frame) = table_view.rect_for_row(index_path) {
+                    cell.fit_to(frame);
+                }
+            }
+
+            let headers: Vec<(usize, View)> = self.visible_headers.borrow().iter()
+                .map(|(section, header)| (*section, header.clone()))
+                .collect();
+
+            for (section, header) in headers {
+                if let Some(frame) = table_view.rect_for_header(section) {
+                    header.set_frame(frame);
+                }
+            }
+
+            table_view.layout_visible_rows();
+        }
+
+        fn press_began(&self, press: &Press) {
+            let key_code = press.key().key_code();
+
+            match key_code {
+                KeyCode::Up => self.view_type().select_adjacent_row(false),
+                KeyCode::Down => self.view_type().select_adjacent_row(true),
+                _ => self.super_behavior().expect("super behavior was missing").press_began(press)
+            }
+        }
+    }
+);
+
+custom_view!(
+    TableViewCell subclasses DefaultBehavior
+
+    struct TableViewCellBehavior {
+        reuse_identifier: String,
+        selected: Cell<bool>,
+        unselected_background_color: RefCell<Color>,
+        selected_background_color: RefCell<Color>
+    }
+
+    impl Self {
+        /// A row of a `TableView`. Cells with the same `reuse_identifier` can
+        /// be reused for each other's rows; see
+        /// `TableView::dequeue_reusable_cell`.
+        pub fn new(reuse_identifier: &str) -> Self {
+            let cell = Self::new_all(
+                Rectangle::new(0, 0, 0, 0),
+                reuse_identifier.to_string(),
+                Cell::new(false),
+                RefCell::new(Color::white()),
+                RefCell::new(Color::new(200, 220, 255, 255))
+            );
+
+            let content_view = View::new(Rectangle::new(0, 0, 0, 0));
+            content_view.set_background_color(Color::clear());
+            cell.add_subview(content_view);
+
+            cell
+        }
+
+        pub fn reuse_identifier(&self) -> String {
+            self.behavior().reuse_identifier.clone()
+        }
+
+        /// The view to add the cell's content to. It's resized to fill the
+        /// cell.
+        pub fn content_view(&self) -> View {
+            self.subviews().first().expect("table view cell missing content view").clone()
+        }
+
+        pub fn is_selected(&self) -> bool {
+            self.behavior().selected.get()
+        }
+
+        pub(crate) fn set_selected(&self, selected: bool) {
+            self.behavior().selected.set(selected);
+            self.update_background_color();
+        }
+
+        /// The background of the cell while it's not selected. White by
+        /// default.
+        pub fn set_unselected_background_color(&self, color: Color) {
+            self.behavior().unselected_background_color.replace(color);
+            self.update_background_color();
+        }
+
+        /// The background of the cell while it's selected, highlighting it.
+        pub fn set_selected_background_color(&self, color: Color) {
+            self.behavior().selected_background_color.replace(color);
+            self.update_background_color();
+        }
+
+        fn update_background_color(&self) {
+            let color = {
+                let behavior = self.behavior();
+                if behavior.selected.get() {
+                    behavior.selected_background_color.borrow().clone()
+                } else {
+                    behavior.unselected_background_color.borrow().clone()
+                }
+            };
+
+            self.set_background_color(color);
+        }
+
+        fn fit_to(&self, frame: Rectangle<i32, u32>) {
+            let size = frame.size.clone();
+            self.set_frame(frame);
+            self.content_view().set_frame(Rectangle::new(0, 0, size.width, size.height));
+        }
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::key::Key;
+    use crate::ui::view::Behavior;
+    use crate::ui::view::test_data_source::TestDataSource;
+
+    fn table_view(sections: Vec<usize>) -> (TableView, TestDataSource) {
+        let table_view = TableView::new(Rectangle::new(0, 0, 200, 100));
+        let data_source = TestDataSource::new(sections);
+        table_view.set_data_source(data_source.clone());
+        (table_view, data_source)
+    }
+
+    #[test]
+    fn test_layout() {
+        let (table_view, _) = table_view(vec![3, 2]);
+        assert_eq!(table_view.number_of_sections(), 2);
+        assert_eq!(table_view.number_of_rows(1), 2);
+        assert_eq!(table_view.scroll_view().content_size(), Size::new(200, 220));
+
+        table_view.set_section_header_height(20);
+        assert_eq!(table_view.rect_for_header(1), Some(Rectangle::new(0, 152, 200, 20)));
+        assert_eq!(table_view.rect_for_row(IndexPath::new(1, 0)), Some(Rectangle::new(0, 172, 200, 44)));
+        assert_eq!(table_view.rect_for_row(IndexPath::new(1, 2)), None);
+
+        assert_eq!(table_view.index_path_for_row_at(&Point::new(10, 10)), None);
+        assert_eq!(table_view.index_path_for_row_at(&Point::new(10, 70)), Some(IndexPath::new(0, 1)));
+        assert_eq!(table_view.index_path_for_row_at(&Point::new(10, 240)), Some(IndexPath::new(1, 1)));
+        assert_eq!(table_view.index_path_for_row_at(&Point::new(10, 260)), None);
+    }
+
+    #[test]
+    fn test_only_visible_rows_have_cells() {
+        let (table_view, data_source) = table_view(vec![1000]);
+
+        assert_eq!(
+            table_view.index_paths_for_visible_rows(),
+            vec![IndexPath::new(0, 0), IndexPath::new(0, 1), IndexPath::new(0, 2)]
+        );
+        assert_eq!(data_source.created(), 3);
+
+        // Cells of rows scrolled out of view are reused.
+        table_view.set_content_offset(Point::new(0, 440), false);
+        assert_eq!(
+            table_view.index_paths_for_visible_rows(),
+            vec![IndexPath::new(0, 10), IndexPath::new(0, 11), IndexPath::new(0, 12)]
+        );
+        assert_eq!(data_source.created(), 3);
+        assert_eq!(table_view.content_view().subviews().len(), 3);
+
+        let cell = table_view.cell_for_row(IndexPath::new(0, 10)).unwrap();
+        assert_eq!(cell.frame(), Rectangle::new(0, 440, 200, 44));
+        assert_eq!(cell.content_view().frame(), Rectangle::new(0, 0, 200, 44));
+    }
+
+    #[test]
+    fn test_resize() {
+        let (table_view, data_source) = table_view(vec![1000]);
+
+        table_view.set_frame(Rectangle::new(0, 0, 300, 200));
+        table_view.layout_if_needed();
+
+        assert_eq!(table_view.scroll_view().frame(), Rectangle::new(0, 0, 300, 200));
+        assert_eq!(table_view.scroll_view().content_size(), Size::new(300, 44000));
+        assert_eq!(table_view.index_paths_for_visible_rows().len(), 5);
+        assert_eq!(data_source.created(), 5);
+
+        let cell = table_view.cell_for_row(IndexPath::new(0, 0)).unwrap();
+        assert_eq!(cell.frame(), Rectangle::new(0, 0, 300, 44));
+    }
+
+    #[test]
+    fn test_selection() {
+        let (table_view, _) = table_view(vec![10]);
+
+        table_view.select_row(IndexPath::new(0, 1));
+        assert!(table_view.cell_for_row(IndexPath::new(0, 1)).unwrap().is_selected());
+
+        table_view.select_row(IndexPath::new(0, 2));
+        assert_eq!(table_view.selected_rows(), vec![IndexPath::new(0, 2)]);
+        assert!(!table_view.cell_for_row(IndexPath::new(0, 1)).unwrap().is_selected());
+
+        table_view.set_allows_multiple_selection(true);
+        table_view.select_row(IndexPath::new(0, 0));
+        assert_eq!(table_view.selected_rows(), vec![IndexPath::new(0, 0), IndexPath::new(0, 2)]);
+
+        // Selection isn't lost when the row's cell is reused.
+        table_view.set_content_offset(Point::new(0, 300), false);
+        table_view.set_content_offset(Point::new(0, 0), false);
+        assert!(table_view.cell_for_row(IndexPath::new(0, 0)).unwrap().is_selected());
+        assert!(!table_view.cell_for_row(IndexPath::new(0, 1)).unwrap().is_selected());
+
+        table_view.deselect_row(IndexPath::new(0, 0));
+        assert_eq!(table_view.selected_row(), Some(IndexPath::new(0, 2)));
+    }
+
+    #[test]
+    fn test_reload_data_drops_missing_rows() {
+        let table_view = TableView::new(Rectangle::new(0, 0, 200, 100));
+        table_view.set_data_source(TestDataSource::new(vec![10]));
+        table_view.select_row(IndexPath::new(0, 8));
+
+        table_view.set_data_source(TestDataSource::new(vec![5]));
+        assert_eq!(table_view.selected_row(), None);
+    }
+
+    struct SelectionLog {
+        log: Rc<RefCell<Vec<String>>>
+    }
+
+    impl TableViewDelegate for SelectionLog {
+        fn did_select_row(&self, _table_view: &TableView, index_path: IndexPath) {
+            self.log.borrow_mut().push(format!("select {}.{}", index_path.section, index_path.row));
+        }
+
+        fn did_deselect_row(&self, _table_view: &TableView, index_path: IndexPath) {
+            self.log.borrow_mut().push(format!("deselect {}.{}", index_path.section, index_path.row));
+        }
+    }
+
+    #[test]
+    fn test_keyboard_navigation() {
+        let (table_view, _) = table_view(vec![2, 0, 1]);
+        let log = Rc::new(RefCell::new(Vec::new()));
+        table_view.set_delegate(SelectionLog { log: log.clone() });
+
+        let down = Press::new(Key::new(KeyCode::Down, vec![]));
+        let up = Press::new(Key::new(KeyCode::Up, vec![]));
+
+        table_view.behavior().press_began(&down);
+        assert_eq!(table_view.selected_row(), Some(IndexPath::new(0, 0)));
+
+        table_view.behavior().press_began(&down);
+        table_view.behavior().press_began(&down);
+        assert_eq!(table_view.selected_row(), Some(IndexPath::new(2, 0)));
+
+        // Stays at the last row.
+        table_view.behavior().press_began(&down);
+        assert_eq!(table_view.selected_row(), Some(IndexPath::new(2, 0)));
+
+        table_view.behavior().press_began(&up);
+        assert_eq!(table_view.selected_row(), Some(IndexPath::new(0, 1)));
+
+        assert_eq!(*log.borrow(), vec![
+            "select 0.0", "deselect 0.0", "select 0.1", "deselect 0.1", "select 2.0",
+            "deselect 2.0", "select 0.1"
+        ]);
+    }
+}
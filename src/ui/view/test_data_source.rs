@@ -0,0 +1,83 @@
+//! A data source shared by the `TableView` and `CollectionView` tests.
+
+use crate::graphics::Size;
+use crate::ui::view::collection_view::{CollectionView, CollectionViewCell, CollectionViewDataSource};
+use crate::ui::view::table_view::{IndexPath, TableView, TableViewCell, TableViewDataSource};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+const REUSE_IDENTIFIER: &str = "cell";
+
+/// Plain cells, with the given number of rows (or items) in each section.
+/// Clones share their counts, so a test can keep one to change the counts
+/// before an update, or to check how many cells were created rather than
+/// reused.
+#[derive(Clone)]
+pub(crate) struct TestDataSource {
+    counts: Rc<RefCell<Vec<usize>>>,
+    sizes: Option<Vec<Size<u32>>>,
+    created: Rc<Cell<usize>>
+}
+
+impl TestDataSource {
+    pub(crate) fn new(counts: Vec<usize>) -> TestDataSource {
+        TestDataSource {
+            counts: Rc::new(RefCell::new(counts)),
+            sizes: None,
+            created: Rc::new(Cell::new(0))
+        }
+    }
+
+    /// Gives each item its own size, by its index within the section.
+    pub(crate) fn with_sizes(self, sizes: Vec<Size<u32>>) -> TestDataSource {
+        TestDataSource { sizes: Some(sizes), ..self }
+    }
+
+    pub(crate) fn set_count(&self, section: usize, count: usize) {
+        self.counts.borrow_mut()[section] = count;
+    }
+
+    /// How many cells have been created, rather than dequeued.
+    pub(crate) fn created(&self) -> usize {
+        self.created.get()
+    }
+
+    fn dequeue_or_create<C>(&self, dequeued: Option<C>, create: impl FnOnce(&str) -> C) -> C {
+        dequeued.unwrap_or_else(|| {
+            self.created.set(self.created.get() + 1);
+            create(REUSE_IDENTIFIER)
+        })
+    }
+}
+
+impl TableViewDataSource for TestDataSource {
+    fn number_of_sections(&self, _table_view: &TableView) -> usize {
+        self.counts.borrow().len()
+    }
+
+    fn number_of_rows(&self, _table_view: &TableView, section: usize) -> usize {
+        self.counts.borrow()[section]
+    }
+
+    fn cell_for_row(&self, table_view: &TableView, _index_path: IndexPath) -> TableViewCell {
+        self.dequeue_or_create(table_view.dequeue_reusable_cell(REUSE_IDENTIFIER), TableViewCell::new)
+    }
+}
+
+impl CollectionViewDataSource for TestDataSource {
+    fn number_of_sections(&self, _collection_view: &CollectionView) -> usize {
+        self.counts.borrow().len()
+    }
+
+    fn number_of_items(&self, _collection_view: &CollectionView, section: usize) -> usize {
+        self.counts.borrow()[section]
+    }
+
+    fn cell_for_item(&self, collection_view: &CollectionView, _index_path: IndexPath) -> CollectionViewCell {
+        self.dequeue_or_create(collection_view.dequeue_reusable_cell(REUSE_IDENTIFIER), CollectionViewCell::new)
+    }
+
+    fn size_for_item(&self, _collection_view: &CollectionView, index_path: IndexPath) -> Option<Size<u32>> {
+        self.sizes.as_ref().map(|sizes| sizes[index_path.row].clone())
+    }
+}
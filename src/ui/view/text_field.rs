@@ -2,10 +2,10 @@ use crate::graphics::{Rectangle, Size, Point};
 use crate::ui::view::{View, WeakView};
 use crate::ui::view::DefaultBehavior;
 use crate::ui::Color;
+use crate::ui::Cursor;
 use crate::macros::*;
 use crate::ui::view::Label;
-use crate::ui::run_loop::RunLoop;
-use crate::ui::timer::Timer;
+use crate::ui::animation::{Animation, AnimationCurve, AnimationRepeat};
 use crate::ui::touch::Touch;
 use crate::ui::press::Press;
 use crate::ui::key::{KeyCode, ModifierFlag};
@@ -78,10 +78,8 @@ impl Drop for Selection {
 
 impl Drop for TextFieldBehavior {
     fn drop(&mut self) {
-        let mut carat_animation_timer = self.carat_animation_timer.borrow_mut();
-
-        if let Some(timer) = carat_animation_timer.as_mut() {
-            timer.invalidate();
+        if let Some(animation) = self.carat_animation.borrow().as_ref() {
+            animation.cancel();
         }
     }
 }
@@ -106,8 +104,12 @@ custom_view!(
         // is made from where the touch started to where the finger is now.
         touch_began_at_index: Cell<usize>,
 
-        // A timer responsible for animating the carats.
-        carat_animation_timer: RefCell<Option<Timer>>,
+        // Blinks the carats, halfway through each cycle and at its end.
+        carat_animation: RefCell<Option<Animation<f32>>>,
+
+        // Whether the carat animation was in the second half of its cycle
+        // as of its last frame.
+        carat_blink_half: Cell<bool>,
 
         delay_animation: Cell<bool>,
 
@@ -140,6 +142,7 @@ custom_view!(
                 Cell::new(0),
                 RefCell::new(None),
                 Cell::new(false),
+                Cell::new(false),
                 Cell::new(Instant::now()),
                 Cell::new(0),
                 RefCell::new(History::new()),
@@ -150,17 +153,18 @@ custom_view!(
             text_field.spawn_carat(0);
 
             let weak_text_field = text_field.downgrade();
-            let carat_animation_timer = Timer::new_repeating(Duration::from_millis(CARAT_BLINK_INTERVAL_MS), move || {
-                if let Some(view) = weak_text_field.upgrade() {
-                    let text_field = TextField::from_view(view);
-                    text_field.animate_carats();
-                }
-            });
-            let run_loop = RunLoop::borrow();
-            run_loop.add_timer(carat_animation_timer.clone());
+            let carat_animation = Animation::new(Duration::from_millis(CARAT_BLINK_INTERVAL_MS * 2), 0.0, 1.0, AnimationCurve::Linear)
+                .with_repeat(AnimationRepeat::Forever)
+                .with_tick(move |time| {
+                    if let Some(view) = weak_text_field.upgrade() {
+                        let text_field = TextField::from_view(view);
+                        text_field.blink_carats_at(*time);
+                    }
+                });
+            carat_animation.start();
 
             let behavior = text_field.behavior();
-            behavior.carat_animation_timer.replace(Some(carat_animation_timer));
+            behavior.carat_animation.replace(Some(carat_animation));
 
             text_field.clone()
         }
@@ -253,9 +257,36 @@ custom_view!(
             self.set_needs_display();
         }
 
+        /// Blinks the carats each time the carat animation passes half way
+        /// through its cycle or starts over.
+        fn blink_carats_at(&self, time: f32) {
+            let second_half = time >= 0.5;
+
+            if self.behavior().carat_blink_half.replace(second_half) != second_half {
+                self.animate_carats();
+            }
+        }
+
         fn animate_carats(&self) {
             let behavior = self.behavior();
 
+            // Only the key window shows a blinking carat; elsewhere it stays
+            // hidden until the window becomes key again. The carats are only
+            // touched once, rather than redrawn on every tick.
+            let is_in_key_window = self.window().is_some_and(|window| window.is_key_window());
+            if !is_in_key_window {
+                for carat in behavior.carats.borrow().iter() {
+                    if let Some(view) = carat.view.upgrade() {
+                        if !view.is_hidden() {
+                            view.set_hidden(true);
+                        }
+                    }
+                }
+
+                behavior.delay_animation.set(true);
+                return;
+            }
+
             let mut hidden: Option<bool> = None;
             for carat in behavior.carats.borrow().iter() {
                 if let Some(view) = carat.view.upgrade() {
@@ -270,9 +301,7 @@ custom_view!(
                 }
             }
 
-            if behavior.delay_animation.get() {
-                behavior.delay_animation.set(false);
-            }
+            behavior.delay_animation.set(false);
         }
 
         fn select_all(&self) {
@@ -771,6 +800,10 @@ custom_view!(
             self.last_click.set(Instant::now());
         }
 
+        fn cursor_for_point(&self, _point: &Point<i32>) -> Option<Cursor> {
+            Some(Cursor::IBeam)
+        }
+
         fn did_become_first_responder(&self) {
             // Ensure SDL2 text input is active so we receive TextInput events.
             unsafe { sdl2::sys::SDL_StartTextInput(); }
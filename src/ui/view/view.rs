@@ -1,7 +1,8 @@
 use crate::ui::Color;
 use crate::ui::Touch;
+use crate::ui::Cursor;
 use crate::ui::view::{WeakView, Behavior, DefaultBehavior, ViewInner};
-use crate::graphics::{Layer, Rectangle, Point, LayerDelegate};
+use crate::graphics::{Layer, Rectangle, Point, Size, LayerDelegate, Transform};
 use std::rc::{Rc, Weak};
 use std::cell::RefCell;
 use std::cell::Ref;
@@ -9,6 +10,9 @@ use crate::ui::gesture::recognizer::Recognizer;
 use crate::ui::event::{TouchEvent, PressEvent};
 use crate::ui::window::Window;
 use crate::ui::press::Press;
+use crate::ui::layout::{self, Anchor, Attribute, AutoresizingMask, Constraint};
+use crate::ui::animation::{self, AnimatedValue, AnimationCurve, ViewAnimation};
+use std::time::Duration;
 
 use std::sync::atomic::{AtomicUsize, Ordering};
 fn next_id() -> usize {
@@ -53,16 +57,23 @@ impl View {
         let inner_self = ViewInner {
             id: next_id(),
             tag: 0,
-            frame: frame,
+            frame: frame.clone(),
             bounds: bounds,
+            transform: Transform::identity(),
             background_color: white,
+            alpha: 1.0,
             layer: None,
             superview: WeakView::none(),
             subviews: Vec::new(),
             gesture_recognizers: Vec::new(),
             hidden: false,
             user_interaction_enabled: true,
-            clips_to_bounds: false
+            clips_to_bounds: false,
+            constraints: Vec::new(),
+            translates_frame_into_constraints: true,
+            autoresizing_mask: AutoresizingMask::NONE,
+            autoresized_size: frame.size.clone(),
+            needs_layout: true
         };
 
         let view = View {
@@ -130,7 +141,6 @@ impl View {
     pub fn add_subview(&self, child: impl Into<View>) {
         let child: View = child.into();
         let weak_self = self.downgrade();
-        let mut inner_self = self.inner_self.borrow_mut();
 
         {
             let mut child_inner = child.inner_self.borrow_mut();
@@ -139,9 +149,10 @@ impl View {
             child_inner.superview = weak_self;
         }
 
-        inner_self.subviews.push(child.clone());
+        self.inner_self.borrow_mut().subviews.push(child.clone());
 
         child.set_needs_display();
+        self.set_needs_layout();
     }
 
     /// Remove the view from its superview.
@@ -154,6 +165,7 @@ impl View {
                 superview_inner.subviews.retain(|view| view.id() != self.id());
             }
             superview.set_needs_display();
+            superview.set_needs_layout();
         }
     }
 
@@ -179,7 +191,13 @@ impl View {
     }
 
     /// Change the background color for this view.
+    pub fn background_color(&self) -> Color {
+        self.inner_self.borrow().background_color.clone()
+    }
+
     pub fn set_background_color(&self, color: Color) {
+        animation::record(self, || AnimatedValue::BackgroundColor(self.background_color()));
+
         {
             let mut inner_self = self.inner_self.borrow_mut();
 
@@ -193,6 +211,57 @@ impl View {
         self.set_needs_display();
     }
 
+    pub fn alpha(&self) -> f32 {
+        self.inner_self.borrow().alpha
+    }
+
+    /// Fade the view and its subviews, from `0.0` (invisible) to `1.0`
+    /// (opaque, the default). Views with an alpha of `0.0` don't receive
+    /// touches.
+    pub fn set_alpha(&self, alpha: f32) {
+        animation::record(self, || AnimatedValue::Alpha(self.alpha()));
+
+        {
+            let mut inner_self = self.inner_self.borrow_mut();
+            let alpha = alpha.clamp(0.0, 1.0);
+
+            if inner_self.alpha == alpha {
+                return;
+            }
+
+            inner_self.alpha = alpha;
+        }
+
+        // The superview draws this view's layer faded.
+        if let Some(superview) = self.superview().upgrade() {
+            superview.set_needs_display();
+        }
+    }
+
+    /// Animate the changes made in `changes` to any view's frame, bounds
+    /// origin, background colour, alpha or transform. The views move from
+    /// their old values to the new ones over `duration`, following `curve`.
+    ///
+    /// Properties already being animated are taken over by the new
+    /// animation, starting from where they are. See `ViewAnimation` for
+    /// delays, repeating and completion callbacks.
+    ///
+    /// ```ignore
+    /// View::animate(Duration::from_millis(250), AnimationCurve::EaseOut, || {
+    ///     panel.set_frame(Rectangle::new(0, 0, 200, 400));
+    ///     dimming.set_alpha(0.5);
+    /// });
+    /// ```
+    pub fn animate(duration: Duration, curve: AnimationCurve, changes: impl FnOnce()) -> ViewAnimation {
+        ViewAnimation::animate(duration, curve, changes)
+    }
+
+    /// Stop any animations of this view's properties, leaving them where
+    /// they are.
+    pub fn cancel_animations(&self) {
+        animation::cancel_view_animations(self);
+    }
+
     /// Request for this view to be redrawn soon.
     ///
     /// See `#draw`, which includes the instructions on what would actually be
@@ -231,6 +300,7 @@ impl View {
         }
 
         self.set_needs_display();
+        self.set_needs_superview_layout();
     }
 
     pub fn is_hidden(&self) -> bool {
@@ -252,6 +322,53 @@ impl View {
         behavior.touches_moved(touches);
     }
 
+    pub fn touches_cancelled(&self, touches: &Vec<Touch>, _event: &TouchEvent) {
+        let behavior = self.behavior.borrow();
+        behavior.touches_cancelled(touches);
+    }
+
+    pub fn right_mouse_down(&self, touch: &Touch) {
+        let behavior = self.behavior.borrow();
+        behavior.right_mouse_down(touch);
+    }
+
+    pub fn right_mouse_up(&self, touch: &Touch) {
+        let behavior = self.behavior.borrow();
+        behavior.right_mouse_up(touch);
+    }
+
+    pub fn other_mouse_down(&self, touch: &Touch) {
+        let behavior = self.behavior.borrow();
+        behavior.other_mouse_down(touch);
+    }
+
+    pub fn other_mouse_up(&self, touch: &Touch) {
+        let behavior = self.behavior.borrow();
+        behavior.other_mouse_up(touch);
+    }
+
+    pub fn mouse_entered(&self) {
+        let behavior = self.behavior.borrow();
+        behavior.mouse_entered();
+    }
+
+    pub fn mouse_exited(&self) {
+        let behavior = self.behavior.borrow();
+        behavior.mouse_exited();
+    }
+
+    pub fn mouse_moved(&self, position: &Point<i32>) {
+        let behavior = self.behavior.borrow();
+        behavior.mouse_moved(position);
+    }
+
+    /// The cursor to show while the mouse is over the given point, in this
+    /// view's coordinate space. See `Behavior::cursor_for_point`.
+    pub fn cursor_for_point(&self, point: &Point<i32>) -> Option<Cursor> {
+        let behavior = self.behavior.borrow();
+        behavior.cursor_for_point(point)
+    }
+
     pub fn press_began(&self, press: &Press, _event: &PressEvent) {
         let behavior = self.behavior.borrow();
         behavior.press_began(press);
@@ -288,16 +405,74 @@ impl View {
     /// Convert the given point from the coordinate system of this view to the
     /// coordinate system of the given view.
     pub fn convert_point_to(&self, point: &Point<i32>, to_view: &View) -> Point<i32> {
-        let from = self.get_location_in_window();
-        let to = to_view.get_location_in_window();
+        let mut position = (point.x as f32, point.y as f32);
+
+        // Up to the window...
+        let mut view = self.clone();
+        while let Some(superview) = view.superview_in_window() {
+            position = view.point_to_superview(position, &superview);
+            view = superview;
+        }
+
+        // ...and back down to the other view.
+        let mut ancestors = Vec::new();
+        let mut view = to_view.clone();
+        while let Some(superview) = view.superview_in_window() {
+            ancestors.push((view, superview.clone()));
+            view = superview;
+        }
+
+        for (view, superview) in ancestors.iter().rev() {
+            position = view.point_from_superview(position, superview);
+        }
+
+        Point::new(position.0.round() as i32, position.1.round() as i32)
+    }
 
-        let x_shift = from.x - to.x;
-        let y_shift = from.y - to.y;
+    /// The superview, unless this is the window (the top of the hierarchy).
+    fn superview_in_window(&self) -> Option<View> {
+        if self.is_window() {
+            return None;
+        }
+
+        self.superview().upgrade()
+    }
+
+    /// Converts a point in this view to the superview's coordinate system,
+    /// through the transform.
+    fn point_to_superview(&self, point: (f32, f32), superview: &View) -> (f32, f32) {
+        let inner_self = self.inner_self.borrow();
+        let (center_x, center_y) = self.center_in_superview(superview);
 
-        let x = point.x + x_shift;
-        let y = point.y + y_shift;
+        let half_width = inner_self.frame.size.width as f32 / 2.0;
+        let half_height = inner_self.frame.size.height as f32 / 2.0;
+        let (x, y) = inner_self.transform.apply(point.0 - half_width, point.1 - half_height);
 
-        Point { x, y }
+        (center_x + x, center_y + y)
+    }
+
+    /// Undoes `point_to_superview`.
+    fn point_from_superview(&self, point: (f32, f32), superview: &View) -> (f32, f32) {
+        let inner_self = self.inner_self.borrow();
+        let (center_x, center_y) = self.center_in_superview(superview);
+
+        let half_width = inner_self.frame.size.width as f32 / 2.0;
+        let half_height = inner_self.frame.size.height as f32 / 2.0;
+        let (x, y) = inner_self.transform.unapply(point.0 - center_x, point.1 - center_y);
+
+        (x + half_width, y + half_height)
+    }
+
+    /// The centre of the (untransformed) frame, in the superview's coordinate
+    /// system; i.e. taking its bounds into account.
+    fn center_in_superview(&self, superview: &View) -> (f32, f32) {
+        let frame = self.frame();
+        let superview_bounds = superview.bounds();
+
+        (
+            (frame.origin.x - superview_bounds.origin.x) as f32 + frame.size.width as f32 / 2.0,
+            (frame.origin.y - superview_bounds.origin.y) as f32 + frame.size.height as f32 / 2.0
+        )
     }
 
     /// Returns the deepest subview that contains the given point.
@@ -310,7 +485,7 @@ impl View {
     pub fn hit_test(&self, point: &Point<i32>) -> Option<View> {
         let inner_self = self.inner_self.borrow();
 
-        if inner_self.hidden {
+        if inner_self.hidden || inner_self.alpha == 0.0 {
             return None;
         }
 
@@ -338,37 +513,90 @@ impl View {
         None
     }
 
+    /// Move and resize the view. If the size changes, the view's subviews
+    /// are laid out again; see `Behavior::layout_subviews`.
     pub fn set_frame(&self, frame: Rectangle<i32, u32>) {
-        {
+        animation::record(self, || AnimatedValue::Frame(self.frame()));
+
+        let resized = {
             let mut inner_self = self.inner_self.borrow_mut();
 
             let bounds = Rectangle {
                 origin: inner_self.bounds.origin.clone(),
-                size: inner_self.frame.size.clone(),
+                size: frame.size.clone(),
             };
 
             if inner_self.frame == frame && inner_self.bounds == bounds {
                 return;
             }
 
+            let resized = inner_self.frame.size != frame.size || inner_self.bounds.size != frame.size;
             inner_self.frame = frame;
             inner_self.bounds = bounds;
-        }
+            resized
+        };
 
         self.set_needs_display();
+
+        if resized {
+            self.autoresize_subviews(&self.frame().size);
+            self.set_needs_layout();
+        } else if !layout::is_laying_out() {
+            self.set_needs_window_layout();
+        }
     }
 
     pub fn bounds(&self) -> Rectangle<i32, u32> {
         self.inner_self.borrow().bounds.clone()
     }
 
-    pub fn set_bounds(&self, bounds: Rectangle<i32, u32>) {
+    pub fn transform(&self) -> Transform {
+        self.inner_self.borrow().transform
+    }
+
+    /// Scale and move the view as it's drawn, e.g. to zoom it, without
+    /// changing its frame. Touches and `convert_point_to` follow the
+    /// transform.
+    pub fn set_transform(&self, transform: Transform) {
+        animation::record(self, || AnimatedValue::Transform(self.transform()));
+
         {
             let mut inner_self = self.inner_self.borrow_mut();
-            inner_self.bounds = bounds;
+
+            if inner_self.transform == transform {
+                return;
+            }
+
+            inner_self.transform = transform;
         }
 
+        // The superview draws this view's layer transformed.
+        if let Some(superview) = self.superview().upgrade() {
+            superview.set_needs_display();
+        }
+    }
+
+    pub fn set_bounds(&self, bounds: Rectangle<i32, u32>) {
+        animation::record(self, || AnimatedValue::BoundsOrigin(self.bounds().origin));
+
+        let resized = {
+            let mut inner_self = self.inner_self.borrow_mut();
+            let resized = inner_self.bounds.size != bounds.size;
+            inner_self.bounds = bounds;
+            resized
+        };
+
         self.set_needs_display();
+
+        if resized {
+            // A clipping view's bounds is a viewport onto its content (e.g. a
+            // scroll view's), so resizing it shouldn't resize the content.
+            if !self.clips_to_bounds() {
+                self.autoresize_subviews(&self.bounds().size);
+            }
+
+            self.set_needs_layout();
+        }
     }
 
     /// Returns a boolean indicating whether the given point is contained in
@@ -427,6 +655,18 @@ impl View {
         inner_self.subviews.clone()
     }
 
+    /// The window this view is in, if it's in a window's view hierarchy.
+    pub fn window(&self) -> Option<Window> {
+        let mut current_view = self.clone();
+        loop {
+            if current_view.is_window() {
+                return Some(Window::from_view(current_view));
+            }
+
+            current_view = current_view.superview().upgrade()?;
+        }
+    }
+
     /// Request for this view to be the first responder. A first responder
     /// view will be the first to receive keyboard events. See
     /// `Behavior::text_input_did_receive`, `Behavior::press_began`, etc.
@@ -481,7 +721,166 @@ impl View {
         behavior.did_become_first_responder();
     }
 
+    pub fn leading_anchor(&self) -> Anchor {
+        Anchor::new(self, Attribute::Leading)
+    }
+
+    pub fn trailing_anchor(&self) -> Anchor {
+        Anchor::new(self, Attribute::Trailing)
+    }
+
+    pub fn top_anchor(&self) -> Anchor {
+        Anchor::new(self, Attribute::Top)
+    }
+
+    pub fn bottom_anchor(&self) -> Anchor {
+        Anchor::new(self, Attribute::Bottom)
+    }
+
+    pub fn width_anchor(&self) -> Anchor {
+        Anchor::new(self, Attribute::Width)
+    }
+
+    pub fn height_anchor(&self) -> Anchor {
+        Anchor::new(self, Attribute::Height)
+    }
+
+    pub fn center_x_anchor(&self) -> Anchor {
+        Anchor::new(self, Attribute::CenterX)
+    }
+
+    pub fn center_y_anchor(&self) -> Anchor {
+        Anchor::new(self, Attribute::CenterY)
+    }
+
+    /// The active constraints whose first anchor is one of this view's.
+    pub fn constraints(&self) -> Vec<Constraint> {
+        self.inner_self.borrow().constraints.clone()
+    }
+
+    /// Whether constraint-based layout keeps this view at its current frame,
+    /// so other views can be constrained to it. Defaults to `true`, and is
+    /// turned off when a constraint for this view is activated.
+    pub fn translates_frame_into_constraints(&self) -> bool {
+        self.inner_self.borrow().translates_frame_into_constraints
+    }
+
+    pub fn set_translates_frame_into_constraints(&self, value: bool) {
+        self.inner_self.borrow_mut().translates_frame_into_constraints = value;
+        self.set_needs_window_layout();
+    }
+
+    pub fn autoresizing_mask(&self) -> AutoresizingMask {
+        self.inner_self.borrow().autoresizing_mask
+    }
+
+    /// How this view is resized and moved when its superview's frame or
+    /// bounds size changes, e.g. to keep a frame-based view filling the
+    /// window as it's resized. Defaults to `AutoresizingMask::NONE`.
+    ///
+    /// Ignored for views laid out with constraints.
+    pub fn set_autoresizing_mask(&self, mask: AutoresizingMask) {
+        self.inner_self.borrow_mut().autoresizing_mask = mask;
+    }
+
+    /// Resize and move the subviews, by their autoresizing masks, from the
+    /// size they were last autoresized for to `size`.
+    fn autoresize_subviews(&self, size: &Size<u32>) {
+        let old_size = {
+            let mut inner_self = self.inner_self.borrow_mut();
+
+            if inner_self.autoresized_size == *size {
+                return;
+            }
+
+            std::mem::replace(&mut inner_self.autoresized_size, size.clone())
+        };
+
+        for subview in self.subviews() {
+            let mask = subview.autoresizing_mask();
+
+            if mask.is_empty() || !subview.translates_frame_into_constraints() {
+                continue;
+            }
+
+            let frame = mask.apply(&subview.frame(), &old_size, size);
+            subview.set_frame(frame);
+        }
+    }
+
+    /// The natural size of the view's content, e.g. the size of a label's
+    /// text. See `Behavior::intrinsic_content_size`.
+    pub fn intrinsic_content_size(&self) -> Option<Size<u32>> {
+        let behavior = self.behavior.borrow();
+        behavior.intrinsic_content_size()
+    }
+
+    /// Call when the intrinsic content size has changed, e.g. the text of a
+    /// label, so the superview and constraint-based layout are updated.
+    pub fn invalidate_intrinsic_content_size(&self) {
+        self.set_needs_superview_layout();
+    }
+
+    /// The size the view would like to be to fit within the given size. See
+    /// `Behavior::size_that_fits`.
+    pub fn size_that_fits(&self, size: &Size<u32>) -> Size<u32> {
+        let behavior = self.behavior.borrow();
+        behavior.size_that_fits(size)
+    }
+
+    /// Resize the view to the size that fits its current size, keeping its
+    /// origin.
+    pub fn size_to_fit(&self) {
+        let frame = self.frame();
+        let size = self.size_that_fits(&frame.size);
+        self.set_frame(Rectangle { origin: frame.origin, size });
+    }
 
+    /// Lay out this view's subviews before the window is next drawn. Called
+    /// automatically when the view is resized or its subviews change, so is
+    /// only needed when something else affects the layout.
+    ///
+    /// Calls are batched: the view is laid out once per frame however many
+    /// times this is called. See `Behavior::layout_subviews`.
+    pub fn set_needs_layout(&self) {
+        self.inner_self.borrow_mut().needs_layout = true;
+        self.set_needs_window_layout();
+    }
+
+    /// Lay out any views waiting to be laid out now, rather than before the
+    /// window is next drawn. E.g. to read the frames of subviews straight
+    /// after changing something.
+    ///
+    /// If the view isn't in a window, only it and its subviews are laid out.
+    pub fn layout_if_needed(&self) {
+        match self.window() {
+            Some(window) => window.layout_if_needed(),
+            None => {
+                layout::layout(self);
+            }
+        }
+    }
+
+    /// Whether the view is waiting to be laid out. See `set_needs_layout`.
+    pub fn needs_layout(&self) -> bool {
+        self.inner_self.borrow().needs_layout
+    }
+
+    /// Lay out the superview (and the window), e.g. because this view has
+    /// been hidden or its content has changed size.
+    fn set_needs_superview_layout(&self) {
+        match self.superview().upgrade() {
+            Some(superview) => superview.set_needs_layout(),
+            None => self.set_needs_window_layout()
+        }
+    }
+
+    /// Lay out the window's views again before it's next drawn.
+    pub(crate) fn set_needs_window_layout(&self) {
+        if let Some(window) = self.window() {
+            window.set_needs_layout();
+        }
+    }
 }
 
 impl LayerDelegate for View {
@@ -624,6 +1023,28 @@ mod tests {
         assert_eq!(main.convert_point_to(&Point { x: 2, y: 2 }, &c), Point { x: 0, y: 1 });
     }
 
+    #[test]
+    fn test_convert_point_to_transformed() {
+        let main = View::new(Rectangle::new(0, 0, 100, 100));
+        let a = View::new(Rectangle::new(10, 10, 20, 20));
+        let b = View::new(Rectangle::new(5, 5, 5, 5));
+        main.add_subview(a.clone());
+        a.add_subview(b.clone());
+
+        // Twice the size, around its centre at (20, 20).
+        a.set_transform(Transform::scale(2.0, 2.0));
+        assert_eq!(a.transform(), Transform::scale(2.0, 2.0));
+
+        assert_eq!(a.convert_point_to(&Point::new(0, 0), &main), Point::new(0, 0));
+        assert_eq!(a.convert_point_to(&Point::new(20, 20), &main), Point::new(40, 40));
+        assert_eq!(b.convert_point_to(&Point::new(0, 0), &main), Point::new(10, 10));
+        assert_eq!(main.convert_point_to(&Point::new(10, 10), &b), Point::new(0, 0));
+
+        // Hit testing follows the transform.
+        assert_eq!(main.hit_test(&Point::new(35, 35)), Some(a.clone()));
+        assert_eq!(main.hit_test(&Point::new(12, 12)), Some(b.clone()));
+    }
+
     #[test]
     fn test_point_inside() {
         let frame = Rectangle::new(0, 0, 1000, 1000);
@@ -675,7 +1096,7 @@ mod tests {
         view.set_frame(new_frame.clone());
 
         assert_eq!(view.frame(), new_frame);
-        assert_eq!(view.bounds().size(), &Size::new(1000, 1000));
+        assert_eq!(view.bounds().size(), &Size::new(100, 100));
     }
 
     #[test]
@@ -706,6 +1127,15 @@ mod tests {
         assert_eq!(view.bounds(), Rectangle::new(10, 10, 100, 100));
     }
 
+    #[test]
+    fn test_window_when_not_in_window() {
+        let parent_view = View::new(Rectangle::new(0, 0, 100, 100));
+        let view = View::new(Rectangle::new(0, 0, 10, 10));
+        parent_view.add_subview(view.clone());
+
+        assert!(view.window().is_none());
+    }
+
     #[test]
     fn test_tag() {
         let frame = Rectangle::new(0, 0, 1000, 1000);
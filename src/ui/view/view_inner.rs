@@ -1,7 +1,8 @@
-use crate::graphics::{Layer, Rectangle};
+use crate::graphics::{Layer, Rectangle, Size, Transform};
 use crate::ui::Color;
 use crate::ui::view::{View, WeakView};
 use crate::ui::gesture::recognizer::Recognizer;
+use crate::ui::layout::{AutoresizingMask, Constraint};
 use std::rc::Rc;
 
 pub(crate) struct ViewInner {
@@ -34,10 +35,18 @@ pub(crate) struct ViewInner {
     /// https://stackoverflow.com/a/28917673/869367
     pub bounds: Rectangle<i32, u32>,
 
+    /// Scales and moves the view when it's drawn (and for hit testing),
+    /// around the centre of its frame. The frame itself doesn't change.
+    pub transform: Transform,
+
     /// The background color of the view. In its simplest form, a View is just a
     /// rectangle with a single color - this is that color.
     pub background_color: Color,
 
+    /// How opaque the view is drawn, along with its subviews, from `0.0`
+    /// (invisible) to `1.0`.
+    pub alpha: f32,
+
     /// The actual drawable canvas from the `graphics` library.
     ///
     /// Think of the View as instructions or a template for a picture (this
@@ -79,5 +88,26 @@ pub(crate) struct ViewInner {
     /// `frame.size`, and subviews outside the visible bounds are not rendered.
     /// Used by ScrollView's inner content view to avoid creating a texture the
     /// full content size.
-    pub clips_to_bounds: bool
+    pub clips_to_bounds: bool,
+
+    /// Active constraints whose first anchor belongs to this view. See
+    /// `Constraint::activate`.
+    pub constraints: Vec<Constraint>,
+
+    /// When true, constraint-based layout keeps the view at its frame
+    /// rather than moving it. Turned off when a constraint is activated for
+    /// the view.
+    pub translates_frame_into_constraints: bool,
+
+    /// How the view is resized and moved when its superview changes size.
+    pub autoresizing_mask: AutoresizingMask,
+
+    /// The size the subviews were last autoresized for. Compared against
+    /// the frame or bounds size when either changes, so resizing both only
+    /// moves the subviews once.
+    pub autoresized_size: Size<u32>,
+
+    /// Whether `Behavior::layout_subviews` needs calling before the window
+    /// is next drawn. See `View::set_needs_layout`.
+    pub needs_layout: bool
 }
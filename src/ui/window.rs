@@ -1,13 +1,39 @@
-use crate::graphics::{Context, Rectangle};
+use crate::graphics::{Context, FullscreenMode, Point, Rectangle, Size};
 use crate::ui::{View, WeakView, ViewController};
 use crate::ui::view::{Behavior, DefaultBehavior};
 use crate::ui::application::Application;
 use crate::ui::render;
+use crate::ui::layout;
 use crate::ui::Color;
+use crate::ui::Cursor;
 use crate::ui::timer::Timer;
 use crate::ui::run_loop::RunLoop;
 use std::option::Option;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+/// Receives lifecycle notifications for a `Window`. See `Window::set_delegate`.
+pub trait WindowDelegate {
+    /// Asked when the user (or `Window::perform_close`) requests the window to
+    /// close. Return `false` to keep the window open, e.g. to first ask the
+    /// user to save their changes.
+    ///
+    /// Returns `true` by default.
+    fn window_should_close(&self, _window: &Window) -> bool {
+        true
+    }
+
+    /// Called right before the window is closed and removed from the
+    /// `Application`.
+    fn window_will_close(&self, _window: &Window) {}
+
+    /// Called when the window becomes the key window. That is, the window
+    /// that receives keyboard input; usually the one the user last focused.
+    fn window_did_become_key(&self, _window: &Window) {}
+
+    /// Called when the window stops being the key window.
+    fn window_did_resign_key(&self, _window: &Window) {}
+}
 
 pub struct WindowBehavior {
     view: WeakView,
@@ -18,6 +44,16 @@ pub struct WindowBehavior {
     /// The window's first responder. Default to the window itself. Overriden
     /// by a view calling `become_first_responder`.
     first_responder: RefCell<WeakView>,
+
+    delegate: RefCell<Option<Rc<dyn WindowDelegate>>>,
+
+    /// The views currently under the mouse, from the window down to the
+    /// deepest view. See `update_hover`.
+    hovered_views: RefCell<Vec<WeakView>>,
+
+    /// Whether the views need laying out before the window is next drawn.
+    /// See `Window::layout_if_needed`.
+    needs_layout: Cell<bool>,
 }
 
 pub struct Window {
@@ -50,7 +86,10 @@ impl Window {
             super_behavior: Box::new(default_behavior),
             context: context,
             view_controller: view_controller,
-            first_responder: RefCell::new(WeakView::none())
+            first_responder: RefCell::new(WeakView::none()),
+            delegate: RefCell::new(None),
+            hovered_views: RefCell::new(Vec::new()),
+            needs_layout: Cell::new(true)
         };
 
         let view = View::new_with_behavior(Box::new(window_behavior), frame, "window");
@@ -84,17 +123,388 @@ impl Window {
     }
 
     pub fn make_key_and_visible(&self) {
-        let mut application = Application::borrow_mut();
-        application.set_key_window(&self);
+        self.make_key_window();
         self.set_hidden(false);
     }
 
+    /// Whether this window is the application's key window; the window that
+    /// receives keyboard input.
+    pub fn is_key_window(&self) -> bool {
+        let application = Application::borrow();
+        application.get_key_window().id() == Some(self.id())
+    }
+
+    /// Make this the key window, notifying the delegates of both this window
+    /// and the previous key window.
+    ///
+    /// This is called by the event loop when the OS gives the window focus.
+    pub fn make_key_window(&self) {
+        let previous_key_window = {
+            let mut application = Application::borrow_mut();
+            let previous_key_window = application.get_key_window().upgrade();
+            application.set_key_window(self);
+            previous_key_window
+        };
+
+        if let Some(previous_key_window) = previous_key_window {
+            if previous_key_window.id() == self.id() {
+                return;
+            }
+
+            Window::from_view(previous_key_window).did_resign_key();
+        }
+
+        if let Some(delegate) = self.delegate() {
+            delegate.window_did_become_key(self);
+        }
+    }
+
+    /// Stop being the key window, leaving the application without one. E.g.
+    /// when the OS focus moves to another application.
+    pub(crate) fn resign_key_window(&self) {
+        if !self.is_key_window() {
+            return;
+        }
+
+        {
+            let mut application = Application::borrow_mut();
+            application.clear_key_window();
+        }
+
+        self.did_resign_key();
+    }
+
+    fn did_resign_key(&self) {
+        if let Some(delegate) = self.delegate() {
+            delegate.window_did_resign_key(self);
+        }
+    }
+
     pub fn context(&self) -> Context {
         let behavior = self.view.behavior.borrow();
         let behavior = behavior.as_any().downcast_ref::<WindowBehavior>().expect("view is not a Window");
         behavior.context.clone()
     }
 
+    /// Set the delegate to be notified about this window's lifecycle, e.g.
+    /// when it's about to close.
+    pub fn set_delegate(&self, delegate: impl WindowDelegate + 'static) {
+        let behavior = self.view.behavior.borrow();
+        let behavior = behavior.as_any().downcast_ref::<WindowBehavior>().expect("view is not a Window");
+        behavior.delegate.replace(Some(Rc::new(delegate)));
+    }
+
+    pub(crate) fn delegate(&self) -> Option<Rc<dyn WindowDelegate>> {
+        let behavior = self.view.behavior.borrow();
+        let behavior = behavior.as_any().downcast_ref::<WindowBehavior>().expect("view is not a Window");
+        let delegate = behavior.delegate.borrow().clone();
+        delegate
+    }
+
+    /// Request the window to close, as if the user had clicked the close
+    /// button. The delegate gets the chance to refuse through
+    /// `window_should_close`.
+    ///
+    /// Returns `true` if the window was closed.
+    pub fn perform_close(&self) -> bool {
+        if let Some(delegate) = self.delegate() {
+            if !delegate.window_should_close(self) {
+                return false;
+            }
+        }
+
+        self.close();
+        true
+    }
+
+    /// Close the window, without asking the delegate.
+    ///
+    /// The window is hidden and removed from the `Application`. If it was the
+    /// last window, the application may terminate; see
+    /// `Application::set_terminates_after_last_window_closed`.
+    pub fn close(&self) {
+        if let Some(delegate) = self.delegate() {
+            delegate.window_will_close(self);
+        }
+
+        self.resign_key_window();
+        self.set_hidden(true);
+        self.context().hide();
+
+        let mut application = Application::borrow_mut();
+        application.remove_window(self);
+    }
+
+    pub fn title(&self) -> String {
+        self.context().title()
+    }
+
+    pub fn set_title(&self, title: &str) {
+        self.context().set_title(title);
+    }
+
+    /// The position of the window on screen. This is the same as the window
+    /// view's `frame().origin`.
+    pub fn position(&self) -> Point<i32> {
+        self.frame().origin
+    }
+
+    pub fn set_position(&self, position: Point<i32>) {
+        self.context().set_position(position);
+        self.update_frame_from_context();
+    }
+
+    /// The size of the window in points. This is the same as the window
+    /// view's `frame().size`.
+    pub fn size(&self) -> Size<u32> {
+        self.frame().size
+    }
+
+    /// Resize the window. The window view's frame and bounds are resized to
+    /// match.
+    ///
+    /// The size is constrained by `minimum_size` and `maximum_size`.
+    pub fn set_size(&self, size: Size<u32>) {
+        self.context().set_size(size);
+        self.update_frame_from_context();
+    }
+
+    pub fn minimum_size(&self) -> Size<u32> {
+        self.context().minimum_size()
+    }
+
+    pub fn set_minimum_size(&self, size: Size<u32>) {
+        self.context().set_minimum_size(size);
+        self.update_frame_from_context();
+    }
+
+    /// The maximum size of the window. A dimension of `0` means there is no
+    /// maximum for that dimension.
+    pub fn maximum_size(&self) -> Size<u32> {
+        self.context().maximum_size()
+    }
+
+    pub fn set_maximum_size(&self, size: Size<u32>) {
+        self.context().set_maximum_size(size);
+        self.update_frame_from_context();
+    }
+
+    /// Whether the user can resize the window. Defaults to `false`.
+    pub fn is_resizable(&self) -> bool {
+        self.context().is_resizable()
+    }
+
+    pub fn set_resizable(&self, resizable: bool) {
+        self.context().set_resizable(resizable);
+    }
+
+    pub fn fullscreen_mode(&self) -> FullscreenMode {
+        self.context().fullscreen_mode()
+    }
+
+    pub fn set_fullscreen_mode(&self, mode: FullscreenMode) {
+        self.context().set_fullscreen_mode(mode);
+        self.update_frame_from_context();
+    }
+
+    pub fn minimize(&self) {
+        self.context().minimize();
+    }
+
+    pub fn maximize(&self) {
+        self.context().maximize();
+        self.update_frame_from_context();
+    }
+
+    /// Restore the size and position of a minimized or maximized window.
+    pub fn restore(&self) {
+        self.context().restore();
+        self.update_frame_from_context();
+    }
+
+    pub fn is_minimized(&self) -> bool {
+        self.context().is_minimized()
+    }
+
+    pub fn is_maximized(&self) -> bool {
+        self.context().is_maximized()
+    }
+
+    /// Whether the window is drawn without the OS title bar and border.
+    pub fn is_borderless(&self) -> bool {
+        self.context().is_borderless()
+    }
+
+    pub fn set_borderless(&self, borderless: bool) {
+        self.context().set_borderless(borderless);
+    }
+
+    pub fn is_always_on_top(&self) -> bool {
+        self.context().is_always_on_top()
+    }
+
+    pub fn set_always_on_top(&self, always_on_top: bool) {
+        self.context().set_always_on_top(always_on_top);
+    }
+
+    /// The opacity of the whole window, from `0.0` (fully transparent) to
+    /// `1.0` (fully opaque).
+    pub fn opacity(&self) -> f32 {
+        self.context().opacity()
+    }
+
+    pub fn set_opacity(&self, opacity: f32) {
+        self.context().set_opacity(opacity);
+    }
+
+    /// Shows the cursor for whichever view is under the mouse at the given
+    /// position.
+    ///
+    /// Starting at the deepest view (see `hit_test`), each view up the
+    /// hierarchy is asked for its cursor until one is given. Defaults to
+    /// `Cursor::Arrow`.
+    pub(crate) fn update_cursor(&self, position: &Point<i32>) {
+        let mut cursor = None;
+        let mut current_view = self.hit_test(position);
+
+        while let Some(view) = current_view {
+            let point = self.convert_point_to(position, &view);
+            cursor = view.cursor_for_point(&point);
+
+            if cursor.is_some() || view.is_window() {
+                break;
+            }
+
+            current_view = view.superview().upgrade();
+        }
+
+        cursor.unwrap_or(Cursor::Arrow).activate();
+    }
+
+    /// Tracks which views are under the mouse at the given position, sending
+    /// `mouse_exited` and `mouse_entered` to views the mouse has left or
+    /// arrived at, then `mouse_moved` to the deepest one.
+    ///
+    /// Called by the event loop whenever the mouse moves. While a button is
+    /// pressed the movement is delivered as touches, so only the entered and
+    /// exited changes are sent.
+    pub(crate) fn update_hover(&self, position: &Point<i32>, is_dragging: bool) {
+        let mut hovered_views = Vec::new();
+        let mut current_view = self.hit_test(position);
+
+        while let Some(view) = current_view {
+            current_view = if view.is_window() {
+                None
+            } else {
+                view.superview().upgrade()
+            };
+            hovered_views.push(view);
+        }
+
+        hovered_views.reverse();
+
+        let previously_hovered_views = self.replace_hovered_views(&hovered_views);
+
+        for view in previously_hovered_views.iter().rev() {
+            if !hovered_views.contains(view) {
+                view.mouse_exited();
+            }
+        }
+
+        for view in hovered_views.iter() {
+            if !previously_hovered_views.contains(view) {
+                view.mouse_entered();
+            }
+        }
+
+        if is_dragging {
+            return;
+        }
+
+        if let Some(view) = hovered_views.last() {
+            let point = self.convert_point_to(position, view);
+            view.mouse_moved(&point);
+        }
+    }
+
+    /// Sends `mouse_exited` to every view under the mouse. Called by the
+    /// event loop when the mouse leaves the window.
+    pub(crate) fn clear_hover(&self) {
+        let previously_hovered_views = self.replace_hovered_views(&[]);
+
+        for view in previously_hovered_views.iter().rev() {
+            view.mouse_exited();
+        }
+    }
+
+    fn replace_hovered_views(&self, views: &[View]) -> Vec<View> {
+        let behavior = self.view.behavior.borrow();
+        let behavior = behavior.as_any().downcast_ref::<WindowBehavior>().expect("view is not a Window");
+        let weak_views = views.iter().map(|view| view.downgrade()).collect();
+        let previous = behavior.hovered_views.replace(weak_views);
+        previous.iter().filter_map(|view| view.upgrade()).collect()
+    }
+
+    /// Brings the window view's frame back in line with the OS window.
+    ///
+    /// Called after changing the window through the `Context`, and by the
+    /// event loop when the OS moves or resizes the window.
+    pub(crate) fn update_frame_from_context(&self) {
+        let context = self.context();
+        context.update_size();
+
+        let frame = Rectangle {
+            origin: context.position(),
+            size: context.size()
+        };
+
+        if self.frame() == frame {
+            return;
+        }
+
+        self.set_frame(frame);
+        self.set_needs_layout();
+    }
+
+    /// Lay out the window's views before it's next drawn. E.g. after a
+    /// constraint changes or the window is resized.
+    pub(crate) fn set_needs_layout(&self) {
+        let needed = {
+            let behavior = self.view.behavior.borrow();
+            let behavior = behavior.as_any().downcast_ref::<WindowBehavior>().expect("view is not a Window");
+            behavior.needs_layout.replace(true)
+        };
+
+        if !needed {
+            self.set_needs_display();
+        }
+    }
+
+    /// Solve the constraints of the views in the window and move them to
+    /// match, then lay out the views that need it (see
+    /// `View::set_needs_layout`), if anything has changed since the last
+    /// time. Called by `render::window_display` before drawing, so layout
+    /// happens at most once per frame.
+    pub(crate) fn layout_if_needed(&self) {
+        let needed = {
+            let behavior = self.view.behavior.borrow();
+            let behavior = behavior.as_any().downcast_ref::<WindowBehavior>().expect("view is not a Window");
+            behavior.needs_layout.get()
+        };
+
+        if !needed {
+            return;
+        }
+
+        // Laying out keeps going until it's done, so what it changes doesn't
+        // need another pass. If it gave up, the rest is left for next time.
+        if layout::layout(&self.view) {
+            let behavior = self.view.behavior.borrow();
+            let behavior = behavior.as_any().downcast_ref::<WindowBehavior>().expect("view is not a Window");
+            behavior.needs_layout.set(false);
+        }
+    }
+
     /// Returns the window's first responder.
     ///
     /// If there is no first responder, the window itself is returned.